@@ -0,0 +1,315 @@
+// Antenna gain, half-power beamwidth and pointing loss
+//
+// Three common spacecraft and ground station antennas, with wavelength
+// lambda = c / f:
+//   - parabolic dish: G = eta (pi D / lambda)^2, HPBW = 70 deg lambda / D
+//   - axial-mode helix (Kraus): G = 15 (C / lambda)^2 n S / lambda,
+//     HPBW = 52 deg / ((C / lambda) sqrt(n S / lambda)), C = pi D
+//   - planar patch array: G = eta 4 pi A / lambda^2 over the array area A,
+//     HPBW = 0.886 lambda / (N d) rad in the plane of N elements at spacing d
+// Mispointing by theta costs 12 (theta / HPBW)^2 dB, the Gaussian main-lobe
+// approximation, which holds out to about one beamwidth off boresight.
+// Inputs are in SI units (m, Hz, rad); gains are returned in dBi.
+use crate::CalculationError;
+use crate::link_budget::to_db;
+use crate::path_loss::SPEED_OF_LIGHT;
+use std::f64::consts::PI;
+use std::fmt;
+
+// Circumference range, in wavelengths, over which a helix radiates in axial mode
+const HELIX_AXIAL_MODE: (f64, f64) = (0.75, 1.33);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Antenna {
+    ParabolicDish { diameter: f64, efficiency: f64 },
+    // `spacing` is the axial distance between turns
+    Helix { diameter: f64, turns: f64, spacing: f64 },
+    // `rows` by `columns` elements on a square grid of pitch `spacing`
+    PatchArray { rows: u32, columns: u32, spacing: f64, efficiency: f64 },
+}
+
+// Gain of one end of a link: entered directly with the pointing loss it
+// suffers, or computed from an antenna model at the link frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AntennaGain {
+    Given { gain_dbi: f64, pointing_loss_db: f64 },
+    Modeled { antenna: Antenna, pointing_error: f64 }, // rad
+}
+
+impl AntennaGain {
+    // Peak gain in dBi and pointing loss in dB at `frequency` in Hz
+    pub fn resolve(&self, frequency: f64) -> Result<(f64, f64), CalculationError> {
+        match *self {
+            AntennaGain::Given { gain_dbi, pointing_loss_db } => {
+                if pointing_loss_db < 0.0 {
+                    return Err(CalculationError::NegativeValue);
+                }
+                Ok((gain_dbi, pointing_loss_db))
+            }
+            AntennaGain::Modeled { antenna, pointing_error } => {
+                let performance = antenna.performance(frequency, pointing_error)?;
+                Ok((performance.gain_dbi, performance.pointing_loss_db))
+            }
+        }
+    }
+}
+
+// Number of array elements from an entered value, which must be a whole
+// number of at least one
+pub fn element_count(value: f64) -> Result<u32, CalculationError> {
+    if value.fract() != 0.0 || !(1.0..=f64::from(u32::MAX)).contains(&value) {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(value as u32)
+}
+
+// Gain and beamwidth of an antenna at one frequency and pointing error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AntennaPerformance {
+    pub gain_dbi: f64,
+    pub beamwidth: f64,      // rad, half-power, narrowest plane
+    pub pointing_error: f64, // rad
+    pub pointing_loss_db: f64,
+}
+
+impl AntennaPerformance {
+    // Gain in the direction of the other end of the link
+    pub fn effective_gain_dbi(&self) -> f64 {
+        self.gain_dbi - self.pointing_loss_db
+    }
+}
+
+impl fmt::Display for AntennaPerformance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Peak gain:            {:>10.2} dBi", self.gain_dbi)?;
+        writeln!(f, "Half-power beamwidth: {:>10.3} deg", self.beamwidth.to_degrees())?;
+        writeln!(f, "Pointing error:       {:>10.3} deg", self.pointing_error.to_degrees())?;
+        writeln!(f, "Pointing loss:        {:>10.2} dB", self.pointing_loss_db)?;
+        write!(f, "Effective gain:       {:>10.2} dBi", self.effective_gain_dbi())
+    }
+}
+
+impl Antenna {
+    fn wavelength(frequency: f64) -> Result<f64, CalculationError> {
+        if frequency <= 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(SPEED_OF_LIGHT / frequency)
+    }
+
+    fn check(&self) -> Result<(), CalculationError> {
+        let efficiency_ok = |eta: f64| eta > 0.0 && eta <= 1.0;
+        let valid = match *self {
+            Antenna::ParabolicDish { diameter, efficiency } => diameter > 0.0 && efficiency_ok(efficiency),
+            Antenna::Helix { diameter, turns, spacing } => diameter > 0.0 && turns > 0.0 && spacing > 0.0,
+            Antenna::PatchArray { rows, columns, spacing, efficiency } => {
+                rows > 0 && columns > 0 && spacing > 0.0 && efficiency_ok(efficiency)
+            }
+        };
+        if valid { Ok(()) } else { Err(CalculationError::InvalidInput) }
+    }
+
+    // Peak (boresight) gain as a power ratio
+    pub fn gain(&self, frequency: f64) -> Result<f64, CalculationError> {
+        self.check()?;
+        let lambda = Antenna::wavelength(frequency)?;
+        match *self {
+            Antenna::ParabolicDish { diameter, efficiency } => Ok(efficiency * (PI * diameter / lambda).powi(2)),
+            Antenna::Helix { diameter, turns, spacing } => {
+                let circumference = helix_circumference(diameter, lambda)?;
+                Ok(15.0 * circumference.powi(2) * turns * spacing / lambda)
+            }
+            Antenna::PatchArray { rows, columns, spacing, efficiency } => {
+                // Past one wavelength the array forms grating lobes
+                if spacing >= lambda {
+                    return Err(CalculationError::OutOfRange);
+                }
+                let area = f64::from(rows) * f64::from(columns) * spacing * spacing;
+                Ok(efficiency * 4.0 * PI * area / (lambda * lambda))
+            }
+        }
+    }
+
+    pub fn gain_dbi(&self, frequency: f64) -> Result<f64, CalculationError> {
+        Ok(to_db(self.gain(frequency)?))
+    }
+
+    // Half-power beamwidth, rad; for a rectangular patch array the narrower
+    // beam along its longer side, so pointing losses err on the safe side
+    pub fn beamwidth(&self, frequency: f64) -> Result<f64, CalculationError> {
+        self.check()?;
+        let lambda = Antenna::wavelength(frequency)?;
+        match *self {
+            Antenna::ParabolicDish { diameter, .. } => Ok(70.0_f64.to_radians() * lambda / diameter),
+            Antenna::Helix { diameter, turns, spacing } => {
+                let circumference = helix_circumference(diameter, lambda)?;
+                Ok(52.0_f64.to_radians() / (circumference * (turns * spacing / lambda).sqrt()))
+            }
+            Antenna::PatchArray { rows, columns, spacing, .. } => {
+                Ok(0.886 * lambda / (f64::from(rows.max(columns)) * spacing))
+            }
+        }
+    }
+
+    pub fn pointing_loss_db(&self, frequency: f64, pointing_error: f64) -> Result<f64, CalculationError> {
+        pointing_loss_db(pointing_error, self.beamwidth(frequency)?)
+    }
+
+    pub fn performance(&self, frequency: f64, pointing_error: f64) -> Result<AntennaPerformance, CalculationError> {
+        let beamwidth = self.beamwidth(frequency)?;
+        Ok(AntennaPerformance {
+            gain_dbi: self.gain_dbi(frequency)?,
+            beamwidth,
+            pointing_error,
+            pointing_loss_db: pointing_loss_db(pointing_error, beamwidth)?,
+        })
+    }
+}
+
+impl fmt::Display for Antenna {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Antenna::ParabolicDish { diameter, efficiency } => {
+                write!(f, "Parabolic dish {:.2} m, efficiency {:.0}%", diameter, efficiency * 100.0)
+            }
+            Antenna::Helix { diameter, turns, spacing } => write!(
+                f,
+                "Helix {:.1} mm diameter, {} turns at {:.1} mm",
+                diameter * 1000.0,
+                turns,
+                spacing * 1000.0
+            ),
+            Antenna::PatchArray { rows, columns, spacing, efficiency } => write!(
+                f,
+                "Patch array {}x{} at {:.1} mm, efficiency {:.0}%",
+                rows,
+                columns,
+                spacing * 1000.0,
+                efficiency * 100.0
+            ),
+        }
+    }
+}
+
+// Helix circumference in wavelengths, within the axial-mode range
+fn helix_circumference(diameter: f64, lambda: f64) -> Result<f64, CalculationError> {
+    let circumference = PI * diameter / lambda;
+    if circumference < HELIX_AXIAL_MODE.0 || circumference > HELIX_AXIAL_MODE.1 {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(circumference)
+}
+
+// Loss in dB for a pointing error and half-power beamwidth, both in rad
+pub fn pointing_loss_db(pointing_error: f64, beamwidth: f64) -> Result<f64, CalculationError> {
+    if pointing_error < 0.0 {
+        return Err(CalculationError::NegativeValue);
+    }
+    if beamwidth <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    // Beyond one beamwidth the target is outside the main lobe
+    if pointing_error > beamwidth {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(12.0 * (pointing_error / beamwidth).powi(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GHZ: f64 = 1e9;
+
+    fn wavelength(frequency: f64) -> f64 {
+        SPEED_OF_LIGHT / frequency
+    }
+
+    #[test]
+    fn dish_gain_and_beamwidth() {
+        let dish = Antenna::ParabolicDish { diameter: 1.0, efficiency: 0.55 };
+        // 0.55 (pi / 0.02998)^2 = 6040, 37.81 dBi
+        let gain = dish.gain_dbi(10.0 * GHZ).unwrap();
+        assert!((gain - 37.81).abs() < 0.01, "{gain} dBi");
+        let beamwidth = dish.beamwidth(10.0 * GHZ).unwrap().to_degrees();
+        assert!((beamwidth - 2.0985).abs() < 1e-3, "{beamwidth} deg");
+        // Doubling the frequency quadruples the gain
+        assert!((dish.gain_dbi(20.0 * GHZ).unwrap() - gain - to_db(4.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn kraus_helix() {
+        // One wavelength around, quarter-wavelength turns, ten turns
+        let lambda = wavelength(1.6 * GHZ);
+        let helix = Antenna::Helix { diameter: lambda / PI, turns: 10.0, spacing: lambda / 4.0 };
+        assert!((helix.gain(1.6 * GHZ).unwrap() - 37.5).abs() < 1e-9);
+        let beamwidth = helix.beamwidth(1.6 * GHZ).unwrap().to_degrees();
+        assert!((beamwidth - 52.0 / 2.5_f64.sqrt()).abs() < 1e-9);
+        // Outside the axial mode the helix radiates sideways
+        assert_eq!(helix.gain(3.2 * GHZ), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn half_wavelength_patch_array() {
+        let lambda = wavelength(2.2 * GHZ);
+        let array = Antenna::PatchArray { rows: 4, columns: 4, spacing: lambda / 2.0, efficiency: 1.0 };
+        // 16 elements of lambda^2 / 4: G = 4 pi 4 lambda^2 / lambda^2 = 16 pi
+        assert!((array.gain(2.2 * GHZ).unwrap() - 16.0 * PI).abs() < 1e-9);
+        assert!((array.beamwidth(2.2 * GHZ).unwrap() - 0.443).abs() < 1e-12);
+        // The longer side sets the beamwidth
+        let strip = Antenna::PatchArray { rows: 1, columns: 8, spacing: lambda / 2.0, efficiency: 1.0 };
+        assert!((strip.beamwidth(2.2 * GHZ).unwrap() - 0.886 / 4.0).abs() < 1e-12);
+        let sparse = Antenna::PatchArray { rows: 4, columns: 4, spacing: lambda, efficiency: 1.0 };
+        assert_eq!(sparse.gain(2.2 * GHZ), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn pointing_loss_follows_the_gaussian_lobe() {
+        let beamwidth = 2.0_f64.to_radians();
+        assert_eq!(pointing_loss_db(0.0, beamwidth), Ok(0.0));
+        // Half a beamwidth off boresight is the half-power point
+        assert!((pointing_loss_db(beamwidth / 2.0, beamwidth).unwrap() - 3.0).abs() < 1e-12);
+        assert_eq!(pointing_loss_db(beamwidth * 1.1, beamwidth), Err(CalculationError::OutOfRange));
+        assert_eq!(pointing_loss_db(-0.1, beamwidth), Err(CalculationError::NegativeValue));
+
+        let dish = Antenna::ParabolicDish { diameter: 1.0, efficiency: 0.55 };
+        let performance = dish.performance(10.0 * GHZ, 0.5_f64.to_radians()).unwrap();
+        assert!((performance.effective_gain_dbi() - (performance.gain_dbi - performance.pointing_loss_db)).abs() < 1e-12);
+        assert!(performance.pointing_loss_db > 0.0);
+    }
+
+    #[test]
+    fn rejects_impossible_antennas() {
+        let invalid = [
+            Antenna::ParabolicDish { diameter: 0.0, efficiency: 0.5 },
+            Antenna::ParabolicDish { diameter: 1.0, efficiency: 1.2 },
+            Antenna::Helix { diameter: 0.06, turns: -1.0, spacing: 0.05 },
+            Antenna::PatchArray { rows: 0, columns: 4, spacing: 0.05, efficiency: 0.8 },
+        ];
+        for antenna in invalid {
+            assert_eq!(antenna.gain(2.2 * GHZ), Err(CalculationError::InvalidInput), "{antenna}");
+        }
+        let dish = Antenna::ParabolicDish { diameter: 1.0, efficiency: 0.5 };
+        assert_eq!(dish.gain(0.0), Err(CalculationError::InvalidInput));
+    }
+
+    #[test]
+    fn element_counts_are_whole() {
+        assert_eq!(element_count(4.0), Ok(4));
+        assert_eq!(element_count(2.5), Err(CalculationError::InvalidInput));
+        assert_eq!(element_count(0.0), Err(CalculationError::InvalidInput));
+        assert_eq!(element_count(f64::NAN), Err(CalculationError::InvalidInput));
+        assert_eq!(element_count(1e10), Err(CalculationError::InvalidInput));
+    }
+
+    #[test]
+    fn given_gains_pass_through() {
+        let given = AntennaGain::Given { gain_dbi: 6.0, pointing_loss_db: 0.5 };
+        assert_eq!(given.resolve(2.2 * GHZ), Ok((6.0, 0.5)));
+        let negative = AntennaGain::Given { gain_dbi: 6.0, pointing_loss_db: -0.5 };
+        assert_eq!(negative.resolve(2.2 * GHZ), Err(CalculationError::NegativeValue));
+        let dish = Antenna::ParabolicDish { diameter: 1.0, efficiency: 0.55 };
+        let modeled = AntennaGain::Modeled { antenna: dish, pointing_error: 0.0 };
+        assert_eq!(modeled.resolve(10.0 * GHZ), Ok((dish.gain_dbi(10.0 * GHZ).unwrap(), 0.0)));
+    }
+}
@@ -0,0 +1,230 @@
+// Earth upper-atmosphere density models
+//
+// Three models of increasing fidelity for drag and lifetime analysis:
+//
+// - Exponential: piecewise exponential fit to CIRA-72 (Vallado, table 8-4),
+//   static and independent of solar activity.
+// - Harris-Priester: tabulated minimum (night) and maximum (diurnal bulge)
+//   densities for mean solar activity (Montenbruck & Gill, table 3.8),
+//   weighted by where the satellite sits relative to the bulge.
+// - Solar flux: the thermospheric model used for decay prediction by the
+//   Australian Space Weather Services, with the exospheric temperature
+//   driven by the F10.7 solar flux and the Ap geomagnetic index. Calibrated
+//   between 180 and 500 km; outside that range the Harris-Priester profile
+//   is scaled to meet it at the nearest edge.
+//
+// Altitudes are in m and densities in kg/m^3.
+use std::fmt;
+
+// Base altitude (km), base density (kg/m^3), scale height (km)
+const EXPONENTIAL_TABLE: [(f64, f64, f64); 28] = [
+    (0.0, 1.225, 7.249),
+    (25.0, 3.899e-2, 6.349),
+    (30.0, 1.774e-2, 6.682),
+    (40.0, 3.972e-3, 7.554),
+    (50.0, 1.057e-3, 8.382),
+    (60.0, 3.206e-4, 7.714),
+    (70.0, 8.770e-5, 6.549),
+    (80.0, 1.905e-5, 5.799),
+    (90.0, 3.396e-6, 5.382),
+    (100.0, 5.297e-7, 5.877),
+    (110.0, 9.661e-8, 7.263),
+    (120.0, 2.438e-8, 9.473),
+    (130.0, 8.484e-9, 12.636),
+    (140.0, 3.845e-9, 16.149),
+    (150.0, 2.070e-9, 22.523),
+    (180.0, 5.464e-10, 29.740),
+    (200.0, 2.789e-10, 37.105),
+    (250.0, 7.248e-11, 45.546),
+    (300.0, 2.418e-11, 53.628),
+    (350.0, 9.518e-12, 53.298),
+    (400.0, 3.725e-12, 58.515),
+    (450.0, 1.585e-12, 60.828),
+    (500.0, 6.967e-13, 63.822),
+    (600.0, 1.454e-13, 71.835),
+    (700.0, 3.614e-14, 88.667),
+    (800.0, 1.170e-14, 124.64),
+    (900.0, 5.245e-15, 181.05),
+    (1000.0, 3.019e-15, 268.00),
+];
+
+// Altitude (km), minimum and maximum density (g/km^3 = 1e-12 kg/m^3)
+const HARRIS_PRIESTER_TABLE: [(f64, f64, f64); 50] = [
+    (100.0, 497400.0, 497400.0),
+    (120.0, 24900.0, 24900.0),
+    (130.0, 8377.0, 8710.0),
+    (140.0, 3899.0, 4059.0),
+    (150.0, 2122.0, 2215.0),
+    (160.0, 1263.0, 1344.0),
+    (170.0, 800.8, 875.8),
+    (180.0, 528.3, 601.0),
+    (190.0, 361.7, 429.7),
+    (200.0, 255.7, 316.2),
+    (210.0, 183.9, 239.6),
+    (220.0, 134.1, 185.3),
+    (230.0, 99.49, 145.5),
+    (240.0, 74.88, 115.7),
+    (250.0, 57.09, 93.08),
+    (260.0, 44.03, 75.55),
+    (270.0, 34.30, 61.82),
+    (280.0, 26.97, 50.95),
+    (290.0, 21.39, 42.26),
+    (300.0, 17.08, 35.26),
+    (320.0, 10.99, 25.11),
+    (340.0, 7.214, 18.19),
+    (360.0, 4.824, 13.37),
+    (380.0, 3.274, 9.955),
+    (400.0, 2.249, 7.492),
+    (420.0, 1.558, 5.684),
+    (440.0, 1.091, 4.355),
+    (460.0, 0.7701, 3.362),
+    (480.0, 0.5474, 2.612),
+    (500.0, 0.3916, 2.042),
+    (520.0, 0.2819, 1.605),
+    (540.0, 0.2042, 1.267),
+    (560.0, 0.1488, 1.005),
+    (580.0, 0.1092, 0.7997),
+    (600.0, 0.08070, 0.6390),
+    (620.0, 0.06012, 0.5123),
+    (640.0, 0.04519, 0.4121),
+    (660.0, 0.03430, 0.3325),
+    (680.0, 0.02632, 0.2691),
+    (700.0, 0.02043, 0.2185),
+    (720.0, 0.01607, 0.1779),
+    (740.0, 0.01281, 0.1452),
+    (760.0, 0.01036, 0.1190),
+    (780.0, 0.008496, 0.09776),
+    (800.0, 0.007069, 0.08059),
+    (840.0, 0.004680, 0.05741),
+    (880.0, 0.003200, 0.04210),
+    (920.0, 0.002210, 0.03130),
+    (960.0, 0.001560, 0.02360),
+    (1000.0, 0.001150, 0.01810),
+];
+
+// Altitude range of the solar flux model, km
+pub const SOLAR_FLUX_RANGE: (f64, f64) = (180.0, 500.0);
+
+// Orbit average of cos^2(psi/2), the Harris-Priester bulge weight for a
+// low-inclination orbit that sweeps through day and night
+pub const MEAN_BULGE_WEIGHT: f64 = 0.5;
+
+// Density from the piecewise exponential model. Above 1000 km the last
+// scale height is extended; below sea level the sea-level density is used.
+pub fn exponential_density(altitude: f64) -> f64 {
+    let h = (altitude / 1000.0).max(0.0);
+    let &(base, density, scale_height) =
+        EXPONENTIAL_TABLE.iter().rev().find(|(base, _, _)| h >= *base).unwrap_or(&EXPONENTIAL_TABLE[0]);
+    density * (-(h - base) / scale_height).exp()
+}
+
+// Harris-Priester density. `bulge_weight` is cos^n(psi/2), where psi is the
+// angle between the satellite and the apex of the diurnal bulge: 0 gives
+// the night-side minimum, 1 the bulge maximum. Outside 100-1000 km the
+// exponential model takes over below and the last layer is extended above.
+pub fn harris_priester_density(altitude: f64, bulge_weight: f64) -> f64 {
+    let h = altitude / 1000.0;
+    if h < HARRIS_PRIESTER_TABLE[0].0 {
+        return exponential_density(altitude);
+    }
+    let i = HARRIS_PRIESTER_TABLE
+        .windows(2)
+        .position(|w| h < w[1].0)
+        .unwrap_or(HARRIS_PRIESTER_TABLE.len() - 2);
+    let (h0, min0, max0) = HARRIS_PRIESTER_TABLE[i];
+    let (h1, min1, max1) = HARRIS_PRIESTER_TABLE[i + 1];
+
+    // Exponential interpolation between the layers, as in the original model
+    let layer = |lower: f64, upper: f64| {
+        let scale_height = (h0 - h1) / (upper / lower).ln();
+        lower * ((h0 - h) / scale_height).exp()
+    };
+    let minimum = layer(min0, min1);
+    let maximum = layer(max0, max1);
+    (minimum + (maximum - minimum) * bulge_weight.clamp(0.0, 1.0)) * 1e-12
+}
+
+// Density from the solar flux model: exospheric temperature
+// T = 900 + 2.5 (F10.7 - 70) + 1.5 Ap (K), molecular mass
+// m = 27 - 0.012 (h - 200), scale height T / m (km). The molecular mass fit
+// runs out above the calibrated range (it reaches zero at 2450 km and the
+// density grows with altitude well before that), so outside 180-500 km the
+// mean Harris-Priester profile is scaled to match the model at the edge.
+pub fn solar_flux_density(altitude: f64, solar_flux: f64, ap: f64) -> f64 {
+    let (lowest, highest) = SOLAR_FLUX_RANGE;
+    let h = altitude / 1000.0;
+    let edge = h.clamp(lowest, highest);
+    let temperature = 900.0 + 2.5 * (solar_flux - 70.0) + 1.5 * ap;
+    let molecular_mass = 27.0 - 0.012 * (edge - 200.0);
+    let scale_height = temperature / molecular_mass;
+    let density = 6e-10 * (-(edge - 175.0) / scale_height).exp();
+    if h == edge {
+        return density;
+    }
+    let profile = |h: f64| harris_priester_density(h * 1000.0, MEAN_BULGE_WEIGHT);
+    density * profile(h) / profile(edge)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DensityModel {
+    Exponential,
+    // Orbit-averaged between the night minimum and the bulge maximum
+    HarrisPriester,
+    // F10.7 in solar flux units (1e-22 W/m^2/Hz) and the daily Ap index
+    SolarFlux { solar_flux: f64, ap: f64 },
+}
+
+impl DensityModel {
+    pub fn density(&self, altitude: f64) -> f64 {
+        match *self {
+            DensityModel::Exponential => exponential_density(altitude),
+            DensityModel::HarrisPriester => harris_priester_density(altitude, MEAN_BULGE_WEIGHT),
+            DensityModel::SolarFlux { solar_flux, ap } => solar_flux_density(altitude, solar_flux, ap),
+        }
+    }
+}
+
+impl fmt::Display for DensityModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DensityModel::Exponential => write!(f, "exponential (CIRA-72)"),
+            DensityModel::HarrisPriester => write!(f, "Harris-Priester (orbit-averaged)"),
+            DensityModel::SolarFlux { solar_flux, ap } => {
+                write!(f, "solar flux (F10.7 = {:.0} sfu, Ap = {:.0})", solar_flux, ap)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solar_flux_density_is_continuous_at_the_range_edges() {
+        for edge in [SOLAR_FLUX_RANGE.0, SOLAR_FLUX_RANGE.1] {
+            let inside = solar_flux_density(edge * 1000.0, 150.0, 15.0);
+            let below = solar_flux_density(edge * 1000.0 - 1e-3, 150.0, 15.0);
+            let above = solar_flux_density(edge * 1000.0 + 1e-3, 150.0, 15.0);
+            assert!((below / inside - 1.0).abs() < 1e-6 && (above / inside - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn solar_flux_density_falls_with_altitude() {
+        let mut previous = f64::INFINITY;
+        for km in (100..=36000).step_by(50) {
+            let density = solar_flux_density(km as f64 * 1000.0, 150.0, 15.0);
+            assert!(density.is_finite() && density >= 0.0 && density <= previous, "{} km: {}", km, density);
+            previous = density;
+        }
+    }
+
+    #[test]
+    fn exponential_density_matches_the_table() {
+        assert!((exponential_density(0.0) - 1.225).abs() < 1e-12);
+        assert!((exponential_density(400e3) - 3.725e-12).abs() < 1e-24);
+        // Half way up a layer the density falls by exp(-25 / 53.628)
+        assert!((exponential_density(325e3) / 2.418e-11 - (-25.0f64 / 53.628).exp()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,614 @@
+// Batch evaluation of calculation files
+//
+// A trade study is a file with one calculation per row. JSON input is an
+// array of objects, or one object per line (JSON Lines); CSV input starts
+// with a header row. Every row names its calculation in a `calculation`
+// field, by command name ("hohmann") or `SpaceCalculation` variant
+// ("Hohmann"), and gives its parameters under the command-line flag names.
+// An optional `id` is copied to the results:
+//
+//     [{"id": "leo-geo", "calculation": "hohmann", "r1": 6678, "r2": 42164}]
+//
+//     id,calculation,r1,r2,body
+//     leo-geo,hohmann,6678,42164,earth
+//
+// Empty CSV cells and JSON nulls count as absent, so one wide CSV can mix
+// calculations. Each row is evaluated on its own: a bad row gets an error in
+// its result and the rest of the batch carries on.
+use crate::cli::{CommandSpec, build_calculation, find_command};
+use crate::escape::{csv_field, json_number, json_string};
+use crate::{BodyCatalog, CalculationResult, calculate};
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    JsonLines,
+    Csv,
+}
+
+impl InputFormat {
+    // CSV for a .csv extension, JSON otherwise
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        if has_extension(path.as_ref(), "csv") { InputFormat::Csv } else { InputFormat::Json }
+    }
+}
+
+impl OutputFormat {
+    // CSV for a .csv extension, JSON Lines otherwise
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        if has_extension(path.as_ref(), "csv") { OutputFormat::Csv } else { OutputFormat::JsonLines }
+    }
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+// Errors that stop the whole batch: the file cannot be read or its overall
+// structure is broken. Problems within a row end up in that row's result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchError {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Io(message) => write!(f, "could not read batch file: {}", message),
+            BatchError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+// One input row: its 1-based position in the batch and its fields, with
+// keys lowercased and '_' turned into '-'. `fields` holds the problem when
+// the row itself is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRow {
+    pub row: usize,
+    pub fields: Result<HashMap<String, String>, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    pub row: usize,
+    pub id: Option<String>,
+    pub calculation: Option<String>,
+    pub outcome: Result<CalculationResult, String>,
+}
+
+impl BatchResult {
+    pub fn is_ok(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+pub fn parse_batch(text: &str, format: InputFormat) -> Result<Vec<BatchRow>, BatchError> {
+    match format {
+        InputFormat::Json => parse_json_rows(text),
+        InputFormat::Csv => parse_csv_rows(text),
+    }
+}
+
+pub fn load_batch_file<P: AsRef<Path>>(path: P) -> Result<Vec<BatchRow>, BatchError> {
+    let text = fs::read_to_string(&path).map_err(|e| BatchError::Io(e.to_string()))?;
+    parse_batch(&text, InputFormat::from_path(path))
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+pub fn evaluate_row(row: &BatchRow, catalog: &BodyCatalog) -> BatchResult {
+    let fields = match &row.fields {
+        Ok(fields) => fields,
+        Err(message) => return BatchResult { row: row.row, id: None, calculation: None, outcome: Err(message.clone()) },
+    };
+    let id = fields.get("id").cloned();
+    let name = fields.get("calculation").map(|n| n.trim().to_string());
+    let result = |calculation: Option<String>, outcome| BatchResult { row: row.row, id: id.clone(), calculation, outcome };
+
+    let Some(name) = name.filter(|n| !n.is_empty()) else {
+        return result(None, Err("missing calculation".to_string()));
+    };
+    let Some(spec) = find_command(&name) else {
+        return result(Some(name.clone()), Err(format!("unknown calculation '{}'", name)));
+    };
+    let calculation = Some(spec.name.to_string());
+    if let Some(key) = fields.keys().filter(|k| !accepts(spec, k)).min() {
+        return result(calculation, Err(format!("'{}' does not take {}", spec.name, key)));
+    }
+
+    let values: HashMap<&str, &str> = fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let outcome = build_calculation(spec, &values, catalog)
+        .map_err(|e| e.to_string())
+        .and_then(|c| calculate(c).map_err(|e| e.to_string()));
+    result(calculation, outcome)
+}
+
+fn accepts(spec: &CommandSpec, key: &str) -> bool {
+    matches!(key, "id" | "calculation")
+        || (spec.takes_body && key == "body")
+        || spec.flags.iter().any(|(flag, _)| *flag == key)
+}
+
+// Evaluate every row; never stops at a failing row
+pub fn run_batch(rows: &[BatchRow], catalog: &BodyCatalog) -> Vec<BatchResult> {
+    rows.iter().map(|row| evaluate_row(row, catalog)).collect()
+}
+
+pub fn results_to_jsonl(results: &[BatchResult]) -> String {
+    let mut text = String::new();
+    for r in results {
+        let (label, value, unit, error) = match &r.outcome {
+            Ok(c) => (json_string(c.label), json_number(c.value()), json_string(c.unit().symbol()), "null".to_string()),
+            Err(e) => ("null".to_string(), "null".to_string(), "null".to_string(), json_string(e)),
+        };
+        let _ = writeln!(
+            text,
+            "{{\"row\":{},\"id\":{},\"calculation\":{},\"label\":{},\"value\":{},\"unit\":{},\"error\":{}}}",
+            r.row,
+            r.id.as_deref().map_or("null".to_string(), json_string),
+            r.calculation.as_deref().map_or("null".to_string(), json_string),
+            label,
+            value,
+            unit,
+            error
+        );
+    }
+    text
+}
+
+pub fn results_to_csv(results: &[BatchResult]) -> String {
+    let mut text = String::from("row,id,calculation,label,value,unit,error\n");
+    for r in results {
+        let (label, value, unit, error) = match &r.outcome {
+            Ok(c) => (c.label, c.value().to_string(), c.unit().symbol(), ""),
+            Err(e) => ("", String::new(), "", e.as_str()),
+        };
+        let _ = writeln!(
+            text,
+            "{},{},{},{},{},{},{}",
+            r.row,
+            csv_field(r.id.as_deref().unwrap_or("")),
+            csv_field(r.calculation.as_deref().unwrap_or("")),
+            csv_field(label),
+            value,
+            unit,
+            csv_field(error)
+        );
+    }
+    text
+}
+
+pub fn format_results(results: &[BatchResult], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::JsonLines => results_to_jsonl(results),
+        OutputFormat::Csv => results_to_csv(results),
+    }
+}
+
+// Write the results in the format given by the file extension
+pub fn write_results<P: AsRef<Path>>(results: &[BatchResult], path: P) -> std::io::Result<()> {
+    fs::write(&path, format_results(results, OutputFormat::from_path(&path)))
+}
+
+// Split one CSV record, honouring double-quoted fields with "" escapes
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            (',', false) => fields.push(std::mem::take(&mut field).trim().to_string()),
+            (c, _) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+fn parse_csv_rows(text: &str) -> Result<Vec<BatchRow>, BatchError> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('#'));
+    let Some((header_index, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = split_csv_line(header)
+        .map_err(|message| BatchError::Parse { line: header_index + 1, message })?
+        .iter()
+        .map(|k| normalize_key(k))
+        .collect();
+    if !header.iter().any(|k| k == "calculation") {
+        return Err(BatchError::Parse { line: header_index + 1, message: "header has no 'calculation' column".to_string() });
+    }
+
+    let rows = lines
+        .enumerate()
+        .map(|(i, (line_index, line))| {
+            let fields = split_csv_line(line).and_then(|values| {
+                if values.len() > header.len() {
+                    return Err(format!("line {}: {} fields for {} columns", line_index + 1, values.len(), header.len()));
+                }
+                Ok(header
+                    .iter()
+                    .zip(values)
+                    .filter(|(_, v)| !v.is_empty())
+                    .map(|(k, v)| (k.clone(), v))
+                    .collect())
+            });
+            BatchRow { row: i + 1, fields }
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn parse_json_rows(text: &str) -> Result<Vec<BatchRow>, BatchError> {
+    // A document that starts with '[' is one array; anything else is read as
+    // JSON Lines, where a broken line only fails its own row
+    if text.trim_start().starts_with('[') {
+        let mut parser = JsonParser::new(text);
+        let document = parser.document();
+        let value = document.map_err(|message| BatchError::Parse { line: parser.line, message })?;
+        let JsonValue::Array(items) = value else { unreachable!("document starts with '['") };
+        return Ok(items.into_iter().enumerate().map(|(i, item)| BatchRow { row: i + 1, fields: json_fields(item) }).collect());
+    }
+
+    let rows = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .enumerate()
+        .map(|(i, (line_index, line))| {
+            let fields = JsonParser::new(line)
+                .document()
+                .map_err(|message| format!("line {}: {}", line_index + 1, message))
+                .and_then(json_fields);
+            BatchRow { row: i + 1, fields }
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn json_fields(value: JsonValue) -> Result<HashMap<String, String>, String> {
+    let JsonValue::Object(members) = value else {
+        return Err("row is not a JSON object".to_string());
+    };
+    let mut fields = HashMap::new();
+    for (key, value) in members {
+        let text = match value {
+            JsonValue::Null => continue,
+            JsonValue::String(s) => s,
+            JsonValue::Number(n) => n,
+            JsonValue::Bool | JsonValue::Array(_) | JsonValue::Object(_) => {
+                return Err(format!("'{}' must be a number or a string", key));
+            }
+        };
+        fields.insert(normalize_key(&key), text);
+    }
+    Ok(fields)
+}
+
+// Just enough JSON for batch files. Numbers keep their source text so they
+// are parsed exactly like command-line values.
+enum JsonValue {
+    Null,
+    Bool,
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        JsonParser { chars: text.chars().peekable(), line: 1 }
+    }
+
+    // A single value with nothing but whitespace around it
+    fn document(&mut self) -> Result<JsonValue, String> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected '{}' after the end of the document", c)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}', found the end of the input", expected)),
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some('t') => self.keyword("true", JsonValue::Bool),
+            Some('f') => self.keyword("false", JsonValue::Bool),
+            Some('n') => self.keyword("null", JsonValue::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("unexpected end of the input".to_string()),
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, String> {
+        for expected in word.chars() {
+            if self.chars.next() != Some(expected) {
+                return Err(format!("invalid literal, expected '{}'", word));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let mut text = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+        }
+        match text.parse::<f64>() {
+            Ok(_) => Ok(JsonValue::Number(text)),
+            Err(_) => Err(format!("invalid number '{}'", text)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => {
+                    let escaped = match self.chars.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex: String = (0..4).filter_map(|_| self.chars.next()).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid escape '\\u{}'", hex))?
+                        }
+                        Some(c) => return Err(format!("invalid escape '\\{}'", c)),
+                        None => return Err("unterminated string".to_string()),
+                    };
+                    text.push(escaped);
+                }
+                Some('\n') | None => return Err("unterminated string".to_string()),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(items)),
+                _ => return Err("expected ',' or ']' in array".to_string()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, String> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(members)),
+                _ => return Err("expected ',' or '}' in object".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(row: &BatchRow) -> &HashMap<String, String> {
+        row.fields.as_ref().expect("row should parse")
+    }
+
+    #[test]
+    fn parses_a_json_array() {
+        let text = "[\n  {\"id\": \"leo-geo\", \"Calculation\": \"hohmann\", \"r1\": 6678, \"r2\": 4.2164e4},\n  {\"calculation\": \"escape_velocity\", \"Radius_Km\": \"6378\", \"body\": null}\n]";
+        let rows = parse_batch(text, InputFormat::Json).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row, 1);
+        assert_eq!(fields(&rows[0])["calculation"], "hohmann");
+        // Numbers keep their source text
+        assert_eq!(fields(&rows[0])["r2"], "4.2164e4");
+        // Keys are normalized, values are not
+        assert_eq!(fields(&rows[1])["calculation"], "escape_velocity");
+        assert_eq!(fields(&rows[1])["radius-km"], "6378");
+        assert!(!fields(&rows[1]).contains_key("body"));
+        assert_eq!(parse_batch("[]", InputFormat::Json).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn broken_array_reports_its_line() {
+        let error = parse_batch("[\n{\"r1\": 1},\n{\"r1\": }\n]", InputFormat::Json).unwrap_err();
+        assert_eq!(error, BatchError::Parse { line: 3, message: "unexpected '}'".to_string() });
+        assert!(matches!(parse_batch("[{}] x", InputFormat::Json), Err(BatchError::Parse { line: 1, .. })));
+        assert!(matches!(parse_batch("[{\"a\": 1}", InputFormat::Json), Err(BatchError::Parse { .. })));
+    }
+
+    #[test]
+    fn json_lines_fail_one_row_at_a_time() {
+        let text = "{\"calculation\": \"hohmann\"}\n\n{\"calculation\": \"hohmann\",}\n[1]\n{\"flag\": true}\n";
+        let rows = parse_batch(text, InputFormat::Json).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].fields.is_ok());
+        assert_eq!(rows[1].row, 2);
+        assert!(rows[1].fields.as_ref().unwrap_err().starts_with("line 3:"));
+        assert_eq!(rows[2].fields, Err("row is not a JSON object".to_string()));
+        assert_eq!(rows[3].fields, Err("'flag' must be a number or a string".to_string()));
+    }
+
+    #[test]
+    fn decodes_json_string_escapes() {
+        let text = r#"{"id": "a\"b\\c\/d\né\t"}"#;
+        let rows = parse_batch(text, InputFormat::Json).unwrap();
+        assert_eq!(fields(&rows[0])["id"], "a\"b\\c/d\né\t");
+        for bad in [r#"{"id": "\x"}"#, r#"{"id": "\u12"}"#, "{\"id\": \"open}", r#"{"id": tru}"#, r#"{"r1": 1.2.3}"#] {
+            let rows = parse_batch(bad, InputFormat::Json).unwrap();
+            assert!(rows[0].fields.is_err(), "{bad} should not parse");
+        }
+    }
+
+    #[test]
+    fn json_string_round_trips_through_the_parser() {
+        let original = "quote \" backslash \\ newline \n tab \t bell \u{7} é";
+        let encoded = json_string(original);
+        assert!(!encoded[1..encoded.len() - 1].chars().any(|c| (c as u32) < 0x20));
+        let rows = parse_batch(&format!("{{\"id\": {}}}", encoded), InputFormat::Json).unwrap();
+        assert_eq!(fields(&rows[0])["id"], original);
+    }
+
+    #[test]
+    fn parses_csv_with_quoted_fields() {
+        let text = "# trade study\nID, Calculation ,r1,r2\n\"leo, geo\",hohmann,6678,42164\n\n\"say \"\"hi\"\"\",hohmann,,\n";
+        let rows = parse_batch(text, InputFormat::Csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(fields(&rows[0])["id"], "leo, geo");
+        assert_eq!(fields(&rows[0])["r2"], "42164");
+        assert_eq!(fields(&rows[1])["id"], "say \"hi\"");
+        // Empty cells count as absent
+        assert!(!fields(&rows[1]).contains_key("r1"));
+        assert_eq!(rows[1].row, 2);
+    }
+
+    #[test]
+    fn csv_row_problems_stay_in_their_row() {
+        let text = "calculation,r1\nhohmann,1,2\nhohmann,\"open\nhohmann,7000\n";
+        let rows = parse_batch(text, InputFormat::Csv).unwrap();
+        assert_eq!(rows[0].fields, Err("line 2: 3 fields for 2 columns".to_string()));
+        assert_eq!(rows[1].fields, Err("unterminated quoted field".to_string()));
+        assert_eq!(fields(&rows[2])["r1"], "7000");
+
+        let error = parse_batch("\n\nid,r1\n", InputFormat::Csv).unwrap_err();
+        assert_eq!(error, BatchError::Parse { line: 3, message: "header has no 'calculation' column".to_string() });
+        assert_eq!(parse_batch("", InputFormat::Csv).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn evaluates_each_row_on_its_own() {
+        let text = "id,calculation,r1,r2,radius\nok,hohmann,6678,42164,\nbad,hohmann,6678,,\nextra,hohmann,6678,42164,1\n,warp,,,\n";
+        let results = run_batch(&parse_batch(text, InputFormat::Csv).unwrap(), &BodyCatalog::builtin());
+        assert!(results[0].is_ok());
+        assert_eq!(results[0].id.as_deref(), Some("ok"));
+        assert!(results[1].outcome.as_ref().unwrap_err().contains("r2"));
+        assert_eq!(results[2].outcome, Err("'hohmann' does not take radius".to_string()));
+        assert_eq!(results[3].outcome, Err("unknown calculation 'warp'".to_string()));
+        assert_eq!(results[3].calculation.as_deref(), Some("warp"));
+    }
+
+    #[test]
+    fn formats_results_for_both_outputs() {
+        let text = "id,calculation,r1,r2\n\"a,b\",hohmann,6678,42164\nx,warp,,\n";
+        let results = run_batch(&parse_batch(text, InputFormat::Csv).unwrap(), &BodyCatalog::builtin());
+        let csv = results_to_csv(&results);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "row,id,calculation,label,value,unit,error");
+        assert!(lines[1].starts_with("1,\"a,b\",hohmann,"));
+        assert_eq!(lines[2], "2,x,warp,,,,unknown calculation 'warp'");
+
+        let jsonl = results_to_jsonl(&results);
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert!(lines[0].starts_with("{\"row\":1,\"id\":\"a,b\",\"calculation\":\"hohmann\","));
+        assert!(lines[0].ends_with("\"error\":null}"));
+        assert_eq!(
+            lines[1],
+            "{\"row\":2,\"id\":\"x\",\"calculation\":\"warp\",\"label\":null,\"value\":null,\"unit\":null,\"error\":\"unknown calculation 'warp'\"}"
+        );
+        // The JSON Lines output reads back as input
+        assert_eq!(parse_batch(&jsonl, InputFormat::Json).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn picks_formats_from_the_extension() {
+        assert_eq!(InputFormat::from_path("study.CSV"), InputFormat::Csv);
+        assert_eq!(InputFormat::from_path("study.json"), InputFormat::Json);
+        assert_eq!(OutputFormat::from_path("out.csv"), OutputFormat::Csv);
+        assert_eq!(OutputFormat::from_path("out"), OutputFormat::JsonLines);
+    }
+}
@@ -0,0 +1,254 @@
+// Central bodies used by the orbital calculations
+//
+// All values are stored in SI units (m, s, rad). The built-in catalog uses
+// IAU/JPL planetary constants; user bodies can be loaded from a text file.
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralBody {
+    pub name: String,
+    pub mu: f64,                // Gravitational parameter GM in m^3/s^2
+    pub equatorial_radius: f64, // Equatorial radius in m
+    pub j2: f64,                // Second zonal harmonic (dimensionless)
+    pub j3: f64,                // Third zonal harmonic (dimensionless, 0 when unknown)
+    pub rotation_rate: f64,     // Sidereal rotation rate in rad/s (negative = retrograde)
+    pub flattening: f64,        // Geometric flattening (a - b) / a
+}
+
+impl CentralBody {
+    // Build a body from the units planetary fact sheets use (km^3/s^2 and km)
+    pub fn new(name: &str, mu_km3_s2: f64, radius_km: f64, j2: f64, rotation_rate: f64, flattening: f64) -> Self {
+        CentralBody {
+            name: name.to_string(),
+            mu: mu_km3_s2 * 1e9,
+            equatorial_radius: radius_km * 1000.0,
+            j2,
+            j3: 0.0,
+            rotation_rate,
+            flattening,
+        }
+    }
+
+    pub fn with_j3(mut self, j3: f64) -> Self {
+        self.j3 = j3;
+        self
+    }
+
+    pub fn sun() -> Self {
+        CentralBody::new("Sun", 1.32712440018e11, 695700.0, 2.2e-7, 2.865329607e-6, 9.0e-6)
+    }
+
+    pub fn mercury() -> Self {
+        CentralBody::new("Mercury", 22031.78, 2440.53, 5.03e-5, 1.240013e-6, 0.0009)
+    }
+
+    pub fn venus() -> Self {
+        CentralBody::new("Venus", 324858.592, 6051.8, 4.458e-6, -2.99246e-7, 0.0)
+    }
+
+    pub fn earth() -> Self {
+        CentralBody::new("Earth", 398600.4418, 6378.137, 1.08262668e-3, 7.292115e-5, 1.0 / 298.257223563)
+            .with_j3(-2.53265649e-6)
+    }
+
+    pub fn moon() -> Self {
+        CentralBody::new("Moon", 4902.800066, 1738.1, 2.033e-4, 2.6617e-6, 0.0012).with_j3(8.476e-6)
+    }
+
+    pub fn mars() -> Self {
+        CentralBody::new("Mars", 42828.37, 3396.19, 1.96045e-3, 7.088218e-5, 0.00589).with_j3(3.1450e-5)
+    }
+
+    pub fn jupiter() -> Self {
+        CentralBody::new("Jupiter", 126686534.0, 71492.0, 1.4736e-2, 1.75853e-4, 0.06487)
+    }
+
+    pub fn saturn() -> Self {
+        CentralBody::new("Saturn", 37931187.0, 60268.0, 1.6298e-2, 1.63785e-4, 0.09796)
+    }
+
+    pub fn uranus() -> Self {
+        CentralBody::new("Uranus", 5793939.0, 25559.0, 3.34343e-3, -1.01237e-4, 0.02293)
+    }
+
+    pub fn neptune() -> Self {
+        CentralBody::new("Neptune", 6836529.0, 24764.0, 3.411e-3, 1.08338e-4, 0.01708)
+    }
+
+    pub fn pluto() -> Self {
+        CentralBody::new("Pluto", 869.6, 1188.3, 0.0, -1.1386e-5, 0.0)
+    }
+
+    // Polar radius derived from the flattening, in m
+    pub fn polar_radius(&self) -> f64 {
+        self.equatorial_radius * (1.0 - self.flattening)
+    }
+
+    // Earth-fixed conversions (sidereal time, station look angles, ground
+    // tracks) only know the Earth's rotation; other bodies are out of range
+    pub fn is_earth(&self) -> bool {
+        self.name.eq_ignore_ascii_case("earth")
+    }
+}
+
+impl Default for CentralBody {
+    fn default() -> Self {
+        CentralBody::earth()
+    }
+}
+
+impl fmt::Display for CentralBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (mu = {:.6e} m^3/s^2, R = {:.1} km, J2 = {:.4e})",
+            self.name,
+            self.mu,
+            self.equatorial_radius / 1000.0,
+            self.j2
+        )
+    }
+}
+
+// Errors raised while loading a body file
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogError::Io(message) => write!(f, "could not read body file: {}", message),
+            CatalogError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+// Collection of central bodies looked up by case-insensitive name
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyCatalog {
+    bodies: Vec<CentralBody>,
+}
+
+impl BodyCatalog {
+    pub fn builtin() -> Self {
+        BodyCatalog {
+            bodies: vec![
+                CentralBody::sun(),
+                CentralBody::mercury(),
+                CentralBody::venus(),
+                CentralBody::earth(),
+                CentralBody::moon(),
+                CentralBody::mars(),
+                CentralBody::jupiter(),
+                CentralBody::saturn(),
+                CentralBody::uranus(),
+                CentralBody::neptune(),
+                CentralBody::pluto(),
+            ],
+        }
+    }
+
+    pub fn find(&self, name: &str) -> Option<&CentralBody> {
+        let name = name.trim();
+        self.bodies.iter().find(|body| body.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn bodies(&self) -> &[CentralBody] {
+        &self.bodies
+    }
+
+    // Add a body, replacing any existing body with the same name
+    pub fn insert(&mut self, body: CentralBody) {
+        match self.bodies.iter_mut().find(|b| b.name.eq_ignore_ascii_case(&body.name)) {
+            Some(existing) => *existing = body,
+            None => self.bodies.push(body),
+        }
+    }
+
+    // Load user-defined bodies from a file and add them to the catalog.
+    // Returns the number of bodies read.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, CatalogError> {
+        let text = fs::read_to_string(path).map_err(|e| CatalogError::Io(e.to_string()))?;
+        let bodies = parse_bodies(&text)?;
+        let count = bodies.len();
+        for body in bodies {
+            self.insert(body);
+        }
+        Ok(count)
+    }
+}
+
+impl Default for BodyCatalog {
+    fn default() -> Self {
+        BodyCatalog::builtin()
+    }
+}
+
+// Parse body definitions, one per line:
+//
+//     # name, mu (km^3/s^2), equatorial radius (km), J2, rotation rate (rad/s), flattening[, J3]
+//     Ceres, 62.6284, 469.7, 0.0, 1.923e-4, 0.075
+//
+// The J3 column is optional. Blank lines and lines starting with '#' are ignored.
+pub fn parse_bodies(text: &str) -> Result<Vec<CentralBody>, CatalogError> {
+    let mut bodies = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if fields.len() != 6 && fields.len() != 7 {
+            return Err(CatalogError::Parse {
+                line: line_number,
+                message: format!("expected 6 or 7 comma-separated fields, found {}", fields.len()),
+            });
+        }
+        if fields[0].is_empty() {
+            return Err(CatalogError::Parse { line: line_number, message: "body name is empty".to_string() });
+        }
+
+        let mut values = [0.0; 6];
+        for (value, (field, what)) in values.iter_mut().zip(fields[1..].iter().zip([
+            "gravitational parameter",
+            "equatorial radius",
+            "J2",
+            "rotation rate",
+            "flattening",
+            "J3",
+        ])) {
+            *value = field.parse().map_err(|_| CatalogError::Parse {
+                line: line_number,
+                message: format!("invalid {} '{}'", what, field),
+            })?;
+        }
+
+        let [mu, radius, j2, rotation_rate, flattening, j3] = values;
+        if mu <= 0.0 || radius <= 0.0 {
+            return Err(CatalogError::Parse {
+                line: line_number,
+                message: "gravitational parameter and radius must be positive".to_string(),
+            });
+        }
+        if !(0.0..1.0).contains(&flattening) {
+            return Err(CatalogError::Parse {
+                line: line_number,
+                message: format!("flattening {} must be in [0, 1)", flattening),
+            });
+        }
+
+        bodies.push(CentralBody::new(fields[0], mu, radius, j2, rotation_rate, flattening).with_j3(j3));
+    }
+
+    Ok(bodies)
+}
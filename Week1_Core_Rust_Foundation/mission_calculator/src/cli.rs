@@ -0,0 +1,526 @@
+// Non-interactive command line
+//
+// Every `SpaceCalculation` variant is a subcommand with named flags, so the
+// calculator can be scripted:
+//
+//     mission_calculator hohmann --r1 6678 --r2 42164 --body earth
+//
+// Flags use the same units as the interactive menu (antenna dimensions are
+// all in m), and `batch` evaluates a whole file of calculations (see
+// batch.rs). Parsing never prints; the binary decides how to report results
+// and errors and which exit code to return.
+use crate::antenna::{Antenna, AntennaGain, element_count};
+use crate::body::CatalogError;
+use crate::ephemeris::J2000_JD;
+use crate::frames::{Frame, Geodetic, parse_frame};
+use crate::time::{Epoch, TimeScale, parse_epoch};
+use crate::units::{Angle, Decibels, Frequency, Length, Mass, Power, Velocity};
+use crate::{BodyCatalog, CalculationError, SpaceCalculation};
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+
+// Exit code for malformed command lines, as with most Unix tools
+pub const USAGE_EXIT_CODE: i32 = 2;
+// Exit code when an input file (bodies, batch cases) cannot be read
+pub const FILE_EXIT_CODE: i32 = 1;
+
+// Subcommand name, its flags with descriptions, and whether it takes --body.
+// Flags whose description ends in a [default] may be left out. Batch files
+// use the same names for their parameters.
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub variant: &'static str, // SpaceCalculation variant it builds
+    pub description: &'static str,
+    pub flags: &'static [(&'static str, &'static str)],
+    pub takes_body: bool,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "orbital-velocity",
+        variant: "OrbitalVelocity",
+        description: "Circular orbital velocity",
+        flags: &[("radius", "orbital radius (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "escape-velocity",
+        variant: "EscapeVelocity",
+        description: "Escape velocity",
+        flags: &[("radius", "distance from the center of mass (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "orbital-period",
+        variant: "OrbitalPeriod",
+        description: "Circular orbital period",
+        flags: &[("radius", "orbital radius (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "hohmann",
+        variant: "Hohmann",
+        description: "Hohmann transfer delta-v",
+        flags: &[("r1", "initial orbital radius (km)"), ("r2", "final orbital radius (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "rocket",
+        variant: "TsiolkovskyRocket",
+        description: "Rocket delta-v (Tsiolkovsky)",
+        flags: &[
+            ("exhaust-velocity", "exhaust velocity (m/s)"),
+            ("init-mass", "initial mass (kg)"),
+            ("final-mass", "final mass (kg)"),
+        ],
+        takes_body: false,
+    },
+    CommandSpec {
+        name: "gravitational-force",
+        variant: "GravitationalForce",
+        description: "Gravitational force on a satellite",
+        flags: &[("mass", "satellite mass (kg)"), ("radius", "distance from the center of mass (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "geostationary-radius",
+        variant: "GeostationaryOrbitRadius",
+        description: "Synchronous (geostationary) orbit radius",
+        flags: &[],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "orbital-energy",
+        variant: "OrbitalEnergy",
+        description: "Orbital energy of a circular orbit",
+        flags: &[("mass", "satellite mass (kg)"), ("radius", "orbital radius (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "escape-energy",
+        variant: "EscapeEnergy",
+        description: "Energy needed to escape from a radius",
+        flags: &[("mass", "satellite mass (kg)"), ("radius", "distance from the center of mass (km)")],
+        takes_body: true,
+    },
+    CommandSpec {
+        name: "link-equation",
+        variant: "LinBudgetEquation",
+        description: "Received power from the link equation",
+        flags: &[
+            ("pt", "transmit power (dBm)"),
+            ("gt", "transmit antenna gain (dBi), or model it with --tx-antenna"),
+            ("gr", "receive antenna gain (dBi), or model it with --rx-antenna"),
+            ("lp", "path loss (dB)"),
+            ("ls", "system losses (dB)"),
+            ("lm", "miscellaneous losses (dB)"),
+            ("frequency", "frequency (Hz), required with --tx-antenna or --rx-antenna"),
+            ("tx-antenna", "transmit antenna model: dish, helix or patch"),
+            ("tx-diameter", "dish or helix diameter (m)"),
+            ("tx-efficiency", "dish or patch aperture efficiency (0-1)"),
+            ("tx-turns", "number of helix turns"),
+            ("tx-spacing", "helix turn or patch element spacing (m)"),
+            ("tx-rows", "patch element rows"),
+            ("tx-columns", "patch element columns"),
+            ("tx-pointing-error", "transmit pointing error (degrees) [0]"),
+            ("rx-antenna", "receive antenna model: dish, helix or patch"),
+            ("rx-diameter", "dish or helix diameter (m)"),
+            ("rx-efficiency", "dish or patch aperture efficiency (0-1)"),
+            ("rx-turns", "number of helix turns"),
+            ("rx-spacing", "helix turn or patch element spacing (m)"),
+            ("rx-rows", "patch element rows"),
+            ("rx-columns", "patch element columns"),
+            ("rx-pointing-error", "receive pointing error (degrees) [0]"),
+        ],
+        takes_body: false,
+    },
+    CommandSpec {
+        name: "path-loss",
+        variant: "PathLoss",
+        description: "Free-space path loss",
+        flags: &[("distance", "distance (km)"), ("frequency", "frequency (Hz)")],
+        takes_body: false,
+    },
+    CommandSpec {
+        name: "incline-angle",
+        variant: "InclineAngle",
+        description: "Orbit inclination from a state vector",
+        flags: &[
+            ("x", "position x (m)"),
+            ("y", "position y (m)"),
+            ("z", "position z (m)"),
+            ("vx", "velocity x (m/s)"),
+            ("vy", "velocity y (m/s)"),
+            ("vz", "velocity z (m/s)"),
+            ("frame", "frame of the state: gcrf, mod, tod, teme, pef, itrf [gcrf]"),
+            ("epoch", "UTC epoch of the state, ISO 8601 or JD; needed unless gcrf"),
+        ],
+        takes_body: false,
+    },
+    CommandSpec {
+        name: "slant-range",
+        variant: "SlantRange",
+        description: "Station-to-satellite slant range",
+        flags: &[
+            ("altitude", "satellite altitude above the ellipsoid (km)"),
+            ("elevation", "elevation angle (degrees)"),
+            ("azimuth", "azimuth, clockwise from north (degrees) [0]"),
+            ("latitude", "station geodetic latitude (degrees) [0]"),
+            ("longitude", "station longitude (degrees, east positive) [0]"),
+            ("height", "station height above the ellipsoid (m) [0]"),
+        ],
+        takes_body: true,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownFlag { command: &'static str, flag: String },
+    MissingFlag { command: &'static str, flag: &'static str },
+    MissingValue { flag: String },
+    DuplicateFlag { flag: String },
+    InvalidNumber { flag: String, value: String },
+    InvalidValue { flag: String, value: String },
+    ConflictingFlags { command: &'static str, flags: (&'static str, &'static str) },
+    UnknownBody(String),
+    Catalog(CatalogError),
+    Calculation(CalculationError),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Calculation(e) => e.exit_code(),
+            CliError::Catalog(_) => FILE_EXIT_CODE,
+            _ => USAGE_EXIT_CODE,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownCommand(name) => write!(f, "unknown command '{}'", name),
+            CliError::UnknownFlag { command, flag } => write!(f, "'{}' does not take {}", command, flag),
+            CliError::MissingFlag { command, flag } => write!(f, "'{}' needs a value for {}", command, flag),
+            CliError::MissingValue { flag } => write!(f, "{} needs a value", flag),
+            CliError::DuplicateFlag { flag } => write!(f, "{} given more than once", flag),
+            CliError::InvalidNumber { flag, value } => write!(f, "{}: '{}' is not a finite number", flag, value),
+            CliError::InvalidValue { flag, value } => write!(f, "{}: '{}' is not valid", flag, value),
+            CliError::ConflictingFlags { command, flags: (a, b) } => {
+                write!(f, "'{}' takes either {} or {}, not both", command, a, b)
+            }
+            CliError::UnknownBody(name) => write!(f, "unknown body '{}'", name),
+            CliError::Catalog(e) => write!(f, "{}", e),
+            CliError::Calculation(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<CalculationError> for CliError {
+    fn from(error: CalculationError) -> Self {
+        CliError::Calculation(error)
+    }
+}
+
+// A parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Invocation {
+    Help,
+    Calculation { calculation: SpaceCalculation, value_only: bool },
+    Batch { input: String, output: Option<String>, catalog: BodyCatalog },
+}
+
+// Flags describing the antenna at one end of a link
+struct AntennaFlags {
+    gain: &'static str,
+    model: &'static str,
+    diameter: &'static str,
+    efficiency: &'static str,
+    turns: &'static str,
+    spacing: &'static str,
+    rows: &'static str,
+    columns: &'static str,
+    pointing_error: &'static str,
+}
+
+const TRANSMIT_ANTENNA: AntennaFlags = AntennaFlags {
+    gain: "gt",
+    model: "tx-antenna",
+    diameter: "tx-diameter",
+    efficiency: "tx-efficiency",
+    turns: "tx-turns",
+    spacing: "tx-spacing",
+    rows: "tx-rows",
+    columns: "tx-columns",
+    pointing_error: "tx-pointing-error",
+};
+
+const RECEIVE_ANTENNA: AntennaFlags = AntennaFlags {
+    gain: "gr",
+    model: "rx-antenna",
+    diameter: "rx-diameter",
+    efficiency: "rx-efficiency",
+    turns: "rx-turns",
+    spacing: "rx-spacing",
+    rows: "rx-rows",
+    columns: "rx-columns",
+    pointing_error: "rx-pointing-error",
+};
+
+// Look a command up by its subcommand name or its `SpaceCalculation` variant
+// name, ignoring case and treating '_' like '-'
+pub fn find_command(name: &str) -> Option<&'static CommandSpec> {
+    let name = name.trim().replace('_', "-");
+    COMMANDS.iter().find(|c| c.name.eq_ignore_ascii_case(&name) || c.variant.eq_ignore_ascii_case(&name))
+}
+
+// Build the calculation for `spec` from its named parameters. `body` names a
+// central body in `catalog` and defaults to Earth.
+pub fn build_calculation(
+    spec: &'static CommandSpec,
+    values: &HashMap<&str, &str>,
+    catalog: &BodyCatalog,
+) -> Result<SpaceCalculation, CliError> {
+    // `f64::parse` also takes inf and NaN, which no calculation can use
+    let number = |flag: &'static str| -> Result<f64, CliError> {
+        let text = values.get(flag).ok_or(CliError::MissingFlag { command: spec.name, flag })?;
+        text.trim()
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite())
+            .ok_or_else(|| CliError::InvalidNumber { flag: flag.to_string(), value: text.to_string() })
+    };
+    let number_or = |flag: &'static str, default: f64| if values.contains_key(flag) { number(flag) } else { Ok(default) };
+    let body = || -> Result<_, CliError> {
+        let name = values.get("body").copied().filter(|n| !n.trim().is_empty()).unwrap_or("Earth");
+        catalog.find(name).cloned().ok_or_else(|| CliError::UnknownBody(name.to_string()))
+    };
+
+    let invalid = |flag: &str, value: &str| CliError::InvalidValue { flag: flag.to_string(), value: value.to_string() };
+    // An entered gain is taken net of pointing loss; a model works out both
+    let antenna = |flags: &AntennaFlags| -> Result<AntennaGain, CliError> {
+        let Some(model) = values.get(flags.model) else {
+            return Ok(AntennaGain::Given { gain_dbi: number(flags.gain)?, pointing_loss_db: 0.0 });
+        };
+        if values.contains_key(flags.gain) {
+            return Err(CliError::ConflictingFlags { command: spec.name, flags: (flags.gain, flags.model) });
+        }
+        let count = |flag: &'static str| number(flag).and_then(|n| element_count(n).map_err(|_| invalid(flag, values[flag])));
+        let antenna = match model.trim().to_ascii_lowercase().as_str() {
+            "dish" => Antenna::ParabolicDish { diameter: number(flags.diameter)?, efficiency: number(flags.efficiency)? },
+            "helix" => Antenna::Helix {
+                diameter: number(flags.diameter)?,
+                turns: number(flags.turns)?,
+                spacing: number(flags.spacing)?,
+            },
+            "patch" => Antenna::PatchArray {
+                rows: count(flags.rows)?,
+                columns: count(flags.columns)?,
+                spacing: number(flags.spacing)?,
+                efficiency: number(flags.efficiency)?,
+            },
+            _ => return Err(invalid(flags.model, model)),
+        };
+        Ok(AntennaGain::Modeled { antenna, pointing_error: number_or(flags.pointing_error, 0.0)?.to_radians() })
+    };
+
+    let km = |flag| number(flag).map(Length::from_kilometers);
+    let kg = |flag| number(flag).map(Mass::from_kilograms);
+    let db = |flag| number(flag).map(Decibels::from_db);
+    let calculation = match spec.name {
+        "orbital-velocity" => SpaceCalculation::OrbitalVelocity { radius: km("radius")?, body: body()? },
+        "escape-velocity" => SpaceCalculation::EscapeVelocity { radius: km("radius")?, body: body()? },
+        "orbital-period" => SpaceCalculation::OrbitalPeriod { radius: km("radius")?, body: body()? },
+        "hohmann" => SpaceCalculation::Hohmann { r1: km("r1")?, r2: km("r2")?, body: body()? },
+        "rocket" => SpaceCalculation::TsiolkovskyRocket {
+            exhaust_velocity: Velocity::from_meters_per_second(number("exhaust-velocity")?),
+            init_mass: kg("init-mass")?,
+            final_mass: kg("final-mass")?,
+        },
+        "gravitational-force" => {
+            SpaceCalculation::GravitationalForce { msat: kg("mass")?, radius: km("radius")?, body: body()? }
+        }
+        "geostationary-radius" => SpaceCalculation::GeostationaryOrbitRadius { body: body()? },
+        "orbital-energy" => SpaceCalculation::OrbitalEnergy { msat: kg("mass")?, radius: km("radius")?, body: body()? },
+        "escape-energy" => SpaceCalculation::EscapeEnergy { msat: kg("mass")?, radius: km("radius")?, body: body()? },
+        "link-equation" => {
+            let (gt, gr) = (antenna(&TRANSMIT_ANTENNA)?, antenna(&RECEIVE_ANTENNA)?);
+            // A modeled antenna has no gain without a frequency
+            let modeled = [gt, gr].iter().any(|gain| matches!(gain, AntennaGain::Modeled { .. }));
+            let frequency = if modeled || values.contains_key("frequency") {
+                Some(Frequency::from_hertz(number("frequency")?))
+            } else {
+                None
+            };
+            SpaceCalculation::LinBudgetEquation {
+                pt: Power::from_dbm(number("pt")?),
+                gt,
+                gr,
+                frequency,
+                lp: db("lp")?,
+                ls: db("ls")?,
+                lm: db("lm")?,
+            }
+        }
+        "path-loss" => SpaceCalculation::PathLoss {
+            radius: km("distance")?,
+            frequency: Frequency::from_hertz(number("frequency")?),
+        },
+        "incline-angle" => {
+            let m = |flag| number(flag).map(Length::from_meters);
+            let m_s = |flag| number(flag).map(Velocity::from_meters_per_second);
+            let frame = match values.get("frame") {
+                Some(text) => parse_frame(text).map_err(|_| invalid("frame", text))?,
+                None => Frame::Gcrf,
+            };
+            // GCRF does not move, so its epoch does not matter
+            let epoch = match values.get("epoch") {
+                Some(text) => parse_epoch(text).map_err(|_| invalid("epoch", text))?,
+                None if frame == Frame::Gcrf => Epoch::from_julian_date(J2000_JD, TimeScale::Utc),
+                None => return Err(CliError::MissingFlag { command: spec.name, flag: "epoch" }),
+            };
+            SpaceCalculation::InclineAngle {
+                possatx: m("x")?,
+                possaty: m("y")?,
+                possatz: m("z")?,
+                velsatx: m_s("vx")?,
+                velsaty: m_s("vy")?,
+                velsatz: m_s("vz")?,
+                frame,
+                epoch,
+            }
+        }
+        "slant-range" => SpaceCalculation::SlantRange {
+            altitude: km("altitude")?,
+            elevation: Angle::from_degrees(number("elevation")?),
+            azimuth: Angle::from_degrees(number_or("azimuth", 0.0)?),
+            site: Geodetic {
+                latitude: number_or("latitude", 0.0)?.to_radians(),
+                longitude: number_or("longitude", 0.0)?.to_radians(),
+                altitude: number_or("height", 0.0)?,
+            },
+            body: body()?,
+        },
+        _ => unreachable!("every command in COMMANDS is handled"),
+    };
+    Ok(calculation)
+}
+
+// Parse the arguments after the program name. `--bodies <file>` loads extra
+// central bodies before `--body` is looked up; `--value` asks for the bare
+// number without label and unit.
+pub fn parse_args(args: &[String]) -> Result<Invocation, CliError> {
+    let Some((name, rest)) = args.split_first() else {
+        return Ok(Invocation::Help);
+    };
+    if matches!(name.as_str(), "help" | "--help" | "-h") {
+        return Ok(Invocation::Help);
+    }
+    let batch = name == "batch";
+    let spec = if batch { None } else { Some(find_command(name).ok_or_else(|| CliError::UnknownCommand(name.clone()))?) };
+    let command = spec.map_or("batch", |s| s.name);
+    let accepts = |flag: &str| match spec {
+        Some(spec) => spec.flags.iter().any(|(name, _)| *name == flag) || (spec.takes_body && matches!(flag, "body" | "bodies")),
+        None => matches!(flag, "input" | "output" | "bodies"),
+    };
+
+    let mut values: HashMap<&str, &str> = HashMap::new();
+    let mut value_only = false;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(Invocation::Help);
+        }
+        if arg == "--value" && !batch {
+            value_only = true;
+            continue;
+        }
+        let flag = arg
+            .strip_prefix("--")
+            .filter(|f| accepts(f))
+            .ok_or_else(|| CliError::UnknownFlag { command, flag: arg.clone() })?;
+        let value = iter.next().ok_or_else(|| CliError::MissingValue { flag: arg.clone() })?;
+        if values.insert(flag, value).is_some() {
+            return Err(CliError::DuplicateFlag { flag: arg.clone() });
+        }
+    }
+
+    let mut catalog = BodyCatalog::builtin();
+    if let Some(path) = values.get("bodies") {
+        catalog.load_file(path).map_err(CliError::Catalog)?;
+    }
+    match spec {
+        Some(spec) => Ok(Invocation::Calculation { calculation: build_calculation(spec, &values, &catalog)?, value_only }),
+        None => {
+            let input = values.get("input").ok_or(CliError::MissingFlag { command, flag: "input" })?;
+            Ok(Invocation::Batch {
+                input: input.to_string(),
+                output: values.get("output").map(|o| o.to_string()),
+                catalog,
+            })
+        }
+    }
+}
+
+// Help text listing every subcommand and its flags
+pub fn usage() -> String {
+    let mut text = String::from(
+        "Usage: mission_calculator [COMMAND --FLAG VALUE ...]\n\n\
+         Without a command the interactive menu starts.\n\n\
+         Commands:\n",
+    );
+    for spec in COMMANDS {
+        let _ = writeln!(text, "  {:<22} {}", spec.name, spec.description);
+        for (flag, description) in spec.flags {
+            let _ = writeln!(text, "      --{:<18} {}", flag, description);
+        }
+        if spec.takes_body {
+            let _ = writeln!(text, "      --{:<18} central body [Earth]", "body");
+            let _ = writeln!(text, "      --{:<18} file with extra central bodies", "bodies");
+        }
+    }
+    let _ = writeln!(text, "  {:<22} Evaluate a JSON or CSV file of calculations", "batch");
+    let _ = writeln!(text, "      --{:<18} cases file (.json, .jsonl or .csv)", "input");
+    let _ = writeln!(text, "      --{:<18} results file (.jsonl or .csv) [stdout, JSON Lines]", "output");
+    let _ = writeln!(text, "      --{:<18} file with extra central bodies", "bodies");
+    let _ = writeln!(text, "\nEvery calculation accepts --value to print only the number.");
+    let _ = write!(
+        text,
+        "Exit codes: 0 success, {} usage error, {} file error, {} invalid input, {} division by zero, \
+         {} negative value, {} out of range",
+        USAGE_EXIT_CODE,
+        FILE_EXIT_CODE,
+        CalculationError::InvalidInput.exit_code(),
+        CalculationError::DivisionByZero.exit_code(),
+        CalculationError::NegativeValue.exit_code(),
+        CalculationError::OutOfRange.exit_code()
+    );
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(pairs: &[(&str, &str)]) -> Result<SpaceCalculation, CliError> {
+        let values: HashMap<&str, &str> = pairs.iter().copied().collect();
+        build_calculation(find_command("link-equation").unwrap(), &values, &BodyCatalog::builtin())
+    }
+
+    const LINK: [(&str, &str); 5] = [("pt", "30"), ("gr", "3"), ("lp", "150"), ("ls", "1"), ("lm", "0")];
+
+    #[test]
+    fn modeled_antenna_needs_a_frequency() {
+        let dish = [("tx-antenna", "dish"), ("tx-diameter", "1"), ("tx-efficiency", "0.55")];
+        let error = build(&[&LINK[..], &dish].concat()).unwrap_err();
+        assert_eq!(error, CliError::MissingFlag { command: "link-equation", flag: "frequency" });
+        assert_eq!(error.exit_code(), USAGE_EXIT_CODE);
+        assert!(build(&[&LINK[..], &dish, &[("frequency", "8e9")]].concat()).is_ok());
+        // Entered gains do without one
+        assert!(build(&[&LINK[..], &[("gt", "3")]].concat()).is_ok());
+    }
+}
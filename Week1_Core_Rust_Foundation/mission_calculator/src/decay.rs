@@ -0,0 +1,287 @@
+// Orbital decay from atmospheric drag and lifetime estimation
+//
+// Drag is averaged over one revolution (King-Hele's theory, non-rotating
+// atmosphere): the change of semi-major axis and eccentricity per orbit is
+// integrated over the eccentric anomaly with the density at each point, and
+// the resulting secular rates are integrated in time with a fourth-order
+// Runge-Kutta scheme whose step shrinks as the decay speeds up. The orbit
+// has re-entered once its perigee drops below 100 km.
+//
+// The ballistic coefficient is B = m / (Cd A) in kg/m^2; a larger B decays
+// more slowly.
+use crate::CalculationError;
+use crate::atmosphere::DensityModel;
+use crate::body::CentralBody;
+use std::f64::consts::PI;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+pub const REENTRY_ALTITUDE: f64 = 100e3; // m, perigee altitude treated as re-entry
+pub const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+
+const QUADRATURE_POINTS: usize = 48;
+// Largest relative change of the semi-major axis in one integration step
+const MAX_RELATIVE_STEP: f64 = 1e-3;
+
+pub fn ballistic_coefficient(mass: f64, drag_coefficient: f64, area: f64) -> Result<f64, CalculationError> {
+    if mass <= 0.0 || drag_coefficient <= 0.0 || area <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(mass / (drag_coefficient * area))
+}
+
+// Post-mission disposal rules for LEO: re-enter within 25 years (IADC and
+// ISO 24113 guideline) or within 5 years (FCC rule for US-licensed missions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisposalRule {
+    TwentyFiveYear,
+    FiveYear,
+}
+
+impl DisposalRule {
+    pub fn years(&self) -> f64 {
+        match self {
+            DisposalRule::TwentyFiveYear => 25.0,
+            DisposalRule::FiveYear => 5.0,
+        }
+    }
+}
+
+impl fmt::Display for DisposalRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-year rule", self.years())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecayPoint {
+    pub time: f64,            // s since the start
+    pub semi_major_axis: f64, // m
+    pub eccentricity: f64,
+    pub perigee_altitude: f64, // m
+    pub apogee_altitude: f64,  // m
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifetimeEstimate {
+    pub history: Vec<DecayPoint>,
+    // s until re-entry, None when the orbit survives the whole span
+    pub lifetime: Option<f64>,
+    pub span: f64, // s, length of the propagation
+}
+
+impl LifetimeEstimate {
+    pub fn lifetime_years(&self) -> Option<f64> {
+        self.lifetime.map(|t| t / SECONDS_PER_YEAR)
+    }
+
+    // Whether re-entry happens within the rule's limit. An orbit that
+    // outlives a span shorter than the limit may still re-enter in time, so
+    // the answer is None (undetermined) until the span covers the limit.
+    pub fn complies(&self, rule: DisposalRule) -> Option<bool> {
+        let limit = rule.years() * SECONDS_PER_YEAR;
+        match self.lifetime {
+            Some(t) => Some(t <= limit),
+            None if self.span >= limit => Some(false),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for LifetimeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lifetime_years() {
+            Some(years) => writeln!(f, "Orbital lifetime: {:.2} years ({:.0} days)", years, years * 365.25)?,
+            None => writeln!(f, "Orbital lifetime: more than {:.0} years", self.span / SECONDS_PER_YEAR)?,
+        }
+        for rule in [DisposalRule::TwentyFiveYear, DisposalRule::FiveYear] {
+            match self.complies(rule) {
+                Some(true) => writeln!(f, "{}: compliant", rule)?,
+                Some(false) => writeln!(f, "{}: NOT compliant", rule)?,
+                None => writeln!(f, "{}: undetermined, propagate for at least {} years", rule, rule.years())?,
+            }
+        }
+        if let Some(last) = self.history.last() {
+            write!(
+                f,
+                "Final perigee/apogee altitude: {:.1} / {:.1} km",
+                last.perigee_altitude / 1000.0,
+                last.apogee_altitude / 1000.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn point(time: f64, a: f64, e: f64, body: &CentralBody) -> DecayPoint {
+    DecayPoint {
+        time,
+        semi_major_axis: a,
+        eccentricity: e,
+        perigee_altitude: a * (1.0 - e) - body.equatorial_radius,
+        apogee_altitude: a * (1.0 + e) - body.equatorial_radius,
+    }
+}
+
+// Orbit-averaged da/dt (m/s) and de/dt (1/s) from drag. A density model
+// that breaks down along the orbit (non-finite density) is out of range.
+pub fn decay_rates(
+    a: f64,
+    e: f64,
+    body: &CentralBody,
+    model: &DensityModel,
+    ballistic_coefficient: f64,
+) -> Result<(f64, f64), CalculationError> {
+    let e = e.max(0.0);
+    let step = 2.0 * PI / QUADRATURE_POINTS as f64;
+    let (mut da, mut de) = (0.0, 0.0);
+    // Midpoint rule over the eccentric anomaly, exact for periodic integrands
+    for k in 0..QUADRATURE_POINTS {
+        let cos_e = ((k as f64 + 0.5) * step).cos();
+        let altitude = a * (1.0 - e * cos_e) - body.equatorial_radius;
+        let density = model.density(altitude);
+        if !density.is_finite() {
+            return Err(CalculationError::OutOfRange);
+        }
+        let ratio = ((1.0 + e * cos_e) / (1.0 - e * cos_e)).sqrt();
+        da += density * (1.0 + e * cos_e) * ratio;
+        de += density * ratio * cos_e;
+    }
+    // Changes per revolution, divided by the period to get rates
+    let period = 2.0 * PI * (a.powi(3) / body.mu).sqrt();
+    let da_per_rev = -a * a / ballistic_coefficient * da * step;
+    let de_per_rev = -a / ballistic_coefficient * (1.0 - e * e) * de * step;
+    let rates = (da_per_rev / period, de_per_rev / period);
+    if !rates.0.is_finite() || !rates.1.is_finite() {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(rates)
+}
+
+// Propagate the decay of an orbit given by its perigee and apogee altitudes
+// (m) for at most `span` seconds, recording a point every `output_step`
+// seconds plus the re-entry point
+pub fn estimate_lifetime(
+    perigee_altitude: f64,
+    apogee_altitude: f64,
+    body: &CentralBody,
+    model: &DensityModel,
+    ballistic_coefficient: f64,
+    span: f64,
+    output_step: f64,
+) -> Result<LifetimeEstimate, CalculationError> {
+    if apogee_altitude < perigee_altitude || ballistic_coefficient <= 0.0 || span <= 0.0 || output_step <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    if perigee_altitude <= REENTRY_ALTITUDE {
+        return Err(CalculationError::OutOfRange);
+    }
+    let rp = body.equatorial_radius + perigee_altitude;
+    let ra = body.equatorial_radius + apogee_altitude;
+    let mut a = 0.5 * (rp + ra);
+    let mut e = (ra - rp) / (ra + rp);
+
+    let rates = |a: f64, e: f64| decay_rates(a, e, body, model, ballistic_coefficient);
+    let mut history = vec![point(0.0, a, e, body)];
+    let mut time = 0.0;
+    let mut next_output = output_step;
+
+    while time < span {
+        let (k1a, k1e) = rates(a, e)?;
+        // Keep the step small against the decay time scale and the output grid
+        let mut dt = if k1a < 0.0 { MAX_RELATIVE_STEP * a / -k1a } else { span };
+        dt = dt.min(span - time).min(next_output - time).max(1.0);
+
+        let (k2a, k2e) = rates(a + 0.5 * dt * k1a, (e + 0.5 * dt * k1e).max(0.0))?;
+        let (k3a, k3e) = rates(a + 0.5 * dt * k2a, (e + 0.5 * dt * k2e).max(0.0))?;
+        let (k4a, k4e) = rates(a + dt * k3a, (e + dt * k3e).max(0.0))?;
+        let new_a = a + dt / 6.0 * (k1a + 2.0 * k2a + 2.0 * k3a + k4a);
+        let new_e = (e + dt / 6.0 * (k1e + 2.0 * k2e + 2.0 * k3e + k4e)).max(0.0);
+
+        let new_perigee = new_a * (1.0 - new_e) - body.equatorial_radius;
+        if new_perigee <= REENTRY_ALTITUDE {
+            // Interpolate the crossing within the step
+            let perigee = a * (1.0 - e) - body.equatorial_radius;
+            let fraction = ((perigee - REENTRY_ALTITUDE) / (perigee - new_perigee)).clamp(0.0, 1.0);
+            let reentry = time + fraction * dt;
+            let a_reentry = a + fraction * (new_a - a);
+            let e_reentry = e + fraction * (new_e - e);
+            history.push(point(reentry, a_reentry, e_reentry, body));
+            return Ok(LifetimeEstimate { history, lifetime: Some(reentry), span });
+        }
+
+        a = new_a;
+        e = new_e;
+        time += dt;
+        if time >= next_output - 1e-6 {
+            history.push(point(time, a, e, body));
+            next_output += output_step;
+        }
+    }
+    if history.last().map(|p| p.time) != Some(time) {
+        history.push(point(time, a, e, body));
+    }
+    Ok(LifetimeEstimate { history, lifetime: None, span })
+}
+
+pub fn decay_to_csv(points: &[DecayPoint]) -> String {
+    let mut csv = String::from("time_s,time_years,semi_major_axis_m,eccentricity,perigee_altitude_m,apogee_altitude_m\n");
+    for p in points {
+        let _ = writeln!(
+            csv,
+            "{},{:.6},{:.3},{:.8},{:.3},{:.3}",
+            p.time,
+            p.time / SECONDS_PER_YEAR,
+            p.semi_major_axis,
+            p.eccentricity,
+            p.perigee_altitude,
+            p.apogee_altitude
+        );
+    }
+    csv
+}
+
+pub fn write_decay_csv<P: AsRef<Path>>(points: &[DecayPoint], path: P) -> std::io::Result<()> {
+    fs::write(path, decay_to_csv(points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLAR_FLUX: DensityModel = DensityModel::SolarFlux { solar_flux: 150.0, ap: 15.0 };
+
+    #[test]
+    fn gto_decay_stays_finite() {
+        let body = CentralBody::earth();
+        let span = 2.0 * SECONDS_PER_YEAR;
+        let estimate = estimate_lifetime(250e3, 35786e3, &body, &SOLAR_FLUX, 100.0, span, 86400.0).unwrap();
+        for point in &estimate.history {
+            assert!(point.semi_major_axis.is_finite() && point.eccentricity.is_finite());
+        }
+        let last = estimate.history.last().unwrap();
+        assert!(last.apogee_altitude < 35786e3 && last.perigee_altitude > REENTRY_ALTITUDE);
+    }
+
+    #[test]
+    fn low_orbit_reenters() {
+        let body = CentralBody::earth();
+        let estimate =
+            estimate_lifetime(200e3, 200e3, &body, &DensityModel::Exponential, 50.0, SECONDS_PER_YEAR, 86400.0)
+                .unwrap();
+        let days = estimate.lifetime.unwrap() / 86400.0;
+        assert!(days > 1.0 && days < 30.0, "{} days", days);
+        assert_eq!(estimate.complies(DisposalRule::FiveYear), Some(true));
+    }
+
+    #[test]
+    fn compliance_is_undetermined_for_a_short_span() {
+        let body = CentralBody::earth();
+        let estimate =
+            estimate_lifetime(800e3, 800e3, &body, &DensityModel::Exponential, 100.0, SECONDS_PER_YEAR, 86400.0 * 30.0)
+                .unwrap();
+        assert_eq!(estimate.lifetime, None);
+        assert_eq!(estimate.complies(DisposalRule::TwentyFiveYear), None);
+    }
+}
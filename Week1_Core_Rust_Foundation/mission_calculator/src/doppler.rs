@@ -0,0 +1,144 @@
+// Doppler shift and radio tuning tables for ground station passes
+//
+// The range rate between a station and a satellite shifts every carrier by
+// -f * rdot / c. The receiver on the ground tunes to the shifted downlink,
+// and the station transmitter pre-compensates the uplink so the spacecraft
+// receives its nominal frequency:
+//
+//     f_rx = f_down (1 - rdot / c)        f_tx = f_up / (1 - rdot / c)
+//
+// First-order in rdot / c; the neglected relativistic term is below 1e-9 of
+// the carrier for LEO speeds. Range rates come from the same topocentric
+// look angles as pass prediction, without light-time correction.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::frames::{EopTable, FramedState};
+use crate::ground_station::{GroundStation, LookAngles};
+use crate::path_loss::SPEED_OF_LIGHT;
+use crate::time::{Epoch, TimeScale};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const SAMPLE_TOLERANCE: f64 = 1e-6; // s
+
+// Frequency received on the ground from a carrier sent at `frequency`, Hz
+pub fn downlink_frequency(frequency: f64, range_rate: f64) -> f64 {
+    frequency * (1.0 - range_rate / SPEED_OF_LIGHT)
+}
+
+// Frequency to transmit so the spacecraft receives `frequency`, Hz
+pub fn uplink_frequency(frequency: f64, range_rate: f64) -> f64 {
+    frequency / (1.0 - range_rate / SPEED_OF_LIGHT)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DopplerPoint {
+    pub look: LookAngles,
+    pub downlink: Option<f64>, // Hz, receiver tuning
+    pub uplink: Option<f64>,   // Hz, transmitter tuning
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DopplerTable {
+    pub downlink: Option<f64>, // Hz, nominal carrier
+    pub uplink: Option<f64>,   // Hz, nominal carrier
+    pub points: Vec<DopplerPoint>,
+}
+
+impl DopplerTable {
+    // Largest change of range rate between rows, m/s^2; times f / c it gives
+    // the Doppler rate the radios must follow, highest near culmination
+    pub fn max_range_acceleration(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|w| ((w[1].look.range_rate - w[0].look.range_rate) / (w[1].look.time - w[0].look.time)).abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+// Tuning table from `start` to `end` seconds after the propagator's epoch
+// (usually a pass's AOS and LOS) every `step` seconds. `propagate` and
+// `eop` are used as for pass prediction.
+#[allow(clippy::too_many_arguments)]
+pub fn doppler_table<F>(
+    station: &GroundStation,
+    body: &CentralBody,
+    eop: &EopTable,
+    start: f64,
+    end: f64,
+    step: f64,
+    downlink: Option<f64>,
+    uplink: Option<f64>,
+    propagate: F,
+) -> Result<DopplerTable, CalculationError>
+where
+    F: Fn(f64) -> Result<FramedState, CalculationError>,
+{
+    if step <= 0.0 || end < start {
+        return Err(CalculationError::InvalidInput);
+    }
+    if downlink.is_some_and(|f| f <= 0.0) || uplink.is_some_and(|f| f <= 0.0) {
+        return Err(CalculationError::NegativeValue);
+    }
+    let point = |t: f64| -> Result<DopplerPoint, CalculationError> {
+        let state = propagate(t)?;
+        let look = station.look_angles(body, &state, &eop.at(state.julian_date), t)?;
+        Ok(DopplerPoint {
+            look,
+            downlink: downlink.map(|f| downlink_frequency(f, look.range_rate)),
+            uplink: uplink.map(|f| uplink_frequency(f, look.range_rate)),
+        })
+    };
+    // Times are counted from `start` as for ground tracks, with `end`
+    // added when off the grid
+    let mut points = Vec::new();
+    let mut i = 0usize;
+    loop {
+        let t = start + i as f64 * step;
+        if t > end {
+            break;
+        }
+        points.push(point(t)?);
+        i += 1;
+    }
+    if points.last().is_none_or(|p| end - p.look.time > SAMPLE_TOLERANCE) {
+        points.push(point(end)?);
+    }
+    Ok(DopplerTable { downlink, uplink, points })
+}
+
+// CSV tuning table with UTC time stamps, the times being counted from
+// `epoch`. Frequencies are in Hz, empty when the link is not used.
+pub fn doppler_to_csv(table: &DopplerTable, epoch: &Epoch) -> String {
+    let mut csv = String::from("utc,time_s,azimuth_deg,elevation_deg,range_m,range_rate_m_s,");
+    csv.push_str("downlink_hz,downlink_shift_hz,uplink_hz,uplink_shift_hz\n");
+    let frequency = |tuned: Option<f64>, nominal: Option<f64>| match (tuned, nominal) {
+        (Some(tuned), Some(nominal)) => (format!("{:.1}", tuned), format!("{:.1}", tuned - nominal)),
+        _ => (String::new(), String::new()),
+    };
+    for point in &table.points {
+        let look = &point.look;
+        let (downlink, downlink_shift) = frequency(point.downlink, table.downlink);
+        let (uplink, uplink_shift) = frequency(point.uplink, table.uplink);
+        let _ = writeln!(
+            csv,
+            "{},{:.3},{:.4},{:.4},{:.3},{:.4},{},{},{},{}",
+            (*epoch + look.time).to_iso(TimeScale::Utc, 3),
+            look.time,
+            look.azimuth.to_degrees(),
+            look.elevation.to_degrees(),
+            look.range,
+            look.range_rate,
+            downlink,
+            downlink_shift,
+            uplink,
+            uplink_shift
+        );
+    }
+    csv
+}
+
+pub fn write_doppler_csv<P: AsRef<Path>>(table: &DopplerTable, epoch: &Epoch, path: P) -> std::io::Result<()> {
+    fs::write(path, doppler_to_csv(table, epoch))
+}
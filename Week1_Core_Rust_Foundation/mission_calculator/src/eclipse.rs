@@ -0,0 +1,315 @@
+// Solar ephemeris, Earth shadow and eclipse prediction
+//
+// The Sun's geocentric position comes from the low-precision formulas of
+// the Astronomical Almanac (Vallado, algorithm 29), good to about 0.01 deg
+// between 1950 and 2050. The formulas give the mean equator and equinox of
+// date; the position is rotated back to GCRF by the IAU 1976 precession so
+// it matches the propagated satellite states. Two shadow models are
+// available:
+//
+// - Cylindrical: the shadow is a cylinder of the body's radius behind it,
+//   with no penumbra.
+// - Conical: the Sun is a disk of finite size, giving an umbra and a
+//   penumbra in which the visible fraction of the disk is computed from the
+//   overlap of the two apparent disks (Montenbruck & Gill, section 3.4.2).
+//
+// Eclipses are found by sampling the illumination and refining every shadow
+// boundary by bisection, as for ground station passes.
+use crate::CalculationError;
+use crate::elements::StateVector;
+use crate::ephemeris::{ASTRONOMICAL_UNIT, J2000_JD};
+use crate::frames::precession_matrix;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+pub const SUN_RADIUS: f64 = 6.957e8; // m, IAU 2015 nominal
+
+const BOUNDARY_TOLERANCE: f64 = 1e-3; // s
+
+// Geocentric position of the Sun in m at a Julian date, in GCRF
+pub fn sun_position(julian_date: f64) -> Vector3 {
+    // Taking UT for TT moves the precession by about a minute of time,
+    // far below the accuracy of the series
+    let t = (julian_date - J2000_JD) / 36525.0;
+    precession_matrix(t).transpose() * sun_position_of_date(julian_date)
+}
+
+// Geocentric position of the Sun in m in the mean equator and equinox of date
+fn sun_position_of_date(julian_date: f64) -> Vector3 {
+    let t = (julian_date - J2000_JD) / 36525.0;
+    let mean_longitude = 280.460 + 36000.771 * t;
+    let mean_anomaly = (357.5291092 + 35999.05034 * t).to_radians();
+    let longitude = (mean_longitude
+        + 1.914666471 * mean_anomaly.sin()
+        + 0.019994643 * (2.0 * mean_anomaly).sin())
+    .to_radians();
+    let distance = 1.000140612 - 0.016708617 * mean_anomaly.cos() - 0.000139589 * (2.0 * mean_anomaly).cos();
+    let obliquity = (23.439291 - 0.0130042 * t).to_radians();
+
+    let (sin_l, cos_l) = longitude.sin_cos();
+    Vector3::new(cos_l, obliquity.cos() * sin_l, obliquity.sin() * sin_l) * (distance * ASTRONOMICAL_UNIT)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowModel {
+    Cylindrical,
+    Conical,
+}
+
+impl fmt::Display for ShadowModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShadowModel::Cylindrical => write!(f, "cylindrical"),
+            ShadowModel::Conical => write!(f, "conical (umbra/penumbra)"),
+        }
+    }
+}
+
+// Fraction of the solar disk visible from `position` (0 in umbra, 1 in full
+// sunlight), with both vectors relative to the shadowing body's center
+pub fn sunlight_fraction(position: Vector3, sun: Vector3, body_radius: f64, model: ShadowModel) -> f64 {
+    match model {
+        ShadowModel::Cylindrical => {
+            let direction = match sun.normalized() {
+                Some(direction) => direction,
+                None => return 1.0,
+            };
+            let along = position.dot(direction);
+            let across = (position - direction * along).norm();
+            if along < 0.0 && across < body_radius { 0.0 } else { 1.0 }
+        }
+        ShadowModel::Conical => {
+            let to_sun = sun - position;
+            let r = position.norm();
+            let d = to_sun.norm();
+            // Apparent radii of the Sun and the body and their separation
+            let a = (SUN_RADIUS / d).min(1.0).asin();
+            let b = (body_radius / r).min(1.0).asin();
+            let c = ((-position).dot(to_sun) / (r * d)).clamp(-1.0, 1.0).acos();
+            if c >= a + b {
+                1.0
+            } else if c <= b - a {
+                0.0
+            } else if c <= a - b {
+                // The body sits inside the solar disk
+                1.0 - (b * b) / (a * a)
+            } else {
+                let x = (c * c + a * a - b * b) / (2.0 * c);
+                let y = (a * a - x * x).max(0.0).sqrt();
+                let overlap = a * a * (x / a).clamp(-1.0, 1.0).acos() + b * b * ((c - x) / b).clamp(-1.0, 1.0).acos()
+                    - c * y;
+                1.0 - overlap / (PI * a * a)
+            }
+        }
+    }
+}
+
+// Angle between the orbit plane and the Sun direction, positive when the Sun
+// is on the side of the orbit normal
+pub fn beta_angle(state: &StateVector, sun: Vector3) -> Result<f64, CalculationError> {
+    let normal = state.position.cross(state.velocity).normalized().ok_or(CalculationError::InvalidInput)?;
+    let direction = sun.normalized().ok_or(CalculationError::InvalidInput)?;
+    Ok(normal.dot(direction).clamp(-1.0, 1.0).asin())
+}
+
+// One pass through the shadow. With the conical model `entry` and `exit`
+// are the penumbra boundaries and `umbra` the time in full shadow; with the
+// cylindrical model both coincide.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eclipse {
+    pub entry: f64, // s after the epoch
+    pub exit: f64,  // s after the epoch
+    pub umbra: Option<(f64, f64)>,
+}
+
+impl Eclipse {
+    pub fn duration(&self) -> f64 {
+        self.exit - self.entry
+    }
+
+    pub fn umbra_duration(&self) -> f64 {
+        self.umbra.map_or(0.0, |(entry, exit)| exit - entry)
+    }
+}
+
+// Intervals in [start, end] where `inside` holds, sampled every `step` and
+// refined by bisection. Intervals cut by the window start or end there.
+fn intervals<F>(start: f64, end: f64, step: f64, inside: F) -> Result<Vec<(f64, f64)>, CalculationError>
+where
+    F: Fn(f64) -> Result<bool, CalculationError>,
+{
+    let boundary = |mut a: f64, mut b: f64| -> Result<f64, CalculationError> {
+        let a_inside = inside(a)?;
+        while b - a > BOUNDARY_TOLERANCE {
+            let mid = 0.5 * (a + b);
+            if inside(mid)? == a_inside {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        Ok(0.5 * (a + b))
+    };
+
+    let mut found = Vec::new();
+    let mut opened = if inside(start)? { Some(start) } else { None };
+    let mut t = start;
+    while t < end {
+        let next = (t + step).min(end);
+        match (opened, inside(next)?) {
+            (None, true) => opened = Some(boundary(t, next)?),
+            (Some(from), false) => {
+                found.push((from, boundary(t, next)?));
+                opened = None;
+            }
+            _ => {}
+        }
+        t = next;
+    }
+    if let Some(from) = opened {
+        found.push((from, end));
+    }
+    Ok(found)
+}
+
+// Every eclipse of an Earth satellite between `start` and `end` seconds
+// after the epoch `julian_date`. `propagate` returns the inertial state at
+// a time after the epoch, as for pass prediction.
+pub fn find_eclipses<F>(
+    body_radius: f64,
+    julian_date: f64,
+    start: f64,
+    end: f64,
+    step: f64,
+    model: ShadowModel,
+    propagate: F,
+) -> Result<Vec<Eclipse>, CalculationError>
+where
+    F: Fn(f64) -> Result<StateVector, CalculationError>,
+{
+    if step <= 0.0 || end <= start {
+        return Err(CalculationError::InvalidInput);
+    }
+    let fraction = |t: f64| -> Result<f64, CalculationError> {
+        let sun = sun_position(julian_date + t / 86400.0);
+        Ok(sunlight_fraction(propagate(t)?.position, sun, body_radius, model))
+    };
+    let shadows = intervals(start, end, step, |t| Ok(fraction(t)? < 1.0))?;
+    let umbras = intervals(start, end, step, |t| Ok(fraction(t)? <= 0.0))?;
+
+    Ok(shadows
+        .into_iter()
+        .map(|(entry, exit)| Eclipse {
+            entry,
+            exit,
+            umbra: umbras.iter().copied().find(|&(u_entry, u_exit)| u_entry >= entry && u_exit <= exit),
+        })
+        .collect())
+}
+
+// Shadow statistics of one revolution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitIllumination {
+    pub start: f64,       // s after the epoch
+    pub end: f64,         // s after the epoch
+    pub shadow_time: f64, // s in umbra or penumbra
+    pub umbra_time: f64,  // s
+}
+
+impl OrbitIllumination {
+    pub fn sunlit_fraction(&self) -> f64 {
+        1.0 - self.shadow_time / (self.end - self.start)
+    }
+}
+
+fn overlap(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.1.min(b.1) - a.0.max(b.0)).max(0.0)
+}
+
+// Split [start, end] into revolutions of `period` seconds and add up the
+// shadow time within each
+pub fn orbit_illumination(eclipses: &[Eclipse], start: f64, end: f64, period: f64) -> Vec<OrbitIllumination> {
+    let mut orbits = Vec::new();
+    let mut from = start;
+    while period > 0.0 && from < end {
+        let to = (from + period).min(end);
+        let shadow_time = eclipses.iter().map(|e| overlap((from, to), (e.entry, e.exit))).sum();
+        let umbra_time = eclipses.iter().filter_map(|e| e.umbra).map(|u| overlap((from, to), u)).sum();
+        orbits.push(OrbitIllumination { start: from, end: to, shadow_time, umbra_time });
+        from = to;
+    }
+    orbits
+}
+
+// CSV of every eclipse, times in s after the epoch
+pub fn eclipses_to_csv(eclipses: &[Eclipse]) -> String {
+    let mut csv = String::from("eclipse,entry_s,exit_s,duration_s,umbra_entry_s,umbra_exit_s,umbra_duration_s\n");
+    for (i, eclipse) in eclipses.iter().enumerate() {
+        let (umbra_entry, umbra_exit) = match eclipse.umbra {
+            Some((entry, exit)) => (format!("{:.3}", entry), format!("{:.3}", exit)),
+            None => (String::new(), String::new()),
+        };
+        let _ = writeln!(
+            csv,
+            "{},{:.3},{:.3},{:.3},{},{},{:.3}",
+            i + 1,
+            eclipse.entry,
+            eclipse.exit,
+            eclipse.duration(),
+            umbra_entry,
+            umbra_exit,
+            eclipse.umbra_duration()
+        );
+    }
+    csv
+}
+
+pub fn write_eclipses_csv<P: AsRef<Path>>(eclipses: &[Eclipse], path: P) -> std::io::Result<()> {
+    fs::write(path, eclipses_to_csv(eclipses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Longitude on the J2000 ecliptic, rad
+    fn ecliptic_longitude(position: Vector3) -> f64 {
+        let obliquity = 23.439291_f64.to_radians();
+        (position.y * obliquity.cos() + position.z * obliquity.sin()).atan2(position.x)
+    }
+
+    #[test]
+    fn sun_is_in_gcrf() {
+        let at_j2000 = sun_position(J2000_JD);
+        assert!((at_j2000 - sun_position_of_date(J2000_JD)).norm() < 1.0);
+        // Half a century of precession at 50.29 arcsec a year moves the
+        // equinox of date 0.698 deg ahead of the J2000 one
+        let later = J2000_JD + 50.0 * 365.25;
+        let lag = ecliptic_longitude(sun_position_of_date(later)) - ecliptic_longitude(sun_position(later));
+        assert!((lag.to_degrees() - 0.698).abs() < 0.005, "{} deg", lag.to_degrees());
+        assert!((sun_position(later).norm() - sun_position_of_date(later).norm()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sun_distance_follows_the_seasons() {
+        // Perihelion in early January, aphelion in early July
+        let january = sun_position(J2000_JD + 3.0).norm() / ASTRONOMICAL_UNIT;
+        let july = sun_position(J2000_JD + 185.0).norm() / ASTRONOMICAL_UNIT;
+        assert!((january - 0.9833).abs() < 2e-4 && (july - 1.0167).abs() < 2e-4, "{january} AU, {july} AU");
+    }
+
+    #[test]
+    fn cylindrical_shadow() {
+        let sun = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let radius = 6378137.0;
+        assert_eq!(sunlight_fraction(Vector3::new(-7.0e6, 0.0, 0.0), sun, radius, ShadowModel::Cylindrical), 0.0);
+        assert_eq!(sunlight_fraction(Vector3::new(7.0e6, 0.0, 0.0), sun, radius, ShadowModel::Cylindrical), 1.0);
+        assert_eq!(sunlight_fraction(Vector3::new(-7.0e6, 7.0e6, 0.0), sun, radius, ShadowModel::Cylindrical), 1.0);
+        // The conical umbra is narrower than the cylinder
+        let conical = sunlight_fraction(Vector3::new(-4.0e7, 6.3e6, 0.0), sun, radius, ShadowModel::Conical);
+        assert!(conical > 0.0 && conical < 1.0, "{conical}");
+    }
+}
@@ -0,0 +1,459 @@
+// Conversion between Cartesian state vectors and classical orbital elements
+//
+// Elements are stored with the semi-latus rectum p instead of the semi-major
+// axis so that circular, elliptic, parabolic and hyperbolic orbits all have a
+// finite size parameter. Angles are in radians, lengths in m, speeds in m/s.
+//
+// Degenerate geometries follow the usual conventions (Vallado, "Fundamentals
+// of Astrodynamics and Applications", section 2.5):
+// - circular inclined: argument of periapsis is 0, true anomaly holds the
+//   argument of latitude
+// - elliptic equatorial: RAAN is 0, argument of periapsis holds the
+//   longitude of periapsis
+// - circular equatorial: RAAN and argument of periapsis are 0, true anomaly
+//   holds the true longitude
+use crate::CalculationError;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+use std::fmt;
+
+// Below this eccentricity an orbit is treated as circular, below this
+// sin(inclination) as equatorial
+const CIRCULAR_TOLERANCE: f64 = 1e-11;
+const EQUATORIAL_TOLERANCE: f64 = 1e-11;
+// |e - 1| below this is treated as parabolic
+pub const PARABOLIC_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateVector {
+    pub position: Vector3, // m
+    pub velocity: Vector3, // m/s
+}
+
+impl StateVector {
+    pub fn new(position: Vector3, velocity: Vector3) -> Self {
+        StateVector { position, velocity }
+    }
+}
+
+impl fmt::Display for StateVector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "r = {} m, v = {} m/s", self.position, self.velocity)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConicType {
+    Circular,
+    Elliptic,
+    Parabolic,
+    Hyperbolic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    pub semi_latus_rectum: f64, // p = a (1 - e^2), m
+    pub eccentricity: f64,
+    pub inclination: f64,    // rad, [0, pi]
+    pub raan: f64,           // right ascension of the ascending node, rad
+    pub arg_periapsis: f64,  // rad
+    pub true_anomaly: f64,   // rad
+}
+
+impl OrbitalElements {
+    // Build an element set from the semi-major axis (negative for hyperbolas).
+    // Parabolic orbits have no finite semi-major axis; use
+    // `from_semi_latus_rectum` for those.
+    pub fn new(
+        semi_major_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+        raan: f64,
+        arg_periapsis: f64,
+        true_anomaly: f64,
+    ) -> Result<Self, CalculationError> {
+        if eccentricity < 0.0 || (eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE {
+            return Err(CalculationError::InvalidInput);
+        }
+        let p = semi_major_axis * (1.0 - eccentricity * eccentricity);
+        OrbitalElements::from_semi_latus_rectum(p, eccentricity, inclination, raan, arg_periapsis, true_anomaly)
+    }
+
+    pub fn from_semi_latus_rectum(
+        semi_latus_rectum: f64,
+        eccentricity: f64,
+        inclination: f64,
+        raan: f64,
+        arg_periapsis: f64,
+        true_anomaly: f64,
+    ) -> Result<Self, CalculationError> {
+        if !semi_latus_rectum.is_finite() || semi_latus_rectum <= 0.0 || eccentricity < 0.0 || !(0.0..=PI).contains(&inclination) {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(OrbitalElements {
+            semi_latus_rectum,
+            eccentricity,
+            inclination,
+            raan: wrap_two_pi(raan),
+            arg_periapsis: wrap_two_pi(arg_periapsis),
+            true_anomaly: wrap_anomaly(true_anomaly, eccentricity),
+        })
+    }
+
+    // Convert an inertial state vector around a body with gravitational parameter mu
+    pub fn from_state(state: &StateVector, mu: f64) -> Result<Self, CalculationError> {
+        let r = state.position;
+        let v = state.velocity;
+        let r_mag = r.norm();
+        if mu <= 0.0 || r_mag == 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+
+        let h = r.cross(v);
+        let h_mag = h.norm();
+        // Radial (rectilinear) trajectories have no orbital plane
+        if h_mag <= 1e-12 * r_mag * v.norm() {
+            return Err(CalculationError::InvalidInput);
+        }
+        let h_hat = h / h_mag;
+
+        let node = Vector3::new(-h.y, h.x, 0.0);
+        let e_vec = (r * (v.dot(v) - mu / r_mag) - v * r.dot(v)) / mu;
+        let e = e_vec.norm();
+        let p = h_mag * h_mag / mu;
+        let inclination = (h.z / h_mag).clamp(-1.0, 1.0).acos();
+
+        let equatorial = node.norm() / h_mag < EQUATORIAL_TOLERANCE;
+        let circular = e < CIRCULAR_TOLERANCE;
+
+        // Reference directions: ascending node (x axis when equatorial) and
+        // periapsis (the node itself when circular)
+        let x_axis = Vector3::new(1.0, 0.0, 0.0);
+        let node_dir = if equatorial { x_axis } else { node / node.norm() };
+        let periapsis_dir = if circular { node_dir } else { e_vec / e };
+
+        let raan = if equatorial { 0.0 } else { node.y.atan2(node.x) };
+        let arg_periapsis = signed_angle(node_dir, periapsis_dir, h_hat);
+        let true_anomaly = signed_angle(periapsis_dir, r, h_hat);
+
+        Ok(OrbitalElements {
+            semi_latus_rectum: p,
+            eccentricity: if circular { 0.0 } else { e },
+            inclination,
+            raan: wrap_two_pi(raan),
+            arg_periapsis: if circular { 0.0 } else { wrap_two_pi(arg_periapsis) },
+            true_anomaly: wrap_anomaly(true_anomaly, e),
+        })
+    }
+
+    // Convert back to an inertial state vector
+    pub fn to_state(&self, mu: f64) -> Result<StateVector, CalculationError> {
+        if mu <= 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        let p = self.semi_latus_rectum;
+        let e = self.eccentricity;
+        let (sin_nu, cos_nu) = self.true_anomaly.sin_cos();
+        let denominator = 1.0 + e * cos_nu;
+        // Open orbits only exist between the asymptotes
+        if denominator <= 0.0 {
+            return Err(CalculationError::OutOfRange);
+        }
+
+        let r = p / denominator;
+        let position_pqw = Vector3::new(r * cos_nu, r * sin_nu, 0.0);
+        let speed_factor = (mu / p).sqrt();
+        let velocity_pqw = Vector3::new(-speed_factor * sin_nu, speed_factor * (e + cos_nu), 0.0);
+
+        Ok(StateVector::new(
+            perifocal_to_inertial(position_pqw, self.raan, self.inclination, self.arg_periapsis),
+            perifocal_to_inertial(velocity_pqw, self.raan, self.inclination, self.arg_periapsis),
+        ))
+    }
+
+    pub fn conic(&self) -> ConicType {
+        let e = self.eccentricity;
+        if e < CIRCULAR_TOLERANCE {
+            ConicType::Circular
+        } else if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+            ConicType::Parabolic
+        } else if e < 1.0 {
+            ConicType::Elliptic
+        } else {
+            ConicType::Hyperbolic
+        }
+    }
+
+    // Semi-major axis in m: infinite for parabolas, negative for hyperbolas
+    pub fn semi_major_axis(&self) -> f64 {
+        if self.conic() == ConicType::Parabolic {
+            return f64::INFINITY;
+        }
+        self.semi_latus_rectum / (1.0 - self.eccentricity * self.eccentricity)
+    }
+
+    pub fn periapsis_radius(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 + self.eccentricity)
+    }
+
+    // Apoapsis radius in m, infinite for open orbits
+    pub fn apoapsis_radius(&self) -> f64 {
+        if self.eccentricity >= 1.0 - PARABOLIC_TOLERANCE {
+            return f64::INFINITY;
+        }
+        self.semi_latus_rectum / (1.0 - self.eccentricity)
+    }
+
+    // Orbital period in s, None for open orbits
+    pub fn period(&self, mu: f64) -> Option<f64> {
+        match self.conic() {
+            ConicType::Circular | ConicType::Elliptic => Some(2.0 * PI * (self.semi_major_axis().powi(3) / mu).sqrt()),
+            ConicType::Parabolic | ConicType::Hyperbolic => None,
+        }
+    }
+
+    // Eccentric anomaly E for ellipses, hyperbolic anomaly F for hyperbolas
+    // and parabolic anomaly D = tan(nu / 2) for parabolas
+    pub fn eccentric_anomaly(&self) -> f64 {
+        true_to_eccentric_anomaly(self.true_anomaly, self.eccentricity)
+    }
+
+    // Mean anomaly from Kepler's equation (Barker's equation for parabolas)
+    pub fn mean_anomaly(&self) -> f64 {
+        let e = self.eccentricity;
+        let anomaly = self.eccentric_anomaly();
+        match self.conic() {
+            ConicType::Circular | ConicType::Elliptic => wrap_two_pi(anomaly - e * anomaly.sin()),
+            ConicType::Parabolic => anomaly + anomaly.powi(3) / 3.0,
+            ConicType::Hyperbolic => e * anomaly.sinh() - anomaly,
+        }
+    }
+}
+
+impl fmt::Display for OrbitalElements {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let a = self.semi_major_axis();
+        if a.is_finite() {
+            writeln!(f, "Semi-major axis:         {:.3} km", a / 1000.0)?;
+        } else {
+            writeln!(f, "Semi-major axis:         infinite (parabolic)")?;
+        }
+        writeln!(f, "Semi-latus rectum:       {:.3} km", self.semi_latus_rectum / 1000.0)?;
+        writeln!(f, "Eccentricity:            {:.8}", self.eccentricity)?;
+        writeln!(f, "Inclination:             {:.6} deg", self.inclination.to_degrees())?;
+        writeln!(f, "RAAN:                    {:.6} deg", self.raan.to_degrees())?;
+        writeln!(f, "Argument of periapsis:   {:.6} deg", self.arg_periapsis.to_degrees())?;
+        writeln!(f, "True anomaly:            {:.6} deg", self.true_anomaly.to_degrees())?;
+        let anomaly_name = match self.conic() {
+            ConicType::Circular | ConicType::Elliptic => "Eccentric anomaly:       ",
+            ConicType::Parabolic => "Parabolic anomaly D:     ",
+            ConicType::Hyperbolic => "Hyperbolic anomaly:      ",
+        };
+        match self.conic() {
+            ConicType::Parabolic => writeln!(f, "{}{:.6}", anomaly_name, self.eccentric_anomaly())?,
+            _ => writeln!(f, "{}{:.6} deg", anomaly_name, self.eccentric_anomaly().to_degrees())?,
+        }
+        match self.conic() {
+            ConicType::Parabolic => write!(f, "Mean anomaly (Barker):   {:.6}", self.mean_anomaly()),
+            _ => write!(f, "Mean anomaly:            {:.6} deg", self.mean_anomaly().to_degrees()),
+        }
+    }
+}
+
+// Eccentric (elliptic), hyperbolic or parabolic anomaly from the true anomaly
+pub fn true_to_eccentric_anomaly(true_anomaly: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity;
+    let nu = true_anomaly;
+    if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+        (nu / 2.0).tan()
+    } else if e < 1.0 {
+        let anomaly = ((1.0 - e * e).sqrt() * nu.sin()).atan2(e + nu.cos());
+        wrap_two_pi(anomaly)
+    } else {
+        let nu = wrap_pi(nu);
+        2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (nu / 2.0).tan()).atanh()
+    }
+}
+
+// Inverse of `true_to_eccentric_anomaly`
+pub fn eccentric_to_true_anomaly(anomaly: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity;
+    if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+        2.0 * anomaly.atan()
+    } else if e < 1.0 {
+        let nu = ((1.0 - e * e).sqrt() * anomaly.sin()).atan2(anomaly.cos() - e);
+        wrap_two_pi(nu)
+    } else {
+        2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (anomaly / 2.0).tanh()).atan()
+    }
+}
+
+// Rotate a perifocal (PQW) vector into the inertial frame: R3(-raan) R1(-i) R3(-argp)
+fn perifocal_to_inertial(v: Vector3, raan: f64, inclination: f64, arg_periapsis: f64) -> Vector3 {
+    let (sin_o, cos_o) = raan.sin_cos();
+    let (sin_i, cos_i) = inclination.sin_cos();
+    let (sin_w, cos_w) = arg_periapsis.sin_cos();
+
+    let r11 = cos_o * cos_w - sin_o * sin_w * cos_i;
+    let r12 = -cos_o * sin_w - sin_o * cos_w * cos_i;
+    let r21 = sin_o * cos_w + cos_o * sin_w * cos_i;
+    let r22 = -sin_o * sin_w + cos_o * cos_w * cos_i;
+    let r31 = sin_w * sin_i;
+    let r32 = cos_w * sin_i;
+
+    Vector3::new(
+        r11 * v.x + r12 * v.y,
+        r21 * v.x + r22 * v.y,
+        r31 * v.x + r32 * v.y,
+    )
+}
+
+// Angle from `from` to `to`, measured positive about the unit vector `axis`
+fn signed_angle(from: Vector3, to: Vector3, axis: Vector3) -> f64 {
+    axis.dot(from.cross(to)).atan2(from.dot(to))
+}
+
+pub fn wrap_two_pi(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(2.0 * PI);
+    // rem_euclid can round up to exactly 2 pi for tiny negative inputs
+    if wrapped >= 2.0 * PI { 0.0 } else { wrapped }
+}
+
+pub fn wrap_pi(angle: f64) -> f64 {
+    let wrapped = wrap_two_pi(angle);
+    if wrapped > PI { wrapped - 2.0 * PI } else { wrapped }
+}
+
+// Closed orbits use [0, 2 pi), open orbits (-pi, pi] so that the anomaly
+// stays between the asymptotes
+fn wrap_anomaly(angle: f64, eccentricity: f64) -> f64 {
+    if eccentricity >= 1.0 - PARABOLIC_TOLERANCE {
+        wrap_pi(angle)
+    } else {
+        wrap_two_pi(angle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 3.986004418e14; // m^3/s^2, Earth
+
+    fn deg(angle: f64) -> f64 {
+        angle.to_degrees()
+    }
+
+    fn assert_states_close(a: &StateVector, b: &StateVector) {
+        assert!((a.position - b.position).norm() < 1e-6, "{} vs {}", a, b);
+        assert!((a.velocity - b.velocity).norm() < 1e-9, "{} vs {}", a, b);
+    }
+
+    #[test]
+    fn vallado_example_2_5() {
+        let state = StateVector::new(
+            Vector3::new(6524.834e3, 6862.875e3, 6448.296e3),
+            Vector3::new(4.901327e3, 5.533756e3, -1.976341e3),
+        );
+        let elements = OrbitalElements::from_state(&state, MU).unwrap();
+        assert!((elements.semi_latus_rectum / 1000.0 - 11067.790).abs() < 1e-2);
+        assert!((elements.semi_major_axis() / 1000.0 - 36127.343).abs() < 1e-1);
+        assert!((elements.eccentricity - 0.832853).abs() < 1e-6);
+        assert!((deg(elements.inclination) - 87.870).abs() < 1e-3);
+        assert!((deg(elements.raan) - 227.898).abs() < 1e-3);
+        assert!((deg(elements.arg_periapsis) - 53.38).abs() < 1e-2);
+        assert!((deg(elements.true_anomaly) - 92.335).abs() < 1e-3);
+        assert_states_close(&elements.to_state(MU).unwrap(), &state);
+    }
+
+    #[test]
+    fn round_trips_elliptic_and_hyperbolic() {
+        let cases = [
+            OrbitalElements::new(7000e3, 0.01, 0.9, 1.0, 2.0, 3.0).unwrap(),
+            OrbitalElements::new(26560e3, 0.74, 1.1, 4.0, 4.7, 0.2).unwrap(),
+            OrbitalElements::new(-20000e3, 1.8, 2.5, 0.3, 1.2, -0.9).unwrap(),
+        ];
+        for elements in cases {
+            let state = elements.to_state(MU).unwrap();
+            let back = OrbitalElements::from_state(&state, MU).unwrap();
+            assert!((back.semi_latus_rectum - elements.semi_latus_rectum).abs() < 1e-6);
+            assert!((back.eccentricity - elements.eccentricity).abs() < 1e-12);
+            for (x, y) in [
+                (back.inclination, elements.inclination),
+                (back.raan, elements.raan),
+                (back.arg_periapsis, elements.arg_periapsis),
+                (back.true_anomaly, elements.true_anomaly),
+            ] {
+                assert!(wrap_pi(x - y).abs() < 1e-10, "{} vs {}", x, y);
+            }
+            assert_states_close(&back.to_state(MU).unwrap(), &state);
+        }
+    }
+
+    #[test]
+    fn circular_inclined_keeps_the_argument_of_latitude() {
+        let r = 7000e3;
+        let speed = (MU / r).sqrt();
+        let (raan, inclination, latitude_argument) = (0.5f64, 0.9f64, 1.2f64);
+        // Position at the argument of latitude in the orbital plane
+        let node = Vector3::new(raan.cos(), raan.sin(), 0.0);
+        let normal = Vector3::new(raan.sin() * inclination.sin(), -raan.cos() * inclination.sin(), inclination.cos());
+        let in_plane = normal.cross(node);
+        let direction = node * latitude_argument.cos() + in_plane * latitude_argument.sin();
+        let velocity = normal.cross(direction) * speed;
+        let elements = OrbitalElements::from_state(&StateVector::new(direction * r, velocity), MU).unwrap();
+        assert_eq!(elements.conic(), ConicType::Circular);
+        assert_eq!(elements.arg_periapsis, 0.0);
+        assert!((elements.raan - raan).abs() < 1e-12);
+        assert!((elements.inclination - inclination).abs() < 1e-12);
+        assert!((elements.true_anomaly - latitude_argument).abs() < 1e-12);
+    }
+
+    #[test]
+    fn equatorial_orbits_measure_from_the_x_axis() {
+        // Elliptic equatorial: the argument of periapsis is the longitude of periapsis
+        let elements = OrbitalElements::new(8000e3, 0.1, 0.0, 0.0, 0.7, 0.4).unwrap();
+        let back = OrbitalElements::from_state(&elements.to_state(MU).unwrap(), MU).unwrap();
+        assert_eq!(back.raan, 0.0);
+        assert!((back.arg_periapsis - 0.7).abs() < 1e-10);
+        assert!((back.true_anomaly - 0.4).abs() < 1e-10);
+
+        // Circular equatorial: the true anomaly is the true longitude
+        let state = StateVector::new(Vector3::new(0.0, -7000e3, 0.0), Vector3::new((MU / 7000e3).sqrt(), 0.0, 0.0));
+        let back = OrbitalElements::from_state(&state, MU).unwrap();
+        assert_eq!((back.eccentricity, back.raan, back.arg_periapsis), (0.0, 0.0, 0.0));
+        assert!((back.true_anomaly - 1.5 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parabolic_orbit_at_escape_speed() {
+        let r = 7000e3;
+        let state = StateVector::new(Vector3::new(r, 0.0, 0.0), Vector3::new(0.0, (2.0 * MU / r).sqrt(), 0.0));
+        let elements = OrbitalElements::from_state(&state, MU).unwrap();
+        assert_eq!(elements.conic(), ConicType::Parabolic);
+        assert!((elements.semi_latus_rectum - 2.0 * r).abs() < 1e-6);
+        assert_eq!(elements.semi_major_axis(), f64::INFINITY);
+        assert_eq!(elements.period(MU), None);
+        assert!(elements.true_anomaly.abs() < 1e-12);
+
+        let later = OrbitalElements::from_semi_latus_rectum(2.0 * r, 1.0, 0.0, 0.0, 0.0, PI / 2.0).unwrap();
+        assert!((later.eccentric_anomaly() - 1.0).abs() < 1e-12);
+        assert!((later.mean_anomaly() - 4.0 / 3.0).abs() < 1e-12);
+        // Beyond the asymptote there is no point on the conic
+        let beyond = OrbitalElements::from_semi_latus_rectum(2.0 * r, 1.0, 0.0, 0.0, 0.0, PI).unwrap();
+        assert_eq!(beyond.to_state(MU), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn anomaly_conversions_invert() {
+        for e in [0.0, 0.3, 0.95, 1.0, 1.5, 4.0] {
+            for nu in [-2.0, -0.5, 0.0, 0.5, 2.0] {
+                if e > 1.0 && 1.0 + e * f64::cos(nu) <= 0.0 {
+                    continue;
+                }
+                let back = eccentric_to_true_anomaly(true_to_eccentric_anomaly(nu, e), e);
+                assert!(wrap_pi(back - nu).abs() < 1e-12, "e = {}, nu = {}: {}", e, nu, back);
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+// Approximate heliocentric planet positions
+//
+// Uses the JPL "Keplerian Elements for Approximate Positions of the Major
+// Planets" (E. M. Standish), table 1, valid from 1800 to 2050 AD. Positions
+// are in the J2000 ecliptic frame, good to a few thousand km for the inner
+// planets, which is enough for mission design sweeps.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::elements::{OrbitalElements, StateVector};
+use crate::propagate::mean_to_true_anomaly;
+use crate::time::{Epoch, TimeScale};
+use std::fmt;
+
+pub const ASTRONOMICAL_UNIT: f64 = 1.495978707e11; // m
+pub const J2000_JD: f64 = 2451545.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Earth, // Earth-Moon barycenter
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+}
+
+impl Planet {
+    pub const ALL: [Planet; 9] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Earth,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+        Planet::Uranus,
+        Planet::Neptune,
+        Planet::Pluto,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Planet::Mercury => "Mercury",
+            Planet::Venus => "Venus",
+            Planet::Earth => "Earth",
+            Planet::Mars => "Mars",
+            Planet::Jupiter => "Jupiter",
+            Planet::Saturn => "Saturn",
+            Planet::Uranus => "Uranus",
+            Planet::Neptune => "Neptune",
+            Planet::Pluto => "Pluto",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Planet> {
+        Planet::ALL.iter().copied().find(|p| p.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn body(&self) -> CentralBody {
+        match self {
+            Planet::Mercury => CentralBody::mercury(),
+            Planet::Venus => CentralBody::venus(),
+            Planet::Earth => CentralBody::earth(),
+            Planet::Mars => CentralBody::mars(),
+            Planet::Jupiter => CentralBody::jupiter(),
+            Planet::Saturn => CentralBody::saturn(),
+            Planet::Uranus => CentralBody::uranus(),
+            Planet::Neptune => CentralBody::neptune(),
+            Planet::Pluto => CentralBody::pluto(),
+        }
+    }
+
+    // a (au), e, I (deg), L (deg), longitude of perihelion (deg),
+    // longitude of the ascending node (deg), then their rates per century
+    fn elements(&self) -> ([f64; 6], [f64; 6]) {
+        match self {
+            Planet::Mercury => (
+                [0.38709927, 0.20563593, 7.00497902, 252.25032350, 77.45779628, 48.33076593],
+                [0.00000037, 0.00001906, -0.00594749, 149472.67411175, 0.16047689, -0.12534081],
+            ),
+            Planet::Venus => (
+                [0.72333566, 0.00677672, 3.39467605, 181.97909950, 131.60246718, 76.67984255],
+                [0.00000390, -0.00004107, -0.00078890, 58517.81538729, 0.00268329, -0.27769418],
+            ),
+            Planet::Earth => (
+                [1.00000261, 0.01671123, -0.00001531, 100.46457166, 102.93768193, 0.0],
+                [0.00000562, -0.00004392, -0.01294668, 35999.37244981, 0.32327364, 0.0],
+            ),
+            Planet::Mars => (
+                [1.52371034, 0.09339410, 1.84969142, -4.55343205, -23.94362959, 49.55953891],
+                [0.00001847, 0.00007882, -0.00813131, 19140.30268499, 0.44441088, -0.29257343],
+            ),
+            Planet::Jupiter => (
+                [5.20288700, 0.04838624, 1.30439695, 34.39644051, 14.72847983, 100.47390909],
+                [-0.00011607, -0.00013253, -0.00183714, 3034.74612775, 0.21252668, 0.20469106],
+            ),
+            Planet::Saturn => (
+                [9.53667594, 0.05386179, 2.48599187, 49.95424423, 92.59887831, 113.66242448],
+                [-0.00125060, -0.00050991, 0.00193609, 1222.49362201, -0.41897216, -0.28867794],
+            ),
+            Planet::Uranus => (
+                [19.18916464, 0.04725744, 0.77263783, 313.23810451, 170.95427630, 74.01692503],
+                [-0.00196176, -0.00004397, -0.00242939, 428.48202785, 0.40805281, 0.04240589],
+            ),
+            Planet::Neptune => (
+                [30.06992276, 0.00859048, 1.77004347, -55.12002969, 44.96476227, 131.78422574],
+                [0.00026291, 0.00005105, 0.00035372, 218.45945325, -0.32241464, -0.00508664],
+            ),
+            Planet::Pluto => (
+                [39.48211675, 0.24882730, 17.14001206, 238.92903833, 224.06891629, 110.30393684],
+                [-0.00031596, 0.00005170, 0.00004818, 145.20780515, -0.04062942, -0.01183482],
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Planet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Heliocentric osculating elements of a planet at a Julian date
+pub fn planet_elements(planet: Planet, julian_date: f64) -> Result<OrbitalElements, CalculationError> {
+    let t = (julian_date - J2000_JD) / 36525.0;
+    let (base, rate) = planet.elements();
+    let value = |k: usize| base[k] + rate[k] * t;
+
+    let a = value(0) * ASTRONOMICAL_UNIT;
+    let e = value(1);
+    let inclination = value(2).to_radians();
+    let mean_longitude = value(3).to_radians();
+    let long_perihelion = value(4).to_radians();
+    let long_node = value(5).to_radians();
+
+    let arg_perihelion = long_perihelion - long_node;
+    let mean_anomaly = mean_longitude - long_perihelion;
+    let true_anomaly = mean_to_true_anomaly(mean_anomaly, e)?;
+
+    // The Earth-Moon barycenter inclination dips slightly negative; fold it
+    // into the equivalent positive inclination
+    let (inclination, long_node, arg_perihelion) = if inclination < 0.0 {
+        (-inclination, long_node + std::f64::consts::PI, arg_perihelion + std::f64::consts::PI)
+    } else {
+        (inclination, long_node, arg_perihelion)
+    };
+
+    OrbitalElements::new(a, e, inclination, long_node, arg_perihelion, true_anomaly)
+}
+
+// Heliocentric J2000 ecliptic state of a planet at a Julian date
+pub fn planet_state(planet: Planet, julian_date: f64) -> Result<StateVector, CalculationError> {
+    planet_elements(planet, julian_date)?.to_state(CentralBody::sun().mu)
+}
+
+// Calendar date and UTC time "YYYY-MM-DD HH:MM:SS" of a Julian date
+pub fn format_date_time(julian_date: f64) -> String {
+    let iso = Epoch::from_julian_date(julian_date, TimeScale::Utc).to_iso(TimeScale::Utc, 0);
+    iso.replacen('T', " ", 1).trim_end_matches('Z').to_string()
+}
@@ -0,0 +1,717 @@
+// Earth reference frames and the transformations between them
+//
+// Follows the IAU-76/FK5 reduction (Vallado, section 3.7): the celestial
+// frame GCRF (taken equal to J2000, ignoring the 20 mas frame bias) is
+// carried to the mean equator and equinox of date by the IAU 1976
+// precession, to the true equator and equinox of date by the IAU 1980
+// nutation, to the pseudo Earth-fixed frame by Greenwich apparent sidereal
+// time and to ITRF (ECEF) by polar motion. SGP4's TEME frame sits between
+// the true of date frame and PEF, rotated from the first by the equation of
+// the equinoxes. The nutation series keeps the 30 largest terms of the 106
+// (amplitudes above 1.5 mas), which is well below the accuracy of the
+// propagators in this crate.
+//
+// Earth orientation parameters (polar motion, UT1-UTC, length of day and
+// nutation corrections) can be read from a CelesTrak EOP CSV file; without
+// them the rotation runs on UTC with no polar motion, as the rest of the
+// crate does. Times are Julian dates in UTC.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::elements::{StateVector, wrap_two_pi};
+use crate::ephemeris::J2000_JD;
+use crate::time::{MJD_OFFSET, TT_MINUS_TAI, tai_minus_utc};
+use crate::vector::{Matrix3, Vector3};
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+pub const EARTH_ROTATION_RATE: f64 = 7.292115146706979e-5; // rad/s, nominal
+
+const ARCSEC: f64 = PI / 648000.0; // rad
+
+const GEODETIC_ITERATIONS: usize = 10;
+const GEODETIC_TOLERANCE: f64 = 1e-12; // rad
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+    Gcrf,
+    MeanOfDate,
+    TrueOfDate,
+    Teme,
+    Pef,
+    Itrf,
+}
+
+impl Frame {
+    pub const ALL: [Frame; 6] =
+        [Frame::Gcrf, Frame::MeanOfDate, Frame::TrueOfDate, Frame::Teme, Frame::Pef, Frame::Itrf];
+
+    // Frames that turn with the Earth
+    pub fn is_rotating(&self) -> bool {
+        matches!(self, Frame::Pef | Frame::Itrf)
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Frame::Gcrf => "GCRF/J2000",
+            Frame::MeanOfDate => "MOD",
+            Frame::TrueOfDate => "TOD",
+            Frame::Teme => "TEME",
+            Frame::Pef => "PEF",
+            Frame::Itrf => "ITRF/ECEF",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Earth orientation parameters for one UTC day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EopRecord {
+    pub mjd: f64,           // UTC
+    pub x_pole: f64,        // rad
+    pub y_pole: f64,        // rad
+    pub ut1_minus_utc: f64, // s
+    pub length_of_day: f64, // s, excess over 86400 s
+    pub dpsi: f64,          // rad, correction to the IAU 1980 nutation in longitude
+    pub deps: f64,          // rad, correction to the IAU 1980 nutation in obliquity
+    pub tai_minus_utc: f64, // s
+}
+
+impl EopRecord {
+    // No corrections: UT1 taken as UTC, no polar motion, and TAI-UTC from
+    // the leap second table
+    pub fn nominal(mjd: f64) -> Self {
+        EopRecord {
+            mjd,
+            x_pole: 0.0,
+            y_pole: 0.0,
+            ut1_minus_utc: 0.0,
+            length_of_day: 0.0,
+            dpsi: 0.0,
+            deps: 0.0,
+            tai_minus_utc: tai_minus_utc(mjd),
+        }
+    }
+}
+
+// Errors raised while reading an EOP file. `line` is the line number in
+// the file, starting at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EopError {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for EopError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EopError::Io(message) => write!(f, "could not read EOP file: {}", message),
+            EopError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for EopError {}
+
+// Daily Earth orientation parameters sorted by date
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EopTable {
+    records: Vec<EopRecord>,
+}
+
+impl EopTable {
+    pub fn new(mut records: Vec<EopRecord>) -> Self {
+        records.sort_by(|a, b| a.mjd.total_cmp(&b.mjd));
+        EopTable { records }
+    }
+
+    pub fn records(&self) -> &[EopRecord] {
+        &self.records
+    }
+
+    // Parameters at a UTC Julian date, interpolated linearly between days.
+    // UT1-UTC is interpolated as UT1-TAI so leap seconds do not smear into
+    // the neighboring day. Dates outside the table take the nearest day and
+    // an empty table gives the nominal values.
+    pub fn at(&self, julian_date: f64) -> EopRecord {
+        let mjd = julian_date - MJD_OFFSET;
+        let (first, last) = match (self.records.first(), self.records.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return EopRecord::nominal(mjd),
+        };
+        if mjd <= first.mjd {
+            return EopRecord { mjd, ..first };
+        }
+        if mjd >= last.mjd {
+            return EopRecord { mjd, ..last };
+        }
+        let i = self.records.partition_point(|r| r.mjd <= mjd) - 1;
+        let (a, b) = (self.records[i], self.records[i + 1]);
+        let k = (mjd - a.mjd) / (b.mjd - a.mjd);
+        let lerp = |x: f64, y: f64| x + (y - x) * k;
+        let ut1_minus_tai = lerp(a.ut1_minus_utc - a.tai_minus_utc, b.ut1_minus_utc - b.tai_minus_utc);
+        EopRecord {
+            mjd,
+            x_pole: lerp(a.x_pole, b.x_pole),
+            y_pole: lerp(a.y_pole, b.y_pole),
+            ut1_minus_utc: ut1_minus_tai + a.tai_minus_utc,
+            length_of_day: lerp(a.length_of_day, b.length_of_day),
+            dpsi: lerp(a.dpsi, b.dpsi),
+            deps: lerp(a.deps, b.deps),
+            tai_minus_utc: a.tai_minus_utc,
+        }
+    }
+}
+
+// Parse a CelesTrak EOP CSV file (columns DATE, MJD, X, Y, UT1-UTC, LOD,
+// DPSI, DEPS, DX, DY, DAT, DATA_TYPE). Columns are found by their header
+// name; MJD, X, Y and UT1-UTC are required, DAT defaults to the leap
+// second table and the others to zero when missing or empty. Angles are in
+// arcsec and times in s.
+pub fn parse_eop(text: &str) -> Result<EopTable, EopError> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let (header_line, header): (usize, Vec<String>) = match lines.next() {
+        Some((index, line)) => (index + 1, line.split(',').map(|h| h.trim().to_ascii_uppercase()).collect()),
+        None => return Ok(EopTable::default()),
+    };
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let required = |name: &str| {
+        column(name)
+            .ok_or_else(|| EopError::Parse { line: header_line, message: format!("missing column '{}'", name) })
+    };
+    let (mjd, x, y, dut1) = (required("MJD")?, required("X")?, required("Y")?, required("UT1-UTC")?);
+    let (lod, dpsi, deps, dat) = (column("LOD"), column("DPSI"), column("DEPS"), column("DAT"));
+
+    let mut records = Vec::new();
+    for (index, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let value = |column: Option<usize>, default: f64| -> Result<f64, EopError> {
+            match column.and_then(|c| fields.get(c)) {
+                Some(text) if !text.is_empty() => text.parse().map_err(|_| EopError::Parse {
+                    line: index + 1,
+                    message: format!("invalid number '{}'", text),
+                }),
+                _ => Ok(default),
+            }
+        };
+        let needed = |column: usize| -> Result<f64, EopError> {
+            match fields.get(column) {
+                Some(text) if !text.is_empty() => value(Some(column), 0.0),
+                _ => Err(EopError::Parse { line: index + 1, message: format!("missing '{}'", header[column]) }),
+            }
+        };
+        let day = needed(mjd)?;
+        records.push(EopRecord {
+            mjd: day,
+            x_pole: needed(x)? * ARCSEC,
+            y_pole: needed(y)? * ARCSEC,
+            ut1_minus_utc: needed(dut1)?,
+            length_of_day: value(lod, 0.0)?,
+            dpsi: value(dpsi, 0.0)? * ARCSEC,
+            deps: value(deps, 0.0)? * ARCSEC,
+            tai_minus_utc: value(dat, tai_minus_utc(day))?,
+        });
+    }
+    Ok(EopTable::new(records))
+}
+
+pub fn load_eop_file<P: AsRef<Path>>(path: P) -> Result<EopTable, EopError> {
+    let text = fs::read_to_string(path).map_err(|e| EopError::Io(e.to_string()))?;
+    parse_eop(&text)
+}
+
+// Julian centuries of TT since J2000 at a UTC Julian date
+pub fn tt_centuries(julian_date: f64, eop: &EopRecord) -> f64 {
+    let tt = julian_date + (eop.tai_minus_utc + TT_MINUS_TAI) / 86400.0;
+    (tt - J2000_JD) / 36525.0
+}
+
+// IAU 1976 precession angles zeta, theta and z in rad
+pub fn precession_angles(t: f64) -> (f64, f64, f64) {
+    let zeta = 2306.2181 * t + 0.30188 * t * t + 0.017998 * t.powi(3);
+    let theta = 2004.3109 * t - 0.42665 * t * t - 0.041833 * t.powi(3);
+    let z = 2306.2181 * t + 1.09468 * t * t + 0.018203 * t.powi(3);
+    (zeta * ARCSEC, theta * ARCSEC, z * ARCSEC)
+}
+
+// Rotation from GCRF to the mean equator and equinox of date
+pub fn precession_matrix(t: f64) -> Matrix3 {
+    let (zeta, theta, z) = precession_angles(t);
+    Matrix3::rotation_z(-z) * Matrix3::rotation_y(theta) * Matrix3::rotation_z(-zeta)
+}
+
+// Multipliers of the Moon's and Sun's mean anomalies, the Moon's argument
+// of latitude, the elongation and the node, then the longitude and
+// obliquity coefficients in 0.1 mas and their rates per century
+#[rustfmt::skip]
+const NUTATION_TERMS: [([f64; 5], f64, f64, f64, f64); 30] = [
+    ([0.0, 0.0, 0.0, 0.0, 1.0], -171996.0, -174.2, 92025.0, 8.9),
+    ([0.0, 0.0, 2.0, -2.0, 2.0], -13187.0, -1.6, 5736.0, -3.1),
+    ([0.0, 0.0, 2.0, 0.0, 2.0], -2274.0, -0.2, 977.0, -0.5),
+    ([0.0, 0.0, 0.0, 0.0, 2.0], 2062.0, 0.2, -895.0, 0.5),
+    ([0.0, 1.0, 0.0, 0.0, 0.0], 1426.0, -3.4, 54.0, -0.1),
+    ([1.0, 0.0, 0.0, 0.0, 0.0], 712.0, 0.1, -7.0, 0.0),
+    ([0.0, 1.0, 2.0, -2.0, 2.0], -517.0, 1.2, 224.0, -0.6),
+    ([0.0, 0.0, 2.0, 0.0, 1.0], -386.0, -0.4, 200.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 2.0], -301.0, 0.0, 129.0, -0.1),
+    ([0.0, -1.0, 2.0, -2.0, 2.0], 217.0, -0.5, -95.0, 0.3),
+    ([1.0, 0.0, 0.0, -2.0, 0.0], -158.0, 0.0, -1.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 1.0], 129.0, 0.1, -70.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 2.0], 123.0, 0.0, -53.0, 0.0),
+    ([1.0, 0.0, 0.0, 0.0, 1.0], 63.0, 0.1, -33.0, 0.0),
+    ([0.0, 0.0, 0.0, 2.0, 0.0], 63.0, 0.0, -2.0, 0.0),
+    ([-1.0, 0.0, 2.0, 2.0, 2.0], -59.0, 0.0, 26.0, 0.0),
+    ([-1.0, 0.0, 0.0, 0.0, 1.0], -58.0, -0.1, 32.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 1.0], -51.0, 0.0, 27.0, 0.0),
+    ([2.0, 0.0, 0.0, -2.0, 0.0], 48.0, 0.0, 1.0, 0.0),
+    ([-2.0, 0.0, 2.0, 0.0, 1.0], 46.0, 0.0, -24.0, 0.0),
+    ([0.0, 0.0, 2.0, 2.0, 2.0], -38.0, 0.0, 16.0, 0.0),
+    ([2.0, 0.0, 2.0, 0.0, 2.0], -31.0, 0.0, 13.0, 0.0),
+    ([2.0, 0.0, 0.0, 0.0, 0.0], 29.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 2.0, -2.0, 2.0], 29.0, 0.0, -12.0, 0.0),
+    ([0.0, 0.0, 2.0, 0.0, 0.0], 26.0, 0.0, -1.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 0.0], -22.0, 0.0, 0.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 1.0], 21.0, 0.0, -10.0, 0.0),
+    ([0.0, 2.0, 0.0, 0.0, 0.0], 17.0, -0.1, 0.0, 0.0),
+    ([0.0, 2.0, 2.0, -2.0, 2.0], -16.0, 0.1, 7.0, 0.0),
+    ([-1.0, 0.0, 0.0, 2.0, 1.0], 16.0, 0.0, -8.0, 0.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nutation {
+    pub longitude: f64,      // rad, delta psi
+    pub obliquity: f64,      // rad, delta epsilon
+    pub mean_obliquity: f64, // rad
+    pub node: f64,           // rad, mean longitude of the Moon's ascending node
+}
+
+impl Nutation {
+    pub fn true_obliquity(&self) -> f64 {
+        self.mean_obliquity + self.obliquity
+    }
+
+    // Equation of the equinoxes in rad, with the kinematic terms of the
+    // 1994 IAU resolution when `kinematic` is set
+    pub fn equation_of_equinoxes(&self, kinematic: bool) -> f64 {
+        let mut equation = self.longitude * self.mean_obliquity.cos();
+        if kinematic {
+            equation += (0.00264 * self.node.sin() + 0.000063 * (2.0 * self.node).sin()) * ARCSEC;
+        }
+        equation
+    }
+}
+
+// IAU 1980 nutation at `t` Julian centuries of TT since J2000, without EOP
+// corrections (Vallado, eq. 3-82 to 3-86)
+pub fn nutation(t: f64) -> Nutation {
+    let revolutions = |degrees: f64, revs: f64, t2: f64, t3: f64| {
+        (degrees + (revs * 360.0) * t + t2 * t * t + t3 * t.powi(3)).to_radians()
+    };
+    let arguments = [
+        revolutions(134.96298139, 1325.0 + 198.8673981 / 360.0, 0.0086972, 1.78e-5),
+        revolutions(357.52772333, 99.0 + 359.0503400 / 360.0, -0.0001603, -3.3e-6),
+        revolutions(93.27191028, 1342.0 + 82.0175381 / 360.0, -0.0036825, 3.1e-6),
+        revolutions(297.85036306, 1236.0 + 307.1114800 / 360.0, -0.0019142, 5.3e-6),
+        revolutions(125.04452222, -(5.0 + 134.1362608 / 360.0), 0.0020708, 2.2e-6),
+    ];
+    let (mut longitude, mut obliquity) = (0.0, 0.0);
+    for (multipliers, psi, psi_rate, eps, eps_rate) in NUTATION_TERMS {
+        let argument: f64 = multipliers.iter().zip(arguments).map(|(m, a)| m * a).sum();
+        longitude += (psi + psi_rate * t) * argument.sin();
+        obliquity += (eps + eps_rate * t) * argument.cos();
+    }
+    let mean_obliquity = 84381.448 - 46.8150 * t - 0.00059 * t * t + 0.001813 * t.powi(3);
+    Nutation {
+        longitude: longitude * 1e-4 * ARCSEC,
+        obliquity: obliquity * 1e-4 * ARCSEC,
+        mean_obliquity: mean_obliquity * ARCSEC,
+        node: arguments[4],
+    }
+}
+
+// Greenwich mean sidereal angle in rad at a UT1 Julian date (IAU 1982,
+// Vallado eq. 3-47); callers without EOP pass UTC
+pub fn greenwich_sidereal_angle(julian_date: f64) -> f64 {
+    let t = (julian_date - J2000_JD) / 36525.0;
+    let seconds = 67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t + 0.093104 * t * t - 6.2e-6 * t.powi(3);
+    wrap_two_pi((seconds / 240.0).to_radians())
+}
+
+// Orientation of the Earth at one instant: every rotation needed to move a
+// state between the frames
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EarthOrientation {
+    pub precession: Matrix3,   // GCRF to mean of date
+    pub nutation: Matrix3,     // mean of date to true of date
+    pub teme_equinox: f64,     // rad, true of date to TEME about z
+    pub apparent_sidereal: f64, // rad, Greenwich apparent sidereal time
+    pub polar_motion: Matrix3, // PEF to ITRF
+    pub rotation_rate: f64,    // rad/s
+}
+
+impl EarthOrientation {
+    pub fn at(julian_date: f64, eop: &EopRecord) -> Self {
+        let t = tt_centuries(julian_date, eop);
+        let precession = precession_matrix(t);
+
+        let iau = nutation(t);
+        let corrected = Nutation { longitude: iau.longitude + eop.dpsi, obliquity: iau.obliquity + eop.deps, ..iau };
+        let nutation = Matrix3::rotation_x(-corrected.true_obliquity())
+            * Matrix3::rotation_z(-corrected.longitude)
+            * Matrix3::rotation_x(corrected.mean_obliquity);
+
+        let ut1 = julian_date + eop.ut1_minus_utc / 86400.0;
+        let apparent_sidereal = wrap_two_pi(greenwich_sidereal_angle(ut1) + corrected.equation_of_equinoxes(true));
+        EarthOrientation {
+            precession,
+            nutation,
+            // TEME uses the uncorrected equation of the equinoxes without the
+            // kinematic terms (Vallado et al., AIAA 2006-6753)
+            teme_equinox: iau.equation_of_equinoxes(false),
+            apparent_sidereal,
+            polar_motion: Matrix3::rotation_y(-eop.x_pole) * Matrix3::rotation_x(-eop.y_pole),
+            rotation_rate: EARTH_ROTATION_RATE * (1.0 - eop.length_of_day / 86400.0),
+        }
+    }
+
+    // Rotation from GCRF to a non-rotating frame of date
+    fn inertial_rotation(&self, frame: Frame) -> Matrix3 {
+        match frame {
+            Frame::Gcrf => Matrix3::IDENTITY,
+            Frame::MeanOfDate => self.precession,
+            Frame::TrueOfDate | Frame::Pef | Frame::Itrf => self.nutation * self.precession,
+            Frame::Teme => Matrix3::rotation_z(self.teme_equinox) * self.nutation * self.precession,
+        }
+    }
+
+    pub fn to_gcrf(&self, frame: Frame, state: &StateVector) -> StateVector {
+        let (mut position, mut velocity) = (state.position, state.velocity);
+        if frame == Frame::Itrf {
+            let to_pef = self.polar_motion.transpose();
+            position = to_pef * position;
+            velocity = to_pef * velocity;
+        }
+        if frame.is_rotating() {
+            // Add back the Earth's rotation before leaving the rotating frame
+            let omega = Vector3::new(0.0, 0.0, self.rotation_rate);
+            let to_tod = Matrix3::rotation_z(-self.apparent_sidereal);
+            velocity = to_tod * (velocity + omega.cross(position));
+            position = to_tod * position;
+        }
+        let to_gcrf = self.inertial_rotation(frame).transpose();
+        StateVector::new(to_gcrf * position, to_gcrf * velocity)
+    }
+
+    pub fn from_gcrf(&self, frame: Frame, state: &StateVector) -> StateVector {
+        let rotation = self.inertial_rotation(frame);
+        let (mut position, mut velocity) = (rotation * state.position, rotation * state.velocity);
+        if frame.is_rotating() {
+            let omega = Vector3::new(0.0, 0.0, self.rotation_rate);
+            let to_pef = Matrix3::rotation_z(self.apparent_sidereal);
+            position = to_pef * position;
+            velocity = to_pef * velocity - omega.cross(position);
+        }
+        if frame == Frame::Itrf {
+            position = self.polar_motion * position;
+            velocity = self.polar_motion * velocity;
+        }
+        StateVector::new(position, velocity)
+    }
+}
+
+// A state tagged with its frame and UTC epoch
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramedState {
+    pub frame: Frame,
+    pub julian_date: f64, // UTC
+    pub state: StateVector,
+}
+
+impl FramedState {
+    pub fn new(frame: Frame, julian_date: f64, state: StateVector) -> Self {
+        FramedState { frame, julian_date, state }
+    }
+
+    pub fn to_frame(&self, frame: Frame, eop: &EopRecord) -> FramedState {
+        if frame == self.frame {
+            return self.clone();
+        }
+        let orientation = EarthOrientation::at(self.julian_date, eop);
+        let gcrf = orientation.to_gcrf(self.frame, &self.state);
+        FramedState::new(frame, self.julian_date, orientation.from_gcrf(frame, &gcrf))
+    }
+
+    // Geodetic coordinates of the position, converting to ITRF first
+    pub fn geodetic(&self, body: &CentralBody, eop: &EopRecord) -> Geodetic {
+        Geodetic::from_ecef(self.to_frame(Frame::Itrf, eop).state.position, body)
+    }
+}
+
+impl fmt::Display for FramedState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (r, v) = (self.state.position / 1000.0, self.state.velocity / 1000.0);
+        write!(
+            f,
+            "{:<10} r = [{:>14.6}, {:>14.6}, {:>14.6}] km  v = [{:>11.6}, {:>11.6}, {:>11.6}] km/s",
+            self.frame.to_string(),
+            r.x,
+            r.y,
+            r.z,
+            v.x,
+            v.y,
+            v.z
+        )
+    }
+}
+
+// Geodetic latitude, longitude and altitude of a body-fixed position,
+// iterating on the latitude (Vallado, algorithm 12)
+pub fn geodetic_coordinates(position: Vector3, body: &CentralBody) -> (f64, f64, f64) {
+    let e2 = body.flattening * (2.0 - body.flattening);
+    let p = (position.x * position.x + position.y * position.y).sqrt();
+    let longitude = position.y.atan2(position.x);
+
+    let mut latitude = position.z.atan2(p * (1.0 - e2));
+    let mut c = body.equatorial_radius;
+    for _ in 0..GEODETIC_ITERATIONS {
+        let sin_lat = latitude.sin();
+        c = body.equatorial_radius / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let next = (position.z + c * e2 * sin_lat).atan2(p);
+        let converged = (next - latitude).abs() < GEODETIC_TOLERANCE;
+        latitude = next;
+        if converged {
+            break;
+        }
+    }
+    // Valid at the poles as well as the equator
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let altitude = p * cos_lat + (position.z + c * e2 * sin_lat) * sin_lat - c;
+    (latitude, longitude, altitude)
+}
+
+// Geodetic coordinates on the body's reference ellipsoid (WGS-84 for Earth)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    pub latitude: f64,  // rad
+    pub longitude: f64, // rad, east positive
+    pub altitude: f64,  // m above the ellipsoid
+}
+
+impl Geodetic {
+    pub fn from_ecef(position: Vector3, body: &CentralBody) -> Self {
+        let (latitude, longitude, altitude) = geodetic_coordinates(position, body);
+        Geodetic { latitude, longitude, altitude }
+    }
+
+    pub fn to_ecef(&self, body: &CentralBody) -> Vector3 {
+        let e2 = body.flattening * (2.0 - body.flattening);
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let c = body.equatorial_radius / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let s = c * (1.0 - e2);
+        Vector3::new(
+            (c + self.altitude) * cos_lat * self.longitude.cos(),
+            (c + self.altitude) * cos_lat * self.longitude.sin(),
+            (s + self.altitude) * sin_lat,
+        )
+    }
+
+    // Rotation from ECEF to the site's south-east-zenith frame
+    pub fn sez_rotation(&self) -> Matrix3 {
+        Matrix3::rotation_y(PI / 2.0 - self.latitude) * Matrix3::rotation_z(self.longitude)
+    }
+
+    // Rotation from ECEF to the site's east-north-up frame
+    pub fn enu_rotation(&self) -> Matrix3 {
+        let sez = self.sez_rotation();
+        Matrix3::new([sez.rows[1], -sez.rows[0], sez.rows[2]])
+    }
+
+    // Azimuth, elevation and range of an ECEF position seen from the site
+    pub fn look_at(&self, body: &CentralBody, position: Vector3) -> AzElRange {
+        AzElRange::from_sez(self.sez_rotation() * (position - self.to_ecef(body)))
+    }
+}
+
+impl fmt::Display for Geodetic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "lat {:.6} deg, lon {:.6} deg, alt {:.3} km",
+            self.latitude.to_degrees(),
+            self.longitude.to_degrees(),
+            self.altitude / 1000.0
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AzElRange {
+    pub azimuth: f64,   // rad, clockwise from north
+    pub elevation: f64, // rad
+    pub range: f64,     // m
+}
+
+impl AzElRange {
+    pub fn from_sez(sez: Vector3) -> Self {
+        let range = sez.norm();
+        let elevation = if range > 0.0 { (sez.z / range).clamp(-1.0, 1.0).asin() } else { 0.0 };
+        AzElRange { azimuth: wrap_two_pi(sez.y.atan2(-sez.x)), elevation, range }
+    }
+
+    pub fn from_enu(enu: Vector3) -> Self {
+        AzElRange::from_sez(Vector3::new(-enu.y, enu.x, enu.z))
+    }
+
+    pub fn to_sez(&self) -> Vector3 {
+        let (sin_el, cos_el) = self.elevation.sin_cos();
+        let (sin_az, cos_az) = self.azimuth.sin_cos();
+        Vector3::new(-cos_el * cos_az, cos_el * sin_az, sin_el) * self.range
+    }
+
+    pub fn to_enu(&self) -> Vector3 {
+        let sez = self.to_sez();
+        Vector3::new(sez.y, -sez.x, sez.z)
+    }
+}
+
+impl fmt::Display for AzElRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "az {:.4} deg, el {:.4} deg, range {:.3} km",
+            self.azimuth.to_degrees(),
+            self.elevation.to_degrees(),
+            self.range / 1000.0
+        )
+    }
+}
+
+// Parse a frame name as used on the command line and in prompts
+pub fn parse_frame(text: &str) -> Result<Frame, CalculationError> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "gcrf" | "j2000" | "eci" => Ok(Frame::Gcrf),
+        "mod" => Ok(Frame::MeanOfDate),
+        "tod" => Ok(Frame::TrueOfDate),
+        "teme" => Ok(Frame::Teme),
+        "pef" => Ok(Frame::Pef),
+        "itrf" | "ecef" => Ok(Frame::Itrf),
+        _ => Err(CalculationError::InvalidInput),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{Epoch, TimeScale};
+
+    fn km(x: f64, y: f64, z: f64) -> Vector3 {
+        Vector3::new(x, y, z) * 1000.0
+    }
+
+    // Vallado example 3-15: 2004-04-06 07:51:28.386009 UTC with the EOP of
+    // that day
+    fn example_3_15() -> (FramedState, EopRecord) {
+        let julian_date =
+            Epoch::from_calendar(2004, 4, 6, 7, 51, 28.386009, TimeScale::Utc).unwrap().julian_date(TimeScale::Utc);
+        let eop = EopRecord {
+            mjd: julian_date - MJD_OFFSET,
+            x_pole: -0.140682 * ARCSEC,
+            y_pole: 0.333309 * ARCSEC,
+            ut1_minus_utc: -0.4399619,
+            length_of_day: 0.0015563,
+            dpsi: -0.052195 * ARCSEC,
+            deps: -0.003875 * ARCSEC,
+            tai_minus_utc: 32.0,
+        };
+        let itrf = StateVector::new(
+            km(-1033.4793830, 7901.2952754, 6380.3565958),
+            km(-3.225636520, -2.872451450, 5.531924446),
+        );
+        (FramedState::new(Frame::Itrf, julian_date, itrf), eop)
+    }
+
+    fn assert_state(state: &FramedState, position: Vector3, velocity: Vector3, tolerance: f64) {
+        let (dr, dv) = ((state.state.position - position).norm(), (state.state.velocity - velocity).norm());
+        assert!(dr < tolerance, "{} position off by {} m", state.frame, dr);
+        assert!(dv < tolerance * 1e-3, "{} velocity off by {} m/s", state.frame, dv);
+    }
+
+    #[test]
+    fn vallado_example_3_15() {
+        let (itrf, eop) = example_3_15();
+        assert_state(
+            &itrf.to_frame(Frame::Pef, &eop),
+            km(-1033.4750313, 7901.3055856, 6380.3445328),
+            km(-3.225632747, -2.872442511, 5.531931288),
+            1e-3,
+        );
+        assert_state(
+            &itrf.to_frame(Frame::MeanOfDate, &eop),
+            km(5094.0283745, 6127.8708164, 6380.2485164),
+            km(-4.746263052, 0.786014045, 5.531790562),
+            0.1,
+        );
+        // The 30-term nutation series stays within 0.1 m of the full one
+        assert_state(
+            &itrf.to_frame(Frame::Gcrf, &eop),
+            km(5102.508958, 6123.011401, 6378.136928),
+            km(-4.743220157, 0.790536497, 5.533755727),
+            0.1,
+        );
+    }
+
+    #[test]
+    fn every_frame_round_trips() {
+        let (itrf, eop) = example_3_15();
+        for frame in Frame::ALL {
+            let there = itrf.to_frame(frame, &eop);
+            assert_eq!(there.frame, frame);
+            let back = there.to_frame(Frame::Itrf, &eop);
+            assert_state(&back, itrf.state.position, itrf.state.velocity, 1e-6);
+        }
+    }
+
+    #[test]
+    fn precession_and_nutation_vanish_at_j2000() {
+        let (zeta, theta, z) = precession_angles(0.0);
+        assert_eq!((zeta, theta, z), (0.0, 0.0, 0.0));
+        let iau = nutation(0.0);
+        assert!((iau.mean_obliquity - 84381.448 * ARCSEC).abs() < 1e-15);
+        // Nutation in longitude stays within about 17.2 arcsec
+        assert!(iau.longitude.abs() < 18.0 * ARCSEC && iau.obliquity.abs() < 10.0 * ARCSEC);
+    }
+
+    #[test]
+    fn geodetic_round_trip() {
+        let body = CentralBody::earth();
+        let site = Geodetic { latitude: 39.007_f64.to_radians(), longitude: -104.883_f64.to_radians(), altitude: 2194.56 };
+        let back = Geodetic::from_ecef(site.to_ecef(&body), &body);
+        assert!((back.latitude - site.latitude).abs() < 1e-10 && (back.longitude - site.longitude).abs() < 1e-10);
+        assert!((back.altitude - site.altitude).abs() < 1e-4);
+    }
+
+    #[test]
+    fn look_angles_round_trip() {
+        let look = AzElRange { azimuth: 1.0, elevation: 0.3, range: 1.5e6 };
+        let back = AzElRange::from_enu(look.to_enu());
+        assert!((back.azimuth - 1.0).abs() < 1e-12 && (back.elevation - 0.3).abs() < 1e-12);
+        assert!((back.range - 1.5e6).abs() < 1e-6);
+        // Straight overhead from the site is zenith
+        let body = CentralBody::earth();
+        let site = Geodetic { latitude: 0.5, longitude: 2.0, altitude: 0.0 };
+        let above = Geodetic { altitude: 400e3, ..site }.to_ecef(&body);
+        let overhead = site.look_at(&body, above);
+        assert!((overhead.elevation - PI / 2.0).abs() < 1e-9 && (overhead.range - 400e3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parses_frame_names() {
+        assert_eq!(parse_frame(" J2000 "), Ok(Frame::Gcrf));
+        assert_eq!(parse_frame("ecef"), Ok(Frame::Itrf));
+        assert_eq!(parse_frame("teme"), Ok(Frame::Teme));
+        assert_eq!(parse_frame("icrf"), Err(CalculationError::InvalidInput));
+    }
+}
@@ -0,0 +1,320 @@
+// Ground station visibility and pass prediction
+//
+// A station sits on the Earth's WGS-84 ellipsoid. Satellite states carry
+// their frame (GCRF for the two-body and J2 propagators, TEME for SGP4) and
+// are taken to ITRF through the full Earth orientation reduction (see
+// frames.rs), then to the station's topocentric south-east-zenith frame to
+// get azimuth, elevation and range. Passes are found by sampling the
+// elevation above the mask and refining every crossing by bisection.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::frames::{AzElRange, EopRecord, EopTable, Frame, FramedState, Geodetic};
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+const CROSSING_TOLERANCE: f64 = 1e-3; // s
+const RANGE_TOLERANCE: f64 = 1e-3; // m
+// Caps on the bracketing and bisection of the slant range. Doubling 64
+// times from the climb is far past any orbit, and 200 halvings reach the
+// spacing of doubles, where a huge range can no longer meet the tolerance.
+const RANGE_DOUBLINGS: usize = 64;
+const RANGE_ITERATIONS: usize = 200;
+
+// Distance from `site` to the point at `altitude` above the body's ellipsoid
+// along the line of sight at `azimuth` and `elevation` (rad, lengths in m).
+// At or above the horizon the geodetic altitude only grows along the ray,
+// so the range is bracketed and then bisected.
+pub fn slant_range(
+    body: &CentralBody,
+    site: &Geodetic,
+    azimuth: f64,
+    elevation: f64,
+    altitude: f64,
+) -> Result<f64, CalculationError> {
+    if !(0.0..=PI / 2.0).contains(&elevation) || !altitude.is_finite() || altitude <= site.altitude {
+        return Err(CalculationError::InvalidInput);
+    }
+    let origin = site.to_ecef(body);
+    let direction = site.sez_rotation().transpose() * AzElRange { azimuth, elevation, range: 1.0 }.to_sez();
+    // Far enough out the squared coordinates overflow and the altitude is lost
+    let height = |range: f64| -> Result<f64, CalculationError> {
+        let height = Geodetic::from_ecef(origin + direction * range, body).altitude;
+        if height.is_finite() { Ok(height) } else { Err(CalculationError::OutOfRange) }
+    };
+
+    // Straight up the range equals the climb; any other ray is longer
+    let (mut low, mut high) = (0.0, altitude - site.altitude);
+    let mut doublings = 0;
+    while height(high)? < altitude {
+        doublings += 1;
+        if doublings > RANGE_DOUBLINGS {
+            return Err(CalculationError::OutOfRange);
+        }
+        low = high;
+        high *= 2.0;
+    }
+    for _ in 0..RANGE_ITERATIONS {
+        if high - low <= RANGE_TOLERANCE {
+            break;
+        }
+        let mid = 0.5 * (low + high);
+        if height(mid)? < altitude {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(0.5 * (low + high))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundStation {
+    pub name: String,
+    pub latitude: f64,      // rad, geodetic
+    pub longitude: f64,     // rad, east positive
+    pub altitude: f64,      // m above the reference ellipsoid
+    pub min_elevation: f64, // rad, elevation mask
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookAngles {
+    pub time: f64,       // s after the epoch
+    pub azimuth: f64,    // rad, clockwise from north
+    pub elevation: f64,  // rad
+    pub range: f64,      // m
+    pub range_rate: f64, // m/s, positive when receding
+}
+
+impl GroundStation {
+    pub fn new(
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        min_elevation: f64,
+    ) -> Result<Self, CalculationError> {
+        if latitude.abs() > PI / 2.0 || !(-PI / 2.0..PI / 2.0).contains(&min_elevation) {
+            return Err(CalculationError::OutOfRange);
+        }
+        Ok(GroundStation { name: name.to_string(), latitude, longitude, altitude, min_elevation })
+    }
+
+    // Body-fixed position of the station on the body's reference ellipsoid
+    pub fn position(&self, body: &CentralBody) -> Vector3 {
+        self.geodetic().to_ecef(body)
+    }
+
+    pub fn geodetic(&self) -> Geodetic {
+        Geodetic { latitude: self.latitude, longitude: self.longitude, altitude: self.altitude }
+    }
+
+    // Azimuth, elevation and range of a state in any frame, about the Earth
+    pub fn look_angles(
+        &self,
+        body: &CentralBody,
+        state: &FramedState,
+        eop: &EopRecord,
+        time: f64,
+    ) -> Result<LookAngles, CalculationError> {
+        if !body.is_earth() {
+            return Err(CalculationError::OutOfRange);
+        }
+        // ITRF velocity is relative to the rotating Earth, as the range rate needs
+        let fixed = state.to_frame(Frame::Itrf, eop).state;
+        let rho = fixed.position - self.position(body);
+        let look = AzElRange::from_sez(self.geodetic().sez_rotation() * rho);
+        Ok(LookAngles {
+            time,
+            azimuth: look.azimuth,
+            elevation: look.elevation,
+            range: look.range,
+            range_rate: rho.dot(fixed.velocity) / look.range,
+        })
+    }
+}
+
+impl fmt::Display for GroundStation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (lat {:.4} deg, lon {:.4} deg, alt {:.0} m, mask {:.1} deg)",
+            self.name,
+            self.latitude.to_degrees(),
+            self.longitude.to_degrees(),
+            self.altitude,
+            self.min_elevation.to_degrees()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pass {
+    pub aos: f64,                // s after the epoch, acquisition of signal
+    pub los: f64,                // s after the epoch, loss of signal
+    pub max_elevation: f64,      // rad
+    pub max_elevation_time: f64, // s after the epoch
+    pub profile: Vec<LookAngles>,
+}
+
+impl Pass {
+    pub fn duration(&self) -> f64 {
+        self.los - self.aos
+    }
+}
+
+// Find every pass over `station` between `start` and `end` seconds after
+// the propagator's epoch. `propagate` returns the state, tagged with its
+// frame and date, at a time after the epoch, so any propagator (two-body,
+// J2, SGP4) can drive it; `eop` orients the Earth at each date. Passes cut
+// by the window edges start or end at the edge.
+pub fn predict_passes<F>(
+    station: &GroundStation,
+    body: &CentralBody,
+    eop: &EopTable,
+    start: f64,
+    end: f64,
+    step: f64,
+    propagate: F,
+) -> Result<Vec<Pass>, CalculationError>
+where
+    F: Fn(f64) -> Result<FramedState, CalculationError>,
+{
+    if step <= 0.0 || end <= start {
+        return Err(CalculationError::InvalidInput);
+    }
+    let look = |t: f64| -> Result<LookAngles, CalculationError> {
+        let state = propagate(t)?;
+        station.look_angles(body, &state, &eop.at(state.julian_date), t)
+    };
+    let above = |t: f64| -> Result<f64, CalculationError> { Ok(look(t)?.elevation - station.min_elevation) };
+
+    // Time where the elevation crosses the mask between a (below) and b (above)
+    // or the other way round
+    let crossing = |mut a: f64, mut b: f64| -> Result<f64, CalculationError> {
+        let a_above = above(a)? > 0.0;
+        while b - a > CROSSING_TOLERANCE {
+            let mid = 0.5 * (a + b);
+            if (above(mid)? > 0.0) == a_above {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        Ok(0.5 * (a + b))
+    };
+
+    let mut passes = Vec::new();
+    let mut aos = if above(start)? > 0.0 { Some(start) } else { None };
+    let mut t = start;
+    while t < end {
+        let next = (t + step).min(end);
+        let visible = above(next)? > 0.0;
+        match (aos, visible) {
+            (None, true) => aos = Some(crossing(t, next)?),
+            (Some(acquired), false) => {
+                passes.push(build_pass(acquired, crossing(t, next)?, step, &look)?);
+                aos = None;
+            }
+            _ => {}
+        }
+        t = next;
+    }
+    if let Some(acquired) = aos {
+        passes.push(build_pass(acquired, end, step, &look)?);
+    }
+    Ok(passes)
+}
+
+fn build_pass<L>(aos: f64, los: f64, step: f64, look: &L) -> Result<Pass, CalculationError>
+where
+    L: Fn(f64) -> Result<LookAngles, CalculationError>,
+{
+    let mut profile = vec![look(aos)?];
+    let mut t = aos + step;
+    while t < los {
+        profile.push(look(t)?);
+        t += step;
+    }
+    if los > aos {
+        profile.push(look(los)?);
+    }
+
+    // Refine the culmination with a golden-section search around the best sample
+    let best = profile.iter().max_by(|a, b| a.elevation.total_cmp(&b.elevation)).map(|p| p.time).unwrap_or(aos);
+    let golden = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = ((best - step).max(aos), (best + step).min(los));
+    while high - low > CROSSING_TOLERANCE {
+        let c = high - golden * (high - low);
+        let d = low + golden * (high - low);
+        if look(c)?.elevation > look(d)?.elevation {
+            high = d;
+        } else {
+            low = c;
+        }
+    }
+    let culmination = look(0.5 * (low + high))?;
+
+    Ok(Pass {
+        aos,
+        los,
+        max_elevation: culmination.elevation,
+        max_elevation_time: culmination.time,
+        profile,
+    })
+}
+
+// CSV of the azimuth/elevation/range profile of every pass
+pub fn passes_to_csv(passes: &[Pass]) -> String {
+    let mut csv = String::from("pass,time_s,azimuth_deg,elevation_deg,range_m,range_rate_m_s\n");
+    for (i, pass) in passes.iter().enumerate() {
+        for point in &pass.profile {
+            let _ = writeln!(
+                csv,
+                "{},{},{:.6},{:.6},{:.3},{:.6}",
+                i + 1,
+                point.time,
+                point.azimuth.to_degrees(),
+                point.elevation.to_degrees(),
+                point.range,
+                point.range_rate
+            );
+        }
+    }
+    csv
+}
+
+pub fn write_passes_csv<P: AsRef<Path>>(passes: &[Pass], path: P) -> std::io::Result<()> {
+    fs::write(path, passes_to_csv(passes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slant_range_matches_the_sphere_along_the_equator() {
+        let body = CentralBody::earth();
+        let site = Geodetic { latitude: 0.0, longitude: 0.3, altitude: 0.0 };
+        let (r, h) = (body.equatorial_radius, 500e3);
+        for degrees in [0.0f64, 10.0, 45.0, 90.0] {
+            let elevation = degrees.to_radians();
+            let expected = ((r + h).powi(2) - (r * elevation.cos()).powi(2)).sqrt() - r * elevation.sin();
+            let range = slant_range(&body, &site, PI / 2.0, elevation, h).unwrap();
+            assert!((range - expected).abs() < 2.0 * RANGE_TOLERANCE, "{} deg: {} vs {}", degrees, range, expected);
+        }
+    }
+
+    #[test]
+    fn slant_range_always_finishes() {
+        let body = CentralBody::earth();
+        let site = Geodetic { latitude: 0.5, longitude: 0.0, altitude: 0.0 };
+        let range = slant_range(&body, &site, 0.0, 0.2, 1e150).unwrap();
+        assert!((range / 1e150 - 1.0).abs() < 1e-9);
+        assert_eq!(slant_range(&body, &site, 0.0, 0.2, 1e300), Err(CalculationError::OutOfRange));
+        assert_eq!(slant_range(&body, &site, 0.0, 0.2, f64::INFINITY), Err(CalculationError::InvalidInput));
+        assert_eq!(slant_range(&body, &site, 0.0, -0.1, 500e3), Err(CalculationError::InvalidInput));
+    }
+}
@@ -0,0 +1,361 @@
+// Ground tracks and sensor swaths
+//
+// States, tagged with their frame, are taken to ITRF through the Earth
+// orientation reduction (see frames.rs), so the track drifts west as the
+// Earth turns underneath the orbit, and converted to geodetic latitude,
+// longitude and altitude on the WGS-84 ellipsoid. A sensor swath is drawn on a sphere of
+// the equatorial radius by offsetting each track point perpendicular to
+// its heading by half the swath width.
+//
+// Lines and polygons are split where they cross the antimeridian, as GIS
+// tools expect (RFC 7946, section 3.1.9), and exported as GeoJSON or KML.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::elements::wrap_pi;
+use crate::escape::{json_string, xml_escape};
+use crate::frames::{EopRecord, EopTable, FramedState};
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const SAMPLE_TOLERANCE: f64 = 1e-6; // s
+
+// A point on the surface, in rad
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64, // east positive, in (-pi, pi]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub time: f64,      // s after the epoch
+    pub latitude: f64,  // rad, geodetic
+    pub longitude: f64, // rad, east positive, in (-pi, pi]
+    pub altitude: f64,  // m above the reference ellipsoid
+}
+
+impl TrackPoint {
+    pub fn geo(&self) -> GeoPoint {
+        GeoPoint { latitude: self.latitude, longitude: self.longitude }
+    }
+}
+
+// Sub-satellite point of a state in any frame, about the Earth
+pub fn sub_satellite_point(
+    state: &FramedState,
+    body: &CentralBody,
+    eop: &EopRecord,
+    time: f64,
+) -> Result<TrackPoint, CalculationError> {
+    if !body.is_earth() {
+        return Err(CalculationError::OutOfRange);
+    }
+    let point = state.geodetic(body, eop);
+    Ok(TrackPoint { time, latitude: point.latitude, longitude: point.longitude, altitude: point.altitude })
+}
+
+// Ground track sampled every `step` seconds from `start` to `end` after the
+// propagator's epoch. As for pass prediction, `propagate` returns the state
+// tagged with its frame and date, and `eop` orients the Earth at each date.
+pub fn ground_track<F>(
+    body: &CentralBody,
+    eop: &EopTable,
+    start: f64,
+    end: f64,
+    step: f64,
+    propagate: F,
+) -> Result<Vec<TrackPoint>, CalculationError>
+where
+    F: Fn(f64) -> Result<FramedState, CalculationError>,
+{
+    if step <= 0.0 || end <= start {
+        return Err(CalculationError::InvalidInput);
+    }
+    let point = |t: f64| -> Result<TrackPoint, CalculationError> {
+        let state = propagate(t)?;
+        sub_satellite_point(&state, body, &eop.at(state.julian_date), t)
+    };
+    // Times are counted from `start` so rounding does not build up over
+    // long tracks, and `end` is only added when off the grid
+    let mut track = Vec::new();
+    let mut i = 0usize;
+    loop {
+        let t = start + i as f64 * step;
+        if t > end {
+            break;
+        }
+        track.push(point(t)?);
+        i += 1;
+    }
+    if track.last().is_none_or(|p| end - p.time > SAMPLE_TOLERANCE) {
+        track.push(point(end)?);
+    }
+    Ok(track)
+}
+
+// Initial great-circle bearing from `from` to `to`, clockwise from north
+fn bearing(from: GeoPoint, to: GeoPoint) -> f64 {
+    let dlon = to.longitude - from.longitude;
+    let y = dlon.sin() * to.latitude.cos();
+    let x = from.latitude.cos() * to.latitude.sin() - from.latitude.sin() * to.latitude.cos() * dlon.cos();
+    y.atan2(x)
+}
+
+// Point at angular distance `distance` (rad) along `bearing` from `from`
+fn destination(from: GeoPoint, bearing: f64, distance: f64) -> GeoPoint {
+    let (sin_lat, cos_lat) = from.latitude.sin_cos();
+    let latitude = (sin_lat * distance.cos() + cos_lat * distance.sin() * bearing.cos()).asin();
+    let longitude =
+        from.longitude + (bearing.sin() * distance.sin() * cos_lat).atan2(distance.cos() - sin_lat * latitude.sin());
+    GeoPoint { latitude, longitude: wrap_pi(longitude) }
+}
+
+// Left and right swath edges of every track point for a swath `width` in m
+// measured across the ground
+pub fn swath_edges(
+    track: &[TrackPoint],
+    body: &CentralBody,
+    width: f64,
+) -> Result<Vec<(GeoPoint, GeoPoint)>, CalculationError> {
+    if width <= 0.0 || track.len() < 2 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let half = 0.5 * width / body.equatorial_radius;
+    let edges = (0..track.len())
+        .map(|i| {
+            // Heading towards the next point, or from the previous one at the end
+            let heading = if i + 1 < track.len() {
+                bearing(track[i].geo(), track[i + 1].geo())
+            } else {
+                bearing(track[i - 1].geo(), track[i].geo())
+            };
+            let center = track[i].geo();
+            (destination(center, heading - PI / 2.0, half), destination(center, heading + PI / 2.0, half))
+        })
+        .collect();
+    Ok(edges)
+}
+
+// Point on the segment a-b (longitudes already unwrapped) at longitude `at`
+fn at_longitude(a: GeoPoint, b: GeoPoint, at: f64) -> GeoPoint {
+    let fraction = (at - a.longitude) / (b.longitude - a.longitude);
+    GeoPoint { latitude: a.latitude + fraction * (b.latitude - a.latitude), longitude: at }
+}
+
+// Track line split into pieces that do not cross the antimeridian, each
+// piece ending exactly on it
+pub fn track_segments(track: &[TrackPoint]) -> Vec<Vec<GeoPoint>> {
+    let mut segments = Vec::new();
+    let mut current: Vec<GeoPoint> = Vec::new();
+    for point in track {
+        let next = point.geo();
+        if let Some(&last) = current.last() {
+            let jump = next.longitude - last.longitude;
+            if jump.abs() > PI {
+                let edge = if jump < 0.0 { PI } else { -PI };
+                let unwrapped = GeoPoint { longitude: next.longitude + 2.0 * edge, ..next };
+                let crossing = at_longitude(last, unwrapped, edge);
+                current.push(crossing);
+                segments.push(std::mem::take(&mut current));
+                current.push(GeoPoint { longitude: -edge, ..crossing });
+            }
+        }
+        current.push(next);
+    }
+    if current.len() > 1 {
+        segments.push(current);
+    }
+    segments
+}
+
+// Keep the part of `ring` with longitude on one side of `at`
+fn clip_ring(ring: &[GeoPoint], at: f64, keep_below: bool) -> Vec<GeoPoint> {
+    let inside = |p: &GeoPoint| if keep_below { p.longitude <= at } else { p.longitude >= at };
+    let mut clipped = Vec::new();
+    for (i, &current) in ring.iter().enumerate() {
+        let previous = ring[(i + ring.len() - 1) % ring.len()];
+        match (inside(&previous), inside(&current)) {
+            (true, true) => clipped.push(current),
+            (true, false) => clipped.push(at_longitude(previous, current, at)),
+            (false, true) => {
+                clipped.push(at_longitude(previous, current, at));
+                clipped.push(current);
+            }
+            (false, false) => {}
+        }
+    }
+    clipped
+}
+
+fn crosses_antimeridian(a: GeoPoint, b: GeoPoint) -> bool {
+    (a.longitude - b.longitude).abs() > PI
+}
+
+// Swath coverage as closed polygon rings (first point repeated at the end),
+// counter-clockwise as GeoJSON recommends. The swath between consecutive
+// samples is a quadrilateral; runs of them merge into one polygon until a
+// quadrilateral crosses the antimeridian, which is cut in two along it.
+pub fn swath_polygons(edges: &[(GeoPoint, GeoPoint)]) -> Vec<Vec<GeoPoint>> {
+    let mut polygons = Vec::new();
+    // A single cross-track line covers no area
+    if edges.len() < 2 {
+        return polygons;
+    }
+    let mut strip_start = 0;
+    let close_strip = |polygons: &mut Vec<Vec<GeoPoint>>, from: usize, to: usize| {
+        if to > from {
+            let mut ring: Vec<GeoPoint> = edges[from..=to].iter().map(|e| e.0).collect();
+            ring.extend(edges[from..=to].iter().rev().map(|e| e.1));
+            polygons.push(ring);
+        }
+    };
+    for i in 0..edges.len() - 1 {
+        let quad = [edges[i].0, edges[i + 1].0, edges[i + 1].1, edges[i].1];
+        if !(0..4).any(|k| crosses_antimeridian(quad[k], quad[(k + 1) % 4])) {
+            continue;
+        }
+        close_strip(&mut polygons, strip_start, i);
+        strip_start = i + 1;
+
+        // Make the longitudes continuous, then cut at whichever side overflows
+        let mut unwrapped = quad.to_vec();
+        for k in 1..4 {
+            let previous = unwrapped[k - 1].longitude;
+            unwrapped[k].longitude = previous + wrap_pi(unwrapped[k].longitude - previous);
+        }
+        let edge = if unwrapped.iter().any(|p| p.longitude > PI) { PI } else { -PI };
+        let inner = clip_ring(&unwrapped, edge, edge > 0.0);
+        let outer = clip_ring(&unwrapped, edge, edge < 0.0)
+            .into_iter()
+            .map(|p| GeoPoint { longitude: p.longitude - 2.0 * edge, ..p })
+            .collect();
+        polygons.extend([inner, outer].into_iter().filter(|ring: &Vec<GeoPoint>| ring.len() >= 3));
+    }
+    close_strip(&mut polygons, strip_start, edges.len() - 1);
+
+    for ring in &mut polygons {
+        // Shoelace formula in longitude/latitude: positive when counter-clockwise
+        let area: f64 = (0..ring.len())
+            .map(|k| {
+                let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                a.longitude * b.latitude - b.longitude * a.latitude
+            })
+            .sum();
+        if area < 0.0 {
+            ring.reverse();
+        }
+        ring.push(ring[0]);
+    }
+    polygons
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroundTrackFormat {
+    GeoJson,
+    Kml,
+}
+
+impl GroundTrackFormat {
+    // KML for a .kml extension, GeoJSON otherwise
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let kml = path.as_ref().extension().is_some_and(|e| e.eq_ignore_ascii_case("kml"));
+        if kml { GroundTrackFormat::Kml } else { GroundTrackFormat::GeoJson }
+    }
+}
+
+fn json_coordinates(points: &[GeoPoint]) -> String {
+    let coordinates: Vec<String> = points
+        .iter()
+        .map(|p| format!("[{:.6},{:.6}]", p.longitude.to_degrees(), p.latitude.to_degrees()))
+        .collect();
+    format!("[{}]", coordinates.join(","))
+}
+
+// GeoJSON FeatureCollection with the track as a MultiLineString and, when
+// `swath` is not empty, the coverage as a MultiPolygon
+pub fn ground_track_to_geojson(name: &str, track: &[TrackPoint], swath: &[Vec<GeoPoint>]) -> String {
+    let lines: Vec<String> = track_segments(track).iter().map(|s| json_coordinates(s)).collect();
+    let (start, end) = match (track.first(), track.last()) {
+        (Some(first), Some(last)) => (first.time, last.time),
+        _ => (0.0, 0.0),
+    };
+    let mut features = vec![format!(
+        "{{\"type\":\"Feature\",\"properties\":{{\"name\":{},\"kind\":\"ground track\",\"start_s\":{},\"end_s\":{}}},\
+         \"geometry\":{{\"type\":\"MultiLineString\",\"coordinates\":[{}]}}}}",
+        json_string(name),
+        start,
+        end,
+        lines.join(",")
+    )];
+    if !swath.is_empty() {
+        let polygons: Vec<String> = swath.iter().map(|ring| format!("[{}]", json_coordinates(ring))).collect();
+        features.push(format!(
+            "{{\"type\":\"Feature\",\"properties\":{{\"name\":{},\"kind\":\"swath\"}},\
+             \"geometry\":{{\"type\":\"MultiPolygon\",\"coordinates\":[{}]}}}}",
+            json_string(name),
+            polygons.join(",")
+        ));
+    }
+    format!("{{\"type\":\"FeatureCollection\",\"features\":[\n{}\n]}}\n", features.join(",\n"))
+}
+
+fn kml_coordinates(points: &[GeoPoint]) -> String {
+    let coordinates: Vec<String> = points
+        .iter()
+        .map(|p| format!("{:.6},{:.6},0", p.longitude.to_degrees(), p.latitude.to_degrees()))
+        .collect();
+    coordinates.join(" ")
+}
+
+// KML document with the track and the swath polygons as placemarks
+pub fn ground_track_to_kml(name: &str, track: &[TrackPoint], swath: &[Vec<GeoPoint>]) -> String {
+    let name = xml_escape(name);
+    let mut kml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n");
+    let _ = writeln!(kml, "<Document>\n<name>{}</name>", name);
+    kml.push_str("<Style id=\"track\"><LineStyle><color>ff00ffff</color><width>2</width></LineStyle></Style>\n");
+    kml.push_str(
+        "<Style id=\"swath\"><LineStyle><color>ff0000ff</color></LineStyle>\
+         <PolyStyle><color>4d0000ff</color></PolyStyle></Style>\n",
+    );
+
+    let _ = writeln!(kml, "<Placemark>\n<name>{} ground track</name>\n<styleUrl>#track</styleUrl>\n<MultiGeometry>", name);
+    for segment in track_segments(track) {
+        let _ = writeln!(
+            kml,
+            "<LineString><tessellate>1</tessellate><coordinates>{}</coordinates></LineString>",
+            kml_coordinates(&segment)
+        );
+    }
+    kml.push_str("</MultiGeometry>\n</Placemark>\n");
+
+    if !swath.is_empty() {
+        let _ = writeln!(kml, "<Placemark>\n<name>{} swath</name>\n<styleUrl>#swath</styleUrl>\n<MultiGeometry>", name);
+        for ring in swath {
+            let _ = writeln!(
+                kml,
+                "<Polygon><tessellate>1</tessellate><outerBoundaryIs><LinearRing><coordinates>{}</coordinates>\
+                 </LinearRing></outerBoundaryIs></Polygon>",
+                kml_coordinates(ring)
+            );
+        }
+        kml.push_str("</MultiGeometry>\n</Placemark>\n");
+    }
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+// Write GeoJSON or KML depending on the file extension
+pub fn write_ground_track<P: AsRef<Path>>(
+    name: &str,
+    track: &[TrackPoint],
+    swath: &[Vec<GeoPoint>],
+    path: P,
+) -> std::io::Result<()> {
+    let contents = match GroundTrackFormat::from_path(&path) {
+        GroundTrackFormat::GeoJson => ground_track_to_geojson(name, track, swath),
+        GroundTrackFormat::Kml => ground_track_to_kml(name, track, swath),
+    };
+    fs::write(path, contents)
+}
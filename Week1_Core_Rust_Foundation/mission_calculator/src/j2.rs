@@ -0,0 +1,268 @@
+// J2 (oblateness) perturbations and orbit design
+//
+// Secular rates follow the first-order averaged theory (Vallado, section
+// 9.6). The numerical propagator integrates point-mass plus J2 gravity with
+// a fixed-step fourth-order Runge-Kutta scheme.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::elements::{OrbitalElements, StateVector};
+use crate::propagate::EphemerisPoint;
+use crate::vector::Vector3;
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::fmt;
+
+// Mean motion of the Earth around the Sun (one tropical year), the nodal
+// rate a sun-synchronous Earth orbit has to match, in rad/s
+pub const EARTH_SUN_SYNCHRONOUS_RATE: f64 = 2.0 * PI / (365.2421897 * 86400.0);
+
+// Spacing of the states cached by `J2Propagator` and its integration step, s
+const CHECKPOINT_STEP: f64 = 600.0;
+const CHECKPOINT_MAX_STEP: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct J2Rates {
+    pub raan_rate: f64,          // rad/s
+    pub arg_periapsis_rate: f64, // rad/s
+    pub mean_anomaly_rate: f64,  // rad/s, mean motion including the J2 correction
+}
+
+impl fmt::Display for J2Rates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const DEG_PER_DAY: f64 = 180.0 / PI * 86400.0;
+        writeln!(f, "Nodal regression:      {:.6} deg/day", self.raan_rate * DEG_PER_DAY)?;
+        writeln!(f, "Apsidal precession:    {:.6} deg/day", self.arg_periapsis_rate * DEG_PER_DAY)?;
+        write!(f, "Perturbed mean motion: {:.6} deg/day", self.mean_anomaly_rate * DEG_PER_DAY)
+    }
+}
+
+// Secular drift of RAAN, argument of periapsis and mean anomaly
+pub fn secular_rates(elements: &OrbitalElements, body: &CentralBody) -> Result<J2Rates, CalculationError> {
+    let e = elements.eccentricity;
+    if e >= 1.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(secular_rates_for(elements.semi_major_axis(), e, elements.inclination, body))
+}
+
+fn secular_rates_for(a: f64, e: f64, inclination: f64, body: &CentralBody) -> J2Rates {
+    let n = (body.mu / a.powi(3)).sqrt();
+    let p = a * (1.0 - e * e);
+    let k = n * body.j2 * (body.equatorial_radius / p).powi(2);
+    let cos_i = inclination.cos();
+
+    J2Rates {
+        raan_rate: -1.5 * k * cos_i,
+        arg_periapsis_rate: 0.75 * k * (5.0 * cos_i * cos_i - 1.0),
+        mean_anomaly_rate: n + 0.75 * k * (1.0 - e * e).sqrt() * (3.0 * cos_i * cos_i - 1.0),
+    }
+}
+
+// Acceleration from point-mass gravity plus the J2 zonal term, in m/s^2
+pub fn j2_acceleration(position: Vector3, body: &CentralBody) -> Vector3 {
+    let r = position.norm();
+    let r2 = r * r;
+    let point_mass = position * (-body.mu / (r2 * r));
+
+    let z2_r2 = position.z * position.z / r2;
+    let factor = -1.5 * body.j2 * body.mu * body.equatorial_radius.powi(2) / (r2 * r2 * r);
+    let j2 = Vector3::new(
+        factor * position.x * (1.0 - 5.0 * z2_r2),
+        factor * position.y * (1.0 - 5.0 * z2_r2),
+        factor * position.z * (3.0 - 5.0 * z2_r2),
+    );
+
+    point_mass + j2
+}
+
+// One RK4 step of the J2 equations of motion
+fn rk4_step(state: &StateVector, body: &CentralBody, h: f64) -> StateVector {
+    let derivative = |s: &StateVector| (s.velocity, j2_acceleration(s.position, body));
+    let shifted = |s: &StateVector, d: (Vector3, Vector3), k: f64| {
+        StateVector::new(s.position + d.0 * k, s.velocity + d.1 * k)
+    };
+
+    let k1 = derivative(state);
+    let k2 = derivative(&shifted(state, k1, h / 2.0));
+    let k3 = derivative(&shifted(state, k2, h / 2.0));
+    let k4 = derivative(&shifted(state, k3, h));
+
+    StateVector::new(
+        state.position + (k1.0 + k2.0 * 2.0 + k3.0 * 2.0 + k4.0) * (h / 6.0),
+        state.velocity + (k1.1 + k2.1 * 2.0 + k3.1 * 2.0 + k4.1) * (h / 6.0),
+    )
+}
+
+// Integrate `dt` seconds (either sign) with steps no longer than `max_step`
+pub fn propagate_j2(
+    state: &StateVector,
+    body: &CentralBody,
+    dt: f64,
+    max_step: f64,
+) -> Result<StateVector, CalculationError> {
+    if max_step <= 0.0 || !dt.is_finite() || state.position.norm() <= body.equatorial_radius * 0.5 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let steps = (dt.abs() / max_step).ceil().max(1.0) as usize;
+    let h = dt / steps as f64;
+
+    let mut current = *state;
+    for _ in 0..steps {
+        current = rk4_step(&current, body, h);
+        if !current.position.norm().is_finite() {
+            return Err(CalculationError::OutOfRange);
+        }
+    }
+    Ok(current)
+}
+
+// J2 propagation from checkpoints cached on a fixed grid after the epoch.
+// Every time is reached forward from the nearest earlier checkpoint, so the
+// result does not depend on the order of the queries (bisection, repeated
+// sweeps) and a long window costs one integration however it is sampled.
+#[derive(Debug, Clone)]
+pub struct J2Propagator {
+    state: StateVector,
+    body: CentralBody,
+    checkpoints: RefCell<Vec<StateVector>>,
+}
+
+impl J2Propagator {
+    pub fn new(state: StateVector, body: CentralBody) -> Self {
+        J2Propagator { state, body, checkpoints: RefCell::new(vec![state]) }
+    }
+
+    // State `t` seconds after the epoch; times before it are integrated
+    // directly
+    pub fn state_at(&self, t: f64) -> Result<StateVector, CalculationError> {
+        if t < 0.0 {
+            return propagate_j2(&self.state, &self.body, t, CHECKPOINT_MAX_STEP);
+        }
+        let index = (t / CHECKPOINT_STEP).floor() as usize;
+        let mut cache = self.checkpoints.borrow_mut();
+        while cache.len() <= index {
+            let next = propagate_j2(&cache[cache.len() - 1], &self.body, CHECKPOINT_STEP, CHECKPOINT_MAX_STEP)?;
+            cache.push(next);
+        }
+        propagate_j2(&cache[index], &self.body, t - index as f64 * CHECKPOINT_STEP, CHECKPOINT_MAX_STEP)
+    }
+}
+
+// Time series from 0 to `end` seconds, one point every `output_step`
+pub fn propagate_j2_series(
+    state: &StateVector,
+    body: &CentralBody,
+    end: f64,
+    output_step: f64,
+    max_step: f64,
+) -> Result<Vec<EphemerisPoint>, CalculationError> {
+    if output_step <= 0.0 || !end.is_finite() {
+        return Err(CalculationError::InvalidInput);
+    }
+    let direction = end.signum();
+    let mut points = vec![EphemerisPoint { time: 0.0, state: *state }];
+    let mut time = 0.0;
+    let mut current = *state;
+
+    while (end - time) * direction > 0.0 {
+        let next_time = if ((end - time) * direction) > output_step { time + output_step * direction } else { end };
+        current = propagate_j2(&current, body, next_time - time, max_step)?;
+        time = next_time;
+        points.push(EphemerisPoint { time, state: current });
+    }
+    Ok(points)
+}
+
+// Inclination (rad) that makes the J2 nodal regression match `nodal_rate`
+// (EARTH_SUN_SYNCHRONOUS_RATE for a sun-synchronous Earth orbit)
+pub fn sun_synchronous_inclination(
+    body: &CentralBody,
+    altitude: f64,
+    eccentricity: f64,
+    nodal_rate: f64,
+) -> Result<f64, CalculationError> {
+    if altitude <= 0.0 || !(0.0..1.0).contains(&eccentricity) {
+        return Err(CalculationError::InvalidInput);
+    }
+    if body.j2 == 0.0 {
+        return Err(CalculationError::DivisionByZero);
+    }
+    let a = body.equatorial_radius + altitude;
+    let n = (body.mu / a.powi(3)).sqrt();
+    let p = a * (1.0 - eccentricity * eccentricity);
+    let cos_i = -nodal_rate / (1.5 * n * body.j2 * (body.equatorial_radius / p).powi(2));
+    // Too high: J2 is too weak to precess the node fast enough
+    if cos_i.abs() > 1.0 {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(cos_i.acos())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrozenOrbit {
+    pub eccentricity: f64,
+    pub arg_periapsis: f64, // rad
+}
+
+impl fmt::Display for FrozenOrbit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Frozen eccentricity: {:.6}, argument of periapsis: {:.1} deg",
+            self.eccentricity,
+            self.arg_periapsis.to_degrees()
+        )
+    }
+}
+
+// Frozen orbit from the J2/J3 balance: the mean eccentricity vector stays
+// fixed when the argument of periapsis is 90 deg (or 270 deg) and
+//
+//     e = -(J3 / (2 J2)) (R / a) sin i
+//
+// A body without a J3 term only has the circular frozen orbit.
+pub fn frozen_orbit(body: &CentralBody, semi_major_axis: f64, inclination: f64) -> Result<FrozenOrbit, CalculationError> {
+    if semi_major_axis <= body.equatorial_radius || !(0.0..=PI).contains(&inclination) {
+        return Err(CalculationError::InvalidInput);
+    }
+    if body.j2 == 0.0 {
+        return Err(CalculationError::DivisionByZero);
+    }
+    let e = -(body.j3 / (2.0 * body.j2)) * (body.equatorial_radius / semi_major_axis) * inclination.sin();
+    let arg_periapsis = if e >= 0.0 { PI / 2.0 } else { 1.5 * PI };
+    Ok(FrozenOrbit { eccentricity: e.abs(), arg_periapsis })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leo() -> StateVector {
+        StateVector::new(Vector3::new(7000e3, 0.0, 0.0), Vector3::new(0.0, 5000.0, 5500.0))
+    }
+
+    #[test]
+    fn cached_states_do_not_depend_on_query_order() {
+        let body = CentralBody::earth();
+        let forward = J2Propagator::new(leo(), body.clone());
+        let backward = J2Propagator::new(leo(), body.clone());
+        let times = [0.0, 450.0, 1234.5, 3600.0, 86400.0];
+        let first: Vec<StateVector> = times.iter().map(|&t| forward.state_at(t).unwrap()).collect();
+        let second: Vec<StateVector> = times.iter().rev().map(|&t| backward.state_at(t).unwrap()).collect();
+        for (a, b) in first.iter().zip(second.iter().rev()) {
+            assert_eq!(a, b);
+        }
+        // A checkpoint chain agrees with one direct integration
+        let direct = propagate_j2(&leo(), &body, 3600.0, 10.0).unwrap();
+        assert!((first[3].position - direct.position).norm() < 1e-3);
+    }
+
+    #[test]
+    fn times_before_the_epoch_integrate_backwards() {
+        let body = CentralBody::earth();
+        let propagator = J2Propagator::new(leo(), body.clone());
+        let earlier = propagator.state_at(-900.0).unwrap();
+        assert_eq!(earlier, propagate_j2(&leo(), &body, -900.0, 10.0).unwrap());
+        assert_eq!(propagator.state_at(f64::NAN), Err(CalculationError::InvalidInput));
+    }
+}
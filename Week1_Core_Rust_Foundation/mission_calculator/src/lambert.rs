@@ -0,0 +1,299 @@
+// Lambert's problem: the orbit connecting two positions in a given time
+//
+// Implements Izzo's algorithm ("Revisiting Lambert's problem", Celestial
+// Mechanics and Dynamical Astronomy 121, 2015), which handles single and
+// multiple revolution transfers with Householder iterations on the
+// non-dimensional variable x. Multi-revolution transfers have two solutions
+// per revolution count, the "low path" (x closer to 1) and the "high path".
+use crate::CalculationError;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+
+const MAX_ITERATIONS: usize = 35;
+const TOLERANCE: f64 = 1e-11;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LambertSolution {
+    pub departure_velocity: Vector3, // m/s at r1
+    pub arrival_velocity: Vector3,   // m/s at r2
+    pub revolutions: u32,
+}
+
+// Solve for the transfer from `r1` to `r2` (m) in `time_of_flight` seconds.
+//
+// `prograde` selects motion counter-clockwise about +z. For multi-revolution
+// transfers `low_path` picks between the two solutions.
+pub fn solve_lambert(
+    r1: Vector3,
+    r2: Vector3,
+    time_of_flight: f64,
+    mu: f64,
+    revolutions: u32,
+    prograde: bool,
+    low_path: bool,
+) -> Result<LambertSolution, CalculationError> {
+    if mu <= 0.0 || time_of_flight <= 0.0 || !time_of_flight.is_finite() {
+        return Err(CalculationError::InvalidInput);
+    }
+    let r1_norm = r1.norm();
+    let r2_norm = r2.norm();
+    let chord = r2 - r1;
+    let c = chord.norm();
+    if r1_norm == 0.0 || r2_norm == 0.0 || c == 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+
+    let s = (r1_norm + r2_norm + c) / 2.0;
+    let i_r1 = r1 / r1_norm;
+    let i_r2 = r2 / r2_norm;
+    // A 180 deg transfer does not define a plane
+    let i_h = i_r1.cross(i_r2).normalized().ok_or(CalculationError::InvalidInput)?;
+
+    let mut lambda = (1.0 - (c / s).min(1.0)).sqrt();
+    let (mut i_t1, mut i_t2) = if i_h.z < 0.0 {
+        lambda = -lambda;
+        (i_r1.cross(i_h), i_r2.cross(i_h))
+    } else {
+        (i_h.cross(i_r1), i_h.cross(i_r2))
+    };
+    if !prograde {
+        lambda = -lambda;
+        i_t1 = -i_t1;
+        i_t2 = -i_t2;
+    }
+
+    // Non-dimensional time of flight
+    let t = (2.0 * mu / s.powi(3)).sqrt() * time_of_flight;
+    let x = find_x(lambda, t, revolutions, low_path)?;
+    let y = compute_y(x, lambda);
+
+    let gamma = (mu * s / 2.0).sqrt();
+    let rho = (r1_norm - r2_norm) / c;
+    let sigma = (1.0 - rho * rho).sqrt();
+
+    let v_r1 = gamma * ((lambda * y - x) - rho * (lambda * y + x)) / r1_norm;
+    let v_r2 = -gamma * ((lambda * y - x) + rho * (lambda * y + x)) / r2_norm;
+    let v_t1 = gamma * sigma * (y + lambda * x) / r1_norm;
+    let v_t2 = gamma * sigma * (y + lambda * x) / r2_norm;
+
+    Ok(LambertSolution {
+        departure_velocity: i_r1 * v_r1 + i_t1 * v_t1,
+        arrival_velocity: i_r2 * v_r2 + i_t2 * v_t2,
+        revolutions,
+    })
+}
+
+// Every solution up to `max_revolutions` complete revolutions (one for the
+// direct transfer, two per feasible revolution count after that)
+pub fn solve_lambert_all(
+    r1: Vector3,
+    r2: Vector3,
+    time_of_flight: f64,
+    mu: f64,
+    max_revolutions: u32,
+    prograde: bool,
+) -> Result<Vec<LambertSolution>, CalculationError> {
+    let mut solutions = vec![solve_lambert(r1, r2, time_of_flight, mu, 0, prograde, true)?];
+    for revolutions in 1..=max_revolutions {
+        for low_path in [true, false] {
+            match solve_lambert(r1, r2, time_of_flight, mu, revolutions, prograde, low_path) {
+                Ok(solution) => solutions.push(solution),
+                Err(CalculationError::OutOfRange) => return Ok(solutions),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(solutions)
+}
+
+fn find_x(lambda: f64, t: f64, revolutions: u32, low_path: bool) -> Result<f64, CalculationError> {
+    let m = revolutions as f64;
+    let mut m_max = (t / PI).floor();
+    let t_00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+
+    // Refine the largest feasible revolution count near its minimum time
+    if t < t_00 + m_max * PI && m_max > 0.0 {
+        let t_min = minimum_time(lambda, m_max)?;
+        if t < t_min {
+            m_max -= 1.0;
+        }
+    }
+    if m > m_max {
+        return Err(CalculationError::OutOfRange);
+    }
+
+    let x0 = initial_guess(t, lambda, m, low_path);
+    householder(x0, t, lambda, m)
+}
+
+fn compute_y(x: f64, lambda: f64) -> f64 {
+    (1.0 - lambda * lambda * (1.0 - x * x)).sqrt()
+}
+
+fn compute_psi(x: f64, y: f64, lambda: f64) -> f64 {
+    if (-1.0..1.0).contains(&x) {
+        (x * y + lambda * (1.0 - x * x)).clamp(-1.0, 1.0).acos()
+    } else if x > 1.0 {
+        ((y - x * lambda) * (x * x - 1.0).sqrt()).asinh()
+    } else {
+        0.0
+    }
+}
+
+// Non-dimensional time of flight as a function of x
+fn time_of_flight(x: f64, y: f64, lambda: f64, m: f64) -> f64 {
+    // Near x = 1 the general expression loses precision; use the series
+    // in the hypergeometric function instead (Izzo, eq. 20)
+    if m == 0.0 && x > 0.6_f64.sqrt() && x < 1.4_f64.sqrt() {
+        let eta = y - lambda * x;
+        let s1 = (1.0 - lambda - x * eta) * 0.5;
+        let q = 4.0 / 3.0 * hyp2f1b(s1);
+        (eta.powi(3) * q + 4.0 * lambda * eta) * 0.5
+    } else {
+        let psi = compute_psi(x, y, lambda);
+        ((psi + m * PI) / (1.0 - x * x).abs().sqrt() - x + lambda * y) / (1.0 - x * x)
+    }
+}
+
+// First three derivatives of the time of flight with respect to x
+fn time_derivatives(x: f64, y: f64, t: f64, lambda: f64) -> (f64, f64, f64) {
+    let l2 = lambda * lambda;
+    let l3 = l2 * lambda;
+    let d1 = (3.0 * t * x - 2.0 + 2.0 * l3 * x / y) / (1.0 - x * x);
+    let d2 = (3.0 * t + 5.0 * x * d1 + 2.0 * (1.0 - l2) * l3 / y.powi(3)) / (1.0 - x * x);
+    let d3 = (7.0 * x * d2 + 8.0 * d1 - 6.0 * (1.0 - l2) * l3 * l2 * x / y.powi(5)) / (1.0 - x * x);
+    (d1, d2, d3)
+}
+
+// Gauss hypergeometric function 2F1(3, 1, 5/2, x)
+fn hyp2f1b(x: f64) -> f64 {
+    if x >= 1.0 {
+        return f64::INFINITY;
+    }
+    let mut result = 1.0;
+    let mut term = 1.0;
+    let mut i = 0.0;
+    loop {
+        term = term * (3.0 + i) * (1.0 + i) / (2.5 + i) * x / (i + 1.0);
+        let previous = result;
+        result += term;
+        if previous == result {
+            return result;
+        }
+        i += 1.0;
+    }
+}
+
+// Minimum time of flight for `m` revolutions, found with Halley iterations
+// on dT/dx = 0
+fn minimum_time(lambda: f64, m: f64) -> Result<f64, CalculationError> {
+    if lambda == 1.0 {
+        return Ok(time_of_flight(0.0, compute_y(0.0, lambda), lambda, m));
+    }
+    let mut x = 0.1;
+    for _ in 0..MAX_ITERATIONS {
+        let y = compute_y(x, lambda);
+        let t = time_of_flight(x, y, lambda, m);
+        let (d1, d2, d3) = time_derivatives(x, y, t, lambda);
+        if d2 == 0.0 {
+            return Err(CalculationError::DivisionByZero);
+        }
+        let next = x - 2.0 * d1 * d2 / (2.0 * d2 * d2 - d1 * d3);
+        if (next - x).abs() < TOLERANCE {
+            return Ok(time_of_flight(next, compute_y(next, lambda), lambda, m));
+        }
+        x = next;
+    }
+    Err(CalculationError::OutOfRange)
+}
+
+fn initial_guess(t: f64, lambda: f64, m: f64, low_path: bool) -> f64 {
+    if m == 0.0 {
+        let t_0 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        let t_1 = 2.0 * (1.0 - lambda.powi(3)) / 3.0;
+        if t >= t_0 {
+            (t_0 / t).powf(2.0 / 3.0) - 1.0
+        } else if t < t_1 {
+            2.5 * t_1 / t * (t_1 - t) / (1.0 - lambda.powi(5)) + 1.0
+        } else {
+            // Corrected form of Izzo's eq. 30 (see poliastro issue #1362)
+            (2.0_f64.ln() * (t / t_0).ln() / (t_1 / t_0).ln()).exp() - 1.0
+        }
+    } else {
+        let left = ((m * PI + PI) / (8.0 * t)).powf(2.0 / 3.0);
+        let x_left = (left - 1.0) / (left + 1.0);
+        let right = ((8.0 * t) / (m * PI)).powf(2.0 / 3.0);
+        let x_right = (right - 1.0) / (right + 1.0);
+        if low_path { x_left.max(x_right) } else { x_left.min(x_right) }
+    }
+}
+
+// Householder (fourth order) iterations on T(x) = t
+fn householder(mut x: f64, t: f64, lambda: f64, m: f64) -> Result<f64, CalculationError> {
+    for _ in 0..MAX_ITERATIONS {
+        let y = compute_y(x, lambda);
+        let t_x = time_of_flight(x, y, lambda, m);
+        let f = t_x - t;
+        let (d1, d2, d3) = time_derivatives(x, y, t_x, lambda);
+        let next = x - f * ((d1 * d1 - f * d2 / 2.0) / (d1 * (d1 * d1 - f * d2) + d3 * f * f / 6.0));
+        if !next.is_finite() {
+            return Err(CalculationError::OutOfRange);
+        }
+        if (next - x).abs() < TOLERANCE {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(CalculationError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::StateVector;
+    use crate::propagate::propagate_state;
+
+    const MU: f64 = 398600e9; // m^3/s^2, Earth as in Curtis
+
+    fn km(x: f64, y: f64, z: f64) -> Vector3 {
+        Vector3::new(x, y, z) * 1000.0
+    }
+
+    // The departure state must coast onto r2 with the arrival velocity
+    fn assert_connects(r1: Vector3, r2: Vector3, time_of_flight: f64, solution: &LambertSolution) {
+        let arrival = propagate_state(&StateVector::new(r1, solution.departure_velocity), MU, time_of_flight).unwrap();
+        assert!((arrival.position - r2).norm() < 1e-2, "misses r2 by {} m", (arrival.position - r2).norm());
+        assert!((arrival.velocity - solution.arrival_velocity).norm() < 1e-5);
+    }
+
+    #[test]
+    fn curtis_example_5_2() {
+        let (r1, r2) = (km(5000.0, 10000.0, 2100.0), km(-14600.0, 2500.0, 7000.0));
+        let solution = solve_lambert(r1, r2, 3600.0, MU, 0, true, true).unwrap();
+        assert!((solution.departure_velocity - km(-5.9925, 1.9254, 3.2456)).norm() < 0.1);
+        assert!((solution.arrival_velocity - km(-3.3125, -4.1966, -0.38529)).norm() < 0.1);
+        assert_connects(r1, r2, 3600.0, &solution);
+    }
+
+    #[test]
+    fn retrograde_transfer_goes_the_other_way() {
+        let (r1, r2) = (km(5000.0, 10000.0, 2100.0), km(-14600.0, 2500.0, 7000.0));
+        let solution = solve_lambert(r1, r2, 3600.0, MU, 0, false, true).unwrap();
+        assert!(r1.cross(solution.departure_velocity).z < 0.0);
+        assert_connects(r1, r2, 3600.0, &solution);
+    }
+
+    #[test]
+    fn multi_revolution_solutions_connect() {
+        let (r1, r2) = (km(7000.0, 0.0, 0.0), km(-2000.0, 8000.0, 1000.0));
+        let time_of_flight = 5.0 * 3600.0;
+        let solutions = solve_lambert_all(r1, r2, time_of_flight, MU, 2, true).unwrap();
+        assert_eq!(solutions.iter().map(|s| s.revolutions).collect::<Vec<_>>(), [0, 1, 1, 2, 2]);
+        for solution in &solutions {
+            assert_connects(r1, r2, time_of_flight, solution);
+        }
+        assert_ne!(solutions[1].departure_velocity, solutions[2].departure_velocity);
+        // Too many revolutions for the time of flight
+        assert!(solve_lambert(r1, r2, time_of_flight, MU, 10, true, true).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+// Mission calculator library
+//
+// `calculate` is side-effect free: it never prints, it only returns a
+// `CalculationResult` that carries a typed quantity (see units.rs) and a
+// human readable label. Front ends (the interactive menu in main.rs) decide
+// how to present it.
+use std::fmt;
+
+pub mod antenna;
+pub mod atmosphere;
+pub mod batch;
+pub mod body;
+pub mod cli;
+pub mod decay;
+pub mod doppler;
+pub mod eclipse;
+pub mod elements;
+pub mod ephemeris;
+mod escape;
+pub mod frames;
+pub mod ground_station;
+pub mod ground_track;
+pub mod j2;
+pub mod lambert;
+pub mod link_budget;
+pub mod maneuvers;
+pub mod mission_budget;
+pub mod path_loss;
+pub mod porkchop;
+pub mod power;
+pub mod propagate;
+pub mod sgp4;
+pub mod staging;
+pub mod thermal;
+pub mod time;
+pub mod tle;
+pub mod units;
+pub mod vector;
+
+pub use antenna::{Antenna, AntennaGain};
+pub use body::{BodyCatalog, CentralBody};
+pub use elements::{OrbitalElements, StateVector};
+pub use frames::{Frame, Geodetic};
+pub use time::Epoch;
+pub use units::{Angle, Decibels, Energy, Force, Frequency, Length, Mass, Power, Time, Velocity};
+pub use vector::{Matrix3, Vector3};
+
+// Temperatures share the SpaceSensor converter's types
+pub use spacesensor_temperature_converter::{Celsius, Kelvin};
+
+// Define errors that might occur in calculations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalculationError {
+    InvalidInput,
+    DivisionByZero,
+    NegativeValue,
+    OutOfRange,
+}
+
+impl fmt::Display for CalculationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            CalculationError::InvalidInput => "invalid input",
+            CalculationError::DivisionByZero => "division by zero",
+            CalculationError::NegativeValue => "value must be positive",
+            CalculationError::OutOfRange => "value out of range",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl CalculationError {
+    // Process exit code reported by the command line, one per error kind
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CalculationError::InvalidInput => 3,
+            CalculationError::DivisionByZero => 4,
+            CalculationError::NegativeValue => 5,
+            CalculationError::OutOfRange => 6,
+        }
+    }
+}
+
+impl std::error::Error for CalculationError {}
+
+// Orbital variants take the central body they are computed around. Fields
+// are dimensioned quantities, so callers state the unit they enter values in.
+// States and sites say which frame they are given in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaceCalculation {
+    OrbitalVelocity { radius: Length, body: CentralBody },
+    EscapeVelocity { radius: Length, body: CentralBody },
+    OrbitalPeriod { radius: Length, body: CentralBody },
+    Hohmann { r1: Length, r2: Length, body: CentralBody },
+    TsiolkovskyRocket { exhaust_velocity: Velocity, init_mass: Mass, final_mass: Mass },
+    GravitationalForce { msat: Mass, radius: Length, body: CentralBody },
+    GeostationaryOrbitRadius { body: CentralBody },
+    OrbitalEnergy { msat: Mass, radius: Length, body: CentralBody },
+    EscapeEnergy { msat: Mass, radius: Length, body: CentralBody },
+    LinBudgetEquation {
+        pt: Power,
+        gt: AntennaGain,
+        gr: AntennaGain,
+        frequency: Option<Frequency>, // needed by modeled antennas only
+        lp: Decibels,
+        ls: Decibels,
+        lm: Decibels,
+    },
+    PathLoss { radius: Length, frequency: Frequency },
+    InclineAngle {
+        possatx: Length,
+        possaty: Length,
+        possatz: Length,
+        velsatx: Velocity,
+        velsaty: Velocity,
+        velsatz: Velocity,
+        frame: Frame,
+        epoch: Epoch, // orients the frames of date and the Earth-fixed frames
+    },
+    // Line of sight from a site on the body's ellipsoid (body-fixed)
+    SlantRange { altitude: Length, elevation: Angle, azimuth: Angle, site: Geodetic, body: CentralBody },
+}
+
+// Physical unit attached to a calculation result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    MetersPerSecond,
+    Seconds,
+    Meters,
+    Newtons,
+    Joules,
+    Decibels,
+    DecibelMilliwatts,
+    Degrees,
+}
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::MetersPerSecond => "m/s",
+            Unit::Seconds => "s",
+            Unit::Meters => "m",
+            Unit::Newtons => "N",
+            Unit::Joules => "J",
+            Unit::Decibels => "dB",
+            Unit::DecibelMilliwatts => "dBm",
+            Unit::Degrees => "deg",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+// Typed value of a calculation result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    Length(Length),
+    Velocity(Velocity),
+    Time(Time),
+    Force(Force),
+    Energy(Energy),
+    Power(Power),
+    Gain(Decibels),
+    Angle(Angle),
+}
+
+impl Quantity {
+    // Unit the quantity is reported in
+    pub fn unit(&self) -> Unit {
+        match self {
+            Quantity::Length(_) => Unit::Meters,
+            Quantity::Velocity(_) => Unit::MetersPerSecond,
+            Quantity::Time(_) => Unit::Seconds,
+            Quantity::Force(_) => Unit::Newtons,
+            Quantity::Energy(_) => Unit::Joules,
+            Quantity::Power(_) => Unit::DecibelMilliwatts,
+            Quantity::Gain(_) => Unit::Decibels,
+            Quantity::Angle(_) => Unit::Degrees,
+        }
+    }
+
+    // Value expressed in `unit()`
+    pub fn value(&self) -> f64 {
+        match *self {
+            Quantity::Length(q) => q.as_meters(),
+            Quantity::Velocity(q) => q.as_meters_per_second(),
+            Quantity::Time(q) => q.as_seconds(),
+            Quantity::Force(q) => q.as_newtons(),
+            Quantity::Energy(q) => q.as_joules(),
+            Quantity::Power(q) => q.as_dbm(),
+            Quantity::Gain(q) => q.as_db(),
+            Quantity::Angle(q) => q.as_degrees(),
+        }
+    }
+}
+
+// Value returned by `calculate`, tagged with what it is and how it is measured
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalculationResult {
+    pub label: &'static str,
+    pub quantity: Quantity,
+}
+
+impl CalculationResult {
+    pub fn new(label: &'static str, quantity: Quantity) -> Self {
+        CalculationResult { label, quantity }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.quantity.value()
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.quantity.unit()
+    }
+}
+
+impl fmt::Display for CalculationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {}", self.label, self.value(), self.unit())
+    }
+}
+
+pub fn calculate(calc: SpaceCalculation) -> Result<CalculationResult, CalculationError> {
+    match calc {
+        SpaceCalculation::OrbitalVelocity { radius, body } => {
+            if radius <= Length::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let orbital_velocity = (body.mu / radius.as_meters()).sqrt();
+            Ok(CalculationResult::new(
+                "Orbital velocity",
+                Quantity::Velocity(Velocity::from_meters_per_second(orbital_velocity)),
+            ))
+        }
+
+        SpaceCalculation::EscapeVelocity { radius, body } => {
+            if radius <= Length::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let escape_velocity = (2.0 * body.mu / radius.as_meters()).sqrt();
+            Ok(CalculationResult::new(
+                "Escape velocity",
+                Quantity::Velocity(Velocity::from_meters_per_second(escape_velocity)),
+            ))
+        }
+
+        SpaceCalculation::OrbitalPeriod { radius, body } => {
+            if radius <= Length::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let orbital_period = 2.0 * std::f64::consts::PI * (radius.as_meters().powi(3) / body.mu).sqrt();
+            Ok(CalculationResult::new("Orbital period", Quantity::Time(Time::from_seconds(orbital_period))))
+        }
+
+        SpaceCalculation::Hohmann { r1, r2, body } => {
+            if r1 <= Length::ZERO || r2 <= Length::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            if r1 == r2 {
+                return Err(CalculationError::InvalidInput);
+            }
+            let r1 = r1.as_meters();
+            let r2 = r2.as_meters();
+            // Delta-v for Hohmann transfer
+            let delta_v1 = ((body.mu / r1).sqrt()) * ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0);
+            let delta_v2 = ((body.mu / r2).sqrt()) * (1.0 - (2.0 * r1 / (r1 + r2)).sqrt());
+            Ok(CalculationResult::new(
+                "Delta-v for Hohmann transfer",
+                Quantity::Velocity(Velocity::from_meters_per_second(delta_v1 + delta_v2)),
+            ))
+        }
+
+        SpaceCalculation::TsiolkovskyRocket { exhaust_velocity, init_mass, final_mass } => {
+            if exhaust_velocity <= Velocity::ZERO
+                || init_mass <= Mass::ZERO
+                || final_mass <= Mass::ZERO
+                || init_mass <= final_mass
+            {
+                return Err(CalculationError::InvalidInput);
+            }
+            // Tsiolkovsky rocket equation: delta-v = v_e * ln(m_0 / m_f)
+            let delta_v = exhaust_velocity * (init_mass / final_mass).ln();
+            Ok(CalculationResult::new("Rocket delta-v", Quantity::Velocity(delta_v)))
+        }
+
+        SpaceCalculation::GravitationalForce { msat, radius, body } => {
+            if radius <= Length::ZERO || msat <= Mass::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let force = body.mu * msat.as_kilograms() / radius.as_meters().powi(2);
+            Ok(CalculationResult::new("Gravitational force", Quantity::Force(Force::from_newtons(force))))
+        }
+
+        SpaceCalculation::GeostationaryOrbitRadius { body } => {
+            // Synchronous orbit: orbital period equals the sidereal rotation period
+            if body.rotation_rate == 0.0 {
+                return Err(CalculationError::DivisionByZero);
+            }
+            let synchronous_radius = (body.mu / body.rotation_rate.powi(2)).cbrt();
+            Ok(CalculationResult::new(
+                "Geostationary orbit radius",
+                Quantity::Length(Length::from_meters(synchronous_radius)),
+            ))
+        }
+
+        SpaceCalculation::OrbitalEnergy { msat, radius, body } => {
+            if radius <= Length::ZERO || msat <= Mass::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let energy = -body.mu * msat.as_kilograms() / (2.0 * radius.as_meters());
+            Ok(CalculationResult::new("Orbital energy", Quantity::Energy(Energy::from_joules(energy))))
+        }
+
+        SpaceCalculation::EscapeEnergy { msat, radius, body } => {
+            if radius <= Length::ZERO || msat <= Mass::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let energy = body.mu * msat.as_kilograms() / radius.as_meters();
+            Ok(CalculationResult::new("Escape energy", Quantity::Energy(Energy::from_joules(energy))))
+        }
+
+        SpaceCalculation::LinBudgetEquation { pt, gt, gr, frequency, lp, ls, lm } => {
+            // Losses are positive dB values; 0 dB is a lossless element
+            if lp < Decibels::ZERO || ls < Decibels::ZERO || lm < Decibels::ZERO {
+                return Err(CalculationError::NegativeValue);
+            }
+            // Entered gains do not depend on the frequency; modeled ones need it
+            let net_gain = |gain: AntennaGain| -> Result<Decibels, CalculationError> {
+                let (gain_dbi, pointing_loss_db) = match (gain, frequency) {
+                    (AntennaGain::Given { .. }, _) => gain.resolve(0.0)?,
+                    (AntennaGain::Modeled { .. }, Some(frequency)) => gain.resolve(frequency.as_hertz())?,
+                    (AntennaGain::Modeled { .. }, None) => return Err(CalculationError::InvalidInput),
+                };
+                Ok(Decibels::from_db(gain_dbi - pointing_loss_db))
+            };
+            // Gains and losses shift the transmit power level
+            let gains = net_gain(gt)? + net_gain(gr)?;
+            Ok(CalculationResult::new("Received power", Quantity::Power(pt + (gains - lp - ls - lm))))
+        }
+
+        SpaceCalculation::PathLoss { radius, frequency } => {
+            let path_loss = path_loss::free_space_path_loss(radius.as_meters(), frequency.as_hertz())?;
+            Ok(CalculationResult::new("Free-space path loss", Quantity::Gain(Decibels::from_db(path_loss))))
+        }
+
+        SpaceCalculation::InclineAngle { possatx, possaty, possatz, velsatx, velsaty, velsatz, frame, epoch } => {
+            let position = Vector3::new(possatx.as_meters(), possaty.as_meters(), possatz.as_meters());
+            let velocity = Vector3::new(
+                velsatx.as_meters_per_second(),
+                velsaty.as_meters_per_second(),
+                velsatz.as_meters_per_second(),
+            );
+            // Measured from the equator of the input frame. An Earth-fixed
+            // velocity lacks the Earth's rotation, so those states are taken
+            // to the true equator of date first.
+            let julian_date = epoch.julian_date(time::TimeScale::Utc);
+            let mut state = frames::FramedState::new(frame, julian_date, StateVector::new(position, velocity));
+            if frame.is_rotating() {
+                let eop = frames::EopRecord::nominal(julian_date - time::MJD_OFFSET);
+                state = state.to_frame(Frame::TrueOfDate, &eop);
+            }
+            let h = state.state.position.cross(state.state.velocity);
+
+            let h_mag = h.norm();
+            if h_mag == 0.0 {
+                return Err(CalculationError::InvalidInput);
+            }
+
+            let cos_i = h.z / h_mag;
+            if cos_i.abs() > 1.0 {
+                return Err(CalculationError::InvalidInput); // avoid NaN from acos
+            }
+
+            Ok(CalculationResult::new("Incline angle", Quantity::Angle(Angle::from_radians(cos_i.acos()))))
+        }
+
+        SpaceCalculation::SlantRange { altitude, elevation, azimuth, site, body } => {
+            // 0 deg is the horizon, where the range is longest (AOS and LOS)
+            if altitude <= Length::ZERO || elevation < Angle::ZERO {
+                return Err(CalculationError::InvalidInput);
+            }
+            let slant_range = ground_station::slant_range(
+                &body,
+                &site,
+                azimuth.as_radians(),
+                elevation.as_radians(),
+                altitude.as_meters(),
+            )?;
+            Ok(CalculationResult::new("Slant range", Quantity::Length(Length::from_meters(slant_range))))
+        }
+    }
+}
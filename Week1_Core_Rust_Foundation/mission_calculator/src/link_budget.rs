@@ -0,0 +1,235 @@
+// RF link budget in decibels
+//
+// Everything is added and subtracted in dB: gains add, losses subtract, and
+// the received carrier-to-noise density C/N0 follows from the EIRP, the path
+// losses and the receiver figure of merit G/T. The result is the energy per
+// bit to noise density Eb/N0 and the margin over the required Eb/N0.
+// Antenna gains are either entered or modeled (see antenna.rs); each end
+// brings its own pointing loss.
+use crate::CalculationError;
+use crate::antenna::AntennaGain;
+use crate::path_loss::free_space_path_loss;
+use std::fmt;
+
+// Boltzmann constant in dBW/K/Hz, 10 log10(1.380649e-23)
+pub const BOLTZMANN_DB: f64 = -228.599_167;
+
+pub fn to_db(ratio: f64) -> f64 {
+    10.0 * ratio.log10()
+}
+
+pub fn from_db(db: f64) -> f64 {
+    10.0_f64.powf(db / 10.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkBudgetInputs {
+    pub transmit_power_dbw: f64,
+    pub transmit_antenna: AntennaGain,
+    pub transmit_line_loss_db: f64,
+    pub range: f64,     // m
+    pub frequency: f64, // Hz
+    pub atmospheric_loss_db: f64,
+    pub polarization_loss_db: f64,
+    pub receive_antenna: AntennaGain,
+    pub receive_line_loss_db: f64,
+    pub system_noise_temperature: f64, // K
+    pub data_rate: f64,                // bit/s
+    pub required_eb_n0_db: f64,
+}
+
+// One row of the itemized budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkBudgetLine {
+    pub label: &'static str,
+    pub value: f64,
+    pub unit: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkBudget {
+    pub lines: Vec<LinkBudgetLine>,
+    pub eirp_dbw: f64,
+    pub free_space_loss_db: f64,
+    pub g_over_t_db: f64,
+    pub c_over_n0_dbhz: f64,
+    pub eb_n0_db: f64,
+    pub margin_db: f64,
+}
+
+impl LinkBudget {
+    pub fn closes(&self) -> bool {
+        self.margin_db >= 0.0
+    }
+}
+
+impl fmt::Display for LinkBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<34} {:>12} Unit", "Item", "Value")?;
+        writeln!(f, "{}", "-".repeat(54))?;
+        for line in &self.lines {
+            writeln!(f, "{:<34} {:>12.2} {}", line.label, line.value, line.unit)?;
+        }
+        writeln!(f, "{}", "-".repeat(54))?;
+        write!(f, "Link {}", if self.closes() { "closes" } else { "does NOT close" })
+    }
+}
+
+pub fn link_budget(inputs: &LinkBudgetInputs) -> Result<LinkBudget, CalculationError> {
+    let losses = [
+        inputs.transmit_line_loss_db,
+        inputs.atmospheric_loss_db,
+        inputs.polarization_loss_db,
+        inputs.receive_line_loss_db,
+    ];
+    // Losses are entered as positive dB values; 0 dB means lossless
+    if losses.iter().any(|&l| l < 0.0) {
+        return Err(CalculationError::NegativeValue);
+    }
+    if inputs.system_noise_temperature <= 0.0 || inputs.data_rate <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let (transmit_gain, transmit_pointing) = inputs.transmit_antenna.resolve(inputs.frequency)?;
+    let (receive_gain, receive_pointing) = inputs.receive_antenna.resolve(inputs.frequency)?;
+    let pointing_loss = transmit_pointing + receive_pointing;
+
+    let eirp = inputs.transmit_power_dbw + transmit_gain - inputs.transmit_line_loss_db;
+    let fspl = free_space_path_loss(inputs.range, inputs.frequency)?;
+    let received_isotropic = eirp - fspl - inputs.atmospheric_loss_db - pointing_loss - inputs.polarization_loss_db;
+    let noise_temperature_db = to_db(inputs.system_noise_temperature);
+    let g_over_t = receive_gain - inputs.receive_line_loss_db - noise_temperature_db;
+    let received_power = received_isotropic + receive_gain - inputs.receive_line_loss_db;
+    let c_over_n0 = received_isotropic + g_over_t - BOLTZMANN_DB;
+    let data_rate_db = to_db(inputs.data_rate);
+    let eb_n0 = c_over_n0 - data_rate_db;
+    let margin = eb_n0 - inputs.required_eb_n0_db;
+
+    // Adding 0.0 turns the -0.0 of a lossless entry into 0.0 for display
+    let line = |label, value: f64, unit| LinkBudgetLine { label, value: value + 0.0, unit };
+    let lines = vec![
+        line("Transmit power", inputs.transmit_power_dbw, "dBW"),
+        line("Transmit antenna gain", transmit_gain, "dBi"),
+        line("Transmit line loss", -inputs.transmit_line_loss_db, "dB"),
+        line("EIRP", eirp, "dBW"),
+        line("Free-space path loss", -fspl, "dB"),
+        line("Atmospheric loss", -inputs.atmospheric_loss_db, "dB"),
+        line("Pointing loss", -pointing_loss, "dB"),
+        line("Polarization loss", -inputs.polarization_loss_db, "dB"),
+        line("Isotropic received power", received_isotropic, "dBW"),
+        line("Receive antenna gain", receive_gain, "dBi"),
+        line("Receive line loss", -inputs.receive_line_loss_db, "dB"),
+        line("Received power", received_power, "dBW"),
+        line("System noise temperature", noise_temperature_db, "dBK"),
+        line("G/T", g_over_t, "dB/K"),
+        line("Boltzmann constant", BOLTZMANN_DB, "dBW/K/Hz"),
+        line("C/N0", c_over_n0, "dBHz"),
+        line("Data rate", data_rate_db, "dBHz"),
+        line("Eb/N0", eb_n0, "dB"),
+        line("Required Eb/N0", inputs.required_eb_n0_db, "dB"),
+        line("Link margin", margin, "dB"),
+    ];
+
+    Ok(LinkBudget {
+        lines,
+        eirp_dbw: eirp,
+        free_space_loss_db: fspl,
+        g_over_t_db: g_over_t,
+        c_over_n0_dbhz: c_over_n0,
+        eb_n0_db: eb_n0,
+        margin_db: margin,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antenna::Antenna;
+
+    // S-band downlink from 1000 km: 10 W into 6 dBi, 35 dBi at the ground
+    fn downlink() -> LinkBudgetInputs {
+        LinkBudgetInputs {
+            transmit_power_dbw: 10.0,
+            transmit_antenna: AntennaGain::Given { gain_dbi: 6.0, pointing_loss_db: 0.0 },
+            transmit_line_loss_db: 1.0,
+            range: 1.0e6,
+            frequency: 2.2e9,
+            atmospheric_loss_db: 0.5,
+            polarization_loss_db: 0.2,
+            receive_antenna: AntennaGain::Given { gain_dbi: 35.0, pointing_loss_db: 0.0 },
+            receive_line_loss_db: 0.5,
+            system_noise_temperature: 200.0,
+            data_rate: 1.0e6,
+            required_eb_n0_db: 9.6,
+        }
+    }
+
+    #[test]
+    fn decibel_conversions() {
+        assert!((to_db(2.0) - 3.0103).abs() < 1e-4);
+        assert!((from_db(to_db(123.4)) - 123.4).abs() < 1e-9);
+        assert!((BOLTZMANN_DB - to_db(1.380649e-23)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hand_worked_downlink() {
+        let budget = link_budget(&downlink()).unwrap();
+        // FSPL 32.45 + 20 log10(1000 km) + 20 log10(2200 MHz) = 159.30 dB
+        assert!((budget.eirp_dbw - 15.0).abs() < 1e-12);
+        assert!((budget.free_space_loss_db - 159.296).abs() < 1e-3);
+        // 35 - 0.5 - 10 log10(200)
+        assert!((budget.g_over_t_db - 11.490).abs() < 1e-3);
+        // -145.00 + 11.49 + 228.60
+        assert!((budget.c_over_n0_dbhz - 95.093).abs() < 1e-3);
+        assert!((budget.eb_n0_db - 35.093).abs() < 1e-3);
+        assert!((budget.margin_db - 25.493).abs() < 1e-3);
+        assert!(budget.closes());
+        let margin = budget.lines.last().unwrap();
+        assert_eq!((margin.label, margin.value), ("Link margin", budget.margin_db));
+    }
+
+    #[test]
+    fn margin_goes_negative_at_high_rates() {
+        let fast = LinkBudgetInputs { data_rate: 1.0e9, required_eb_n0_db: 10.0, ..downlink() };
+        let budget = link_budget(&fast).unwrap();
+        assert!(!budget.closes());
+        assert!(budget.to_string().ends_with("Link does NOT close"));
+    }
+
+    #[test]
+    fn pointing_losses_from_both_ends_add() {
+        let inputs = LinkBudgetInputs {
+            transmit_antenna: AntennaGain::Given { gain_dbi: 6.0, pointing_loss_db: 0.3 },
+            receive_antenna: AntennaGain::Given { gain_dbi: 35.0, pointing_loss_db: 0.7 },
+            ..downlink()
+        };
+        let budget = link_budget(&inputs).unwrap();
+        let pointing = budget.lines.iter().find(|l| l.label == "Pointing loss").unwrap();
+        assert!((pointing.value + 1.0).abs() < 1e-12);
+        assert!((link_budget(&downlink()).unwrap().margin_db - budget.margin_db - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn modeled_antenna_matches_its_entered_gain() {
+        let dish = Antenna::ParabolicDish { diameter: 3.7, efficiency: 0.55 };
+        let modeled = LinkBudgetInputs {
+            receive_antenna: AntennaGain::Modeled { antenna: dish, pointing_error: 0.0 },
+            ..downlink()
+        };
+        let gain = dish.gain_dbi(2.2e9).unwrap();
+        let given =
+            LinkBudgetInputs { receive_antenna: AntennaGain::Given { gain_dbi: gain, pointing_loss_db: 0.0 }, ..downlink() };
+        assert_eq!(link_budget(&modeled).unwrap().margin_db, link_budget(&given).unwrap().margin_db);
+    }
+
+    #[test]
+    fn rejects_negative_losses_and_bad_noise() {
+        let negative = LinkBudgetInputs { atmospheric_loss_db: -1.0, ..downlink() };
+        assert_eq!(link_budget(&negative), Err(CalculationError::NegativeValue));
+        let cold = LinkBudgetInputs { system_noise_temperature: 0.0, ..downlink() };
+        assert_eq!(link_budget(&cold), Err(CalculationError::InvalidInput));
+        let silent = LinkBudgetInputs { data_rate: 0.0, ..downlink() };
+        assert_eq!(link_budget(&silent), Err(CalculationError::InvalidInput));
+        let unset = LinkBudgetInputs { frequency: 0.0, ..downlink() };
+        assert_eq!(link_budget(&unset), Err(CalculationError::InvalidInput));
+    }
+}
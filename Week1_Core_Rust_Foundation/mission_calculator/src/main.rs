@@ -1,206 +1,60 @@
-// Define errors that might occur in calculations
-#[derive(Debug)]
-enum CalculationError {
-    InvalidInput,
-    DivisionByZero,
-    NegativeValue,
-    OutOfRange,
-}
-enum SpaceCalculation {
-    OrbitalVelocity { radius: f64,},
-    EscapeVelocity { radius: f64 },
-    OrbitalPeriod { radius: f64},
-    Hohmann { r1: f64, r2: f64, central_mass: f64 },
-    TsiolkovskyRocket { exhaust_velocity: f64, init_mass: f64, final_mass: f64 },
-    GravitationalForce {  msat: f64, radius: f64 },
-    GeostationaryOrbitRadius {  },
-    OrbitalEnergy { msat: f64, radius: f64 },
-    EscapeEnergy { msat: f64, radius: f64 },
-    LinBudgetEquation{
-        pt: f64,
-        gt: f64,
-        gr: f64,
-        lp: f64,
-        ls: f64,
-        lm: f64,
-    },
-    PathLoss { radius: f64, frequency: f64 },
-    InclineAngle { possatx: f64, possaty: f64, possatz: f64, velsatx: f64, velsaty: f64, velsatz: f64 },
-    SlantRange { altitude: f64, elevation: f64 },
-}
-fn calculate(calc: SpaceCalculation) -> Result<f64, CalculationError> {
-    const G: f64 = 6.67430e-11; // Gravitational constant
-    const mearth: f64 = 5.972e24; // Mass of Earth in kg
-    const rearth:f64=6371.0; // Radius of Earth in km
-    
-    match calc {
-        SpaceCalculation::OrbitalVelocity { radius } => {
-            if radius <= 0.0 || mearth <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            
-            let orbital_velocity = (G * mearth / radius).sqrt();
-            //ok function mention the  unit of the result  in terminal 
-            println!("Orbital velocity: {} m/s", orbital_velocity);
-            Ok(orbital_velocity) // Result in meters per second (m/s)
-             
-        },
-        
-        SpaceCalculation::EscapeVelocity { radius} => {
-            if radius <= 0.0 || mearth <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-
-            let radius = radius * 1000.0;
-            //print the unit of the result in terminal
-            println!("Escape velocity: {} m/s", (2.0 * G * mearth / radius).sqrt());
-            Ok((2.0 * G * mearth / radius).sqrt())
-        },
-        
-        SpaceCalculation::OrbitalPeriod { radius } => {
-            if radius <= 0.0 || mearth <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            let orbital_period = 2.0 * std::f64::consts::PI * (radius.powi(3) / (G * mearth)).sqrt();
-            println!("Orbital period: {} seconds", orbital_period);
-            Ok(orbital_period)
-        },
-        
-        SpaceCalculation::Hohmann { r1, r2, central_mass } => {
-            if r1 <= 0.0 || r2 <= 0.0 || central_mass <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            if r1 == r2 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert r1 and r2 to meters
-            let r1 = r1 * 1000.0; // Convert km to m
-            let r2 = r2 * 1000.0; // Convert km to m
-            // Delta-v for Hohmann transfer
-            let delta_v1 = ((G * central_mass / r1).sqrt()) * 
-                          ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0);
-            let delta_v2 = ((G * central_mass / r2).sqrt()) * 
-                          (1.0 - (2.0 * r1 / (r1 + r2)).sqrt());
-            println!("Delta-v for Hohmann transfer: {} m/s", delta_v1 + delta_v2);
-                          
-            Ok(delta_v1 + delta_v2) // Total delta-v
-        },
-        
-        SpaceCalculation::TsiolkovskyRocket { exhaust_velocity, init_mass, final_mass } => {
-            if exhaust_velocity <= 0.0 || init_mass <= 0.0 || final_mass <= 0.0 || init_mass <= final_mass {
-                return Err(CalculationError::InvalidInput);
-            }
-            
-            // Tsiolkovsky rocket equation: delta-v = v_e * ln(m_0 / m_f)
-            let delta_v = exhaust_velocity * (init_mass / final_mass).ln();
-            println!("Rocket delta-v: {} m/s", delta_v);
-            Ok(delta_v)
-        }
-        SpaceCalculation::GravitationalForce {  msat, radius } => {
-            if radius <= 0.0 || mearth <= 0.0 || msat <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            println!("Gravitational force: {} N", (G * mearth * msat) / radius.powi(2));
-            Ok((G * mearth * msat) / radius.powi(2))
-        }
-        SpaceCalculation::GeostationaryOrbitRadius {  } => {
-            let geostationary_radius = (G * mearth * 24.0 * 3600.0_f64.powi(2) / (4.0 * std::f64::consts::PI.powi(2))).cbrt();
-            println!("Geostationary orbit radius: {} km", geostationary_radius / 1000.0);
-            Ok(geostationary_radius)
-        }
-        SpaceCalculation::OrbitalEnergy { msat, radius } => {
-            if radius <= 0.0 || mearth <= 0.0 || msat <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            println!("Orbital Energy :{} julies", -G * mearth * msat / (2.0 * radius));
-            Ok(-G * mearth * msat / (2.0 * radius))
-        }
-        SpaceCalculation::EscapeEnergy { msat, radius } => {
-            if radius <= 0.0 || mearth <= 0.0 || msat <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            println!("Escape energy: {} joules", G * mearth * msat / radius);
-            Ok(G * mearth * msat / radius)
-        }
-        SpaceCalculation::LinBudgetEquation{pt, gt, gr, lp, ls, lm} => {
-            if pt <= 0.0 || gt <= 0.0 || gr <= 0.0 || lp <= 0.0 || ls <= 0.0 || lm <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert pt, gt, gr, lp, ls, lm to dB
-            let pt = 10.0_f64.powf(pt / 10.0);      
-            let gt = 10.0_f64.powf(gt / 10.0);
-            let gr = 10.0_f64.powf(gr / 10.0);
-            let lp = 10.0_f64.powf(lp / 10.0);
-            let ls = 10.0_f64.powf(ls / 10.0);
-            let lm = 10.0_f64.powf(lm / 10.0);
-            //calculate the lin budget equation
-            
-            println!("Lin budget equation: {} dB", pt + gt + gr - lp - ls - lm);
-            Ok(pt + gt + gr - lp - ls - lm)
-        }
-        SpaceCalculation::PathLoss{radius, frequency} => {
-            if radius <= 0.0 || frequency <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert radius to meters
-            let radius = radius * 1000.0; // Convert km to m
-            //convert frequency to Hz
-            let wavelength = 3e8 / frequency;
-            //calculate the path loss
-            println!("Path loss: {} dB", 20.0 * radius.ln() + 20.0 * wavelength.ln() + 32.44);
-            Ok(20.0 * radius.ln() + 20.0 * wavelength.ln() + 32.44)
-        }
-        SpaceCalculation::InclineAngle { possatx, possaty, possatz, velsatx, velsaty, velsatz } => {
-            let h_x = possaty * velsatz - possatz * velsaty;
-            let h_y = possatz * velsatx - possatx * velsatz;
-            let h_z = possatx * velsaty - possaty * velsatx;
-        
-            let h_mag = (h_x.powi(2) + h_y.powi(2) + h_z.powi(2)).sqrt();
-        
-            if h_mag == 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-        
-            let cos_i = h_z / h_mag;
-            if cos_i.abs() > 1.0 {
-                return Err(CalculationError::InvalidInput); // avoid NaN from acos
-            }
-        
-            let inclination_rad = cos_i.acos();
-            let inclination_deg = inclination_rad.to_degrees();
-            println!("Incline angle: {} degrees", inclination_deg);
-            Ok(inclination_deg)
-        }
-        
-        SpaceCalculation::SlantRange{altitude, elevation} => {
-            if altitude <= 0.0 || elevation <= 0.0 {
-                return Err(CalculationError::InvalidInput);
-            }
-            //convert altitude to meters
-            let altitude = altitude * 1000.0; // Convert km to m
-            //convert elevation to radians
-            let elevation = elevation.to_radians();
-            //calculate the slant range
-            let reart = rearth * 1000.0; // Convert km to m
-            println!("Slant range: {} m", ((reart + altitude).powi(2) + reart.powi(2) - 2.0 * reart * (reart + altitude) * (elevation.sin())).sqrt());
-            Ok(((rearth + altitude).powi(2) + rearth.powi(2) - 2.0 * rearth * (rearth + altitude) * (elevation.to_radians().sin())).sqrt())
-        }
-    }
-}
+use mission_calculator::antenna::{element_count, Antenna, AntennaGain};
+use mission_calculator::atmosphere::DensityModel;
+use mission_calculator::batch::{format_results, load_batch_file, run_batch, write_results, BatchResult, OutputFormat};
+use mission_calculator::cli::{parse_args, usage, Invocation, FILE_EXIT_CODE};
+use mission_calculator::decay::{ballistic_coefficient, estimate_lifetime, write_decay_csv, SECONDS_PER_YEAR};
+use mission_calculator::doppler::{doppler_table, write_doppler_csv};
+use mission_calculator::eclipse::{
+    beta_angle, find_eclipses, orbit_illumination, sun_position, write_eclipses_csv, ShadowModel,
+};
+use mission_calculator::ephemeris::{format_date_time, Planet, J2000_JD};
+use mission_calculator::frames::{load_eop_file, parse_frame, EopTable, Frame, FramedState, Geodetic};
+use mission_calculator::ground_station::{predict_passes, write_passes_csv, GroundStation, Pass};
+use mission_calculator::ground_track::{ground_track, swath_edges, swath_polygons, write_ground_track};
+use mission_calculator::j2::{
+    frozen_orbit, propagate_j2_series, secular_rates, sun_synchronous_inclination, J2Propagator,
+    EARTH_SUN_SYNCHRONOUS_RATE,
+};
+use mission_calculator::lambert::solve_lambert_all;
+use mission_calculator::link_budget::{link_budget, LinkBudgetInputs};
+use mission_calculator::maneuvers::{bi_elliptic, combined_plane_change, inclination_change, phasing};
+use mission_calculator::mission_budget::{mission_budget, write_budget_csv, Maneuver, MarginPolicy, MissionPlan, MissionStep};
+use mission_calculator::path_loss::{
+    atmospheric_losses, free_space_path_loss, Atmosphere, RainParameters, ScintillationParameters, SPEED_OF_LIGHT,
+};
+use mission_calculator::porkchop::{minimum_c3, porkchop_grid, write_porkchop_csv, DateRange};
+use mission_calculator::power::{
+    simulate_battery, size_power_system, write_battery_csv, LoadMode, PowerBudgetInputs, Regulation,
+    TYPICAL_BATTERY_EFFICIENCY, TYPICAL_INHERENT_DEGRADATION,
+};
+use mission_calculator::propagate::{
+    propagate_series, propagate_state, write_ephemeris_csv, write_timestamped_ephemeris_csv, EphemerisPoint,
+};
+use mission_calculator::sgp4::Sgp4;
+use mission_calculator::staging::{optimal_staging, Stage, StageDesign, Vehicle};
+use mission_calculator::thermal::{
+    equilibrium_temperature, simulate_transient, write_transient_csv, Conductor, RadiativeLink, Surface,
+    ThermalEnvironment, ThermalModel, ThermalNode, EARTH_ALBEDO, EARTH_IR,
+};
+use mission_calculator::time::{parse_epoch, Epoch, TimeScale};
+use mission_calculator::tle::{load_tle_file, Tle};
+use mission_calculator::{
+    calculate, Angle, BodyCatalog, CalculationError, CalculationResult, Celsius, CentralBody, Decibels, Frequency, Kelvin,
+    Length, Mass, OrbitalElements, Power, SpaceCalculation, StateVector, Vector3, Velocity,
+};
+use std::env;
 use std::io::{self, Write};
+use std::process;
 
 fn main() {
+    // Any argument selects the command-line mode; none starts the menu
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        process::exit(run_command_line(&args));
+    }
+    
     println!("=== Space Mission Calculator ===");
+    let mut catalog = BodyCatalog::builtin();
     
     loop {
         println!("\nSelect calculation type:");
@@ -218,6 +72,37 @@ fn main() {
         println!("12. Path Loss");
         println!("13. Incline Angle");
         println!("14. Slant Range");
+        println!("15. Load central bodies from file");
+        println!("16. State Vector to Orbital Elements");
+        println!("17. Orbital Elements to State Vector");
+        println!("18. Propagate Orbit (Kepler)");
+        println!("19. J2 Secular Rates");
+        println!("20. Sun-Synchronous Inclination");
+        println!("21. Frozen Orbit Design");
+        println!("22. Propagate Orbit (J2)");
+        println!("23. Lambert Transfer");
+        println!("24. Porkchop Plot Data");
+        println!("25. Bi-Elliptic Transfer");
+        println!("26. Inclination Change");
+        println!("27. Combined Plane Change Transfer");
+        println!("28. Phasing Maneuver");
+        println!("29. Multi-Stage Vehicle Performance");
+        println!("30. Optimal Stage Mass Split");
+        println!("31. Link Budget (Eb/N0 and Margin)");
+        println!("32. Atmospheric Path Loss (ITU-R)");
+        println!("33. Ground Station Pass Prediction");
+        println!("34. TLE Propagation (SGP4/SDP4)");
+        println!("35. Batch Calculation File");
+        println!("36. Orbital Lifetime (Drag Decay)");
+        println!("37. Ground Track (GeoJSON/KML)");
+        println!("38. Eclipses and Beta Angle");
+        println!("39. Power Budget and Battery Sizing");
+        println!("40. Thermal Equilibrium and Transient");
+        println!("41. Mission Delta-V Budget");
+        println!("42. Reference Frame Conversion");
+        println!("43. Time Scales and Epoch Formats");
+        println!("44. Doppler Tuning Table");
+        println!("45. Antenna Gain and Pointing Loss");
         println!("0. Exit");
         
         print!("Enter choice: ");
@@ -240,22 +125,71 @@ fn main() {
             break;
         }
         
+        if choice == 15 {
+            load_body_file(&mut catalog);
+            continue;
+        }
+        
+        // Tools that print a multi-line report instead of a single value
+        if choice >= 16 {
+            let report = match choice {
+                16 => report_state_to_elements(&catalog),
+                17 => report_elements_to_state(&catalog),
+                18 => report_kepler_propagation(&catalog),
+                19 => report_j2_rates(&catalog),
+                20 => report_sun_synchronous(&catalog),
+                21 => report_frozen_orbit(&catalog),
+                22 => report_j2_propagation(&catalog),
+                23 => report_lambert(&catalog),
+                24 => report_porkchop(),
+                25 => report_bi_elliptic(&catalog),
+                26 => report_inclination_change(&catalog),
+                27 => report_combined_plane_change(&catalog),
+                28 => report_phasing(&catalog),
+                29 => report_vehicle_performance(),
+                30 => report_optimal_staging(),
+                31 => report_link_budget(),
+                32 => report_atmospheric_loss(),
+                33 => report_passes(),
+                34 => report_tle_propagation(),
+                35 => report_batch(&catalog),
+                36 => report_orbital_lifetime(),
+                37 => report_ground_track(),
+                38 => report_eclipses(),
+                39 => report_power_budget(),
+                40 => report_thermal(),
+                41 => report_mission_budget(&catalog),
+                42 => report_frames(),
+                43 => report_time_scales(),
+                44 => report_doppler(),
+                45 => report_antenna(),
+                _ => {
+                    println!("Invalid choice. Please try again.");
+                    continue;
+                }
+            };
+            if let Err(e) = report {
+                println!("Error: {}", e);
+            }
+            continue;
+        }
+        
         // Process the calculation type
         let calculation_result = match choice {
-            1 => process_orbital_velocity(),
-            2 => process_escape_velocity(),
-            3 => process_orbital_period(),
-            4 => process_hohmann_transfer(),
+            1 => process_orbital_velocity(&catalog),
+            2 => process_escape_velocity(&catalog),
+            3 => process_orbital_period(&catalog),
+            4 => process_hohmann_transfer(&catalog),
             5 => process_rocket_delta_v(),
-            6 => process_slant_range(),
-            7 => process_gravitational_force(),
-            8 => process_geostationary_orbit_radius(),
-            9 => process_orbital_energy(),
-            10 => process_escape_energy(),
+            6 => process_slant_range(&catalog),
+            7 => process_gravitational_force(&catalog),
+            8 => process_geostationary_orbit_radius(&catalog),
+            9 => process_orbital_energy(&catalog),
+            10 => process_escape_energy(&catalog),
             11 => process_lin_budget_equation(),
             12 => process_path_loss(),
             13 => process_incline_angle(),
-            14 => process_slant_range(),
+            14 => process_slant_range(&catalog),
             _ => {
                 println!("Invalid choice. Please try again.");
                 continue;
@@ -264,12 +198,70 @@ fn main() {
         
         // Display the result
         match calculation_result {
-            Ok(value) => println!("Result: {}", value),
-            Err(e) => println!("Error: {:?}", e),
+            Ok(result) => println!("{}", result),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+}
+
+// Run one calculation from the command line and return the exit code
+fn run_command_line(args: &[String]) -> i32 {
+    let invocation = match parse_args(args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Run 'mission_calculator help' for the list of commands.");
+            return e.exit_code();
+        }
+    };
+    match invocation {
+        Invocation::Help => {
+            println!("{}", usage());
+            0
+        }
+        Invocation::Calculation { calculation, value_only } => match calculate(calculation) {
+            Ok(result) if value_only => {
+                println!("{}", result.value());
+                0
+            }
+            Ok(result) => {
+                println!("{}", result);
+                0
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                e.exit_code()
+            }
+        },
+        Invocation::Batch { input, output, catalog } => {
+            let rows = match load_batch_file(&input) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return FILE_EXIT_CODE;
+                }
+            };
+            let results = run_batch(&rows, &catalog);
+            match &output {
+                Some(path) => {
+                    if let Err(e) = write_results(&results, path) {
+                        eprintln!("Error: could not write {}: {}", path, e);
+                        return FILE_EXIT_CODE;
+                    }
+                }
+                None => print!("{}", format_results(&results, OutputFormat::JsonLines)),
+            }
+            eprintln!("{}", batch_summary(&results));
+            0
         }
     }
 }
 
+fn batch_summary(results: &[BatchResult]) -> String {
+    let failed = results.iter().filter(|r| !r.is_ok()).count();
+    format!("Evaluated {} rows, {} failed", results.len(), failed)
+}
+
 // Helper function to read a positive f64 value
 fn read_positive_f64(prompt: &str) -> Result<f64, CalculationError> {
     print!("{}: ", prompt);
@@ -279,6 +271,7 @@ fn read_positive_f64(prompt: &str) -> Result<f64, CalculationError> {
     io::stdin().read_line(&mut input).expect("Failed to read line");
     
     let value: f64 = match input.trim().parse() {
+        Ok(num) if !f64::is_finite(num) => return Err(CalculationError::InvalidInput),
         Ok(num) if num > 0.0 => num,
         Ok(_) => return Err(CalculationError::NegativeValue),
         Err(_) => return Err(CalculationError::InvalidInput),
@@ -287,93 +280,1378 @@ fn read_positive_f64(prompt: &str) -> Result<f64, CalculationError> {
     Ok(value)
 }
 
-// Process functions for each calculation type
-fn process_orbital_velocity() -> Result<f64, CalculationError> {
-    let radius = read_positive_f64("Enter orbital radius (km)")?;
+// Helper function to read any f64 value, negative values included
+fn read_f64(prompt: &str) -> Result<f64, CalculationError> {
+    print!("{}: ", prompt);
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
     
-    calculate(SpaceCalculation::OrbitalVelocity { radius})
+    input.trim().parse().ok().filter(|value: &f64| value.is_finite()).ok_or(CalculationError::InvalidInput)
 }
 
-fn process_escape_velocity() -> Result<f64, CalculationError> {
-    let radius = read_positive_f64("Enter distance from center of mass (km)")?;
+// Helper function to read a count, a whole number of zero or more
+fn read_count(prompt: &str) -> Result<u32, CalculationError> {
+    let value = read_f64(prompt)?;
+    if value < 0.0 {
+        return Err(CalculationError::NegativeValue);
+    }
+    if value.fract() != 0.0 || value > f64::from(u32::MAX) {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(value as u32)
+}
+
+// Helper function to read a count of at least one
+fn read_positive_count(prompt: &str) -> Result<u32, CalculationError> {
+    match read_count(prompt)? {
+        0 => Err(CalculationError::NegativeValue),
+        count => Ok(count),
+    }
+}
+
+// Helper function to read a line of free text, trimmed
+fn read_text(prompt: &str) -> String {
+    print!("{}: ", prompt);
+    io::stdout().flush().unwrap();
     
-    calculate(SpaceCalculation::EscapeVelocity { radius })
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+    input.trim().to_string()
 }
 
-fn process_orbital_period() -> Result<f64, CalculationError> {
-    let radius = read_positive_f64("Enter orbital radius (km)")?;
+// Helper function to offer writing a result to a file, reporting how many
+// `items` went out or why the file could not be written
+fn offer_export<W>(prompt: &str, count: usize, items: &str, write: W)
+where
+    W: FnOnce(&str) -> io::Result<()>,
+{
+    let path = read_text(&format!("{} (leave empty to skip)", prompt));
+    if path.is_empty() {
+        return;
+    }
+    match write(&path) {
+        Ok(()) => println!("Wrote {} {} to {}", count, items, path),
+        Err(e) => println!("Could not write {}: {}", path, e),
+    }
+}
+
+// Helper function to read an epoch in ISO 8601 or CCSDS ASCII form, UTC
+// unless a time scale follows
+fn read_epoch(prompt: &str) -> Result<Epoch, CalculationError> {
+    parse_epoch(&read_text(&format!("{} (e.g. 2024-03-01T12:00:00Z, 2024-061T12:00:00 TT)", prompt)))
+}
+
+// Helper function to read an epoch that may be left out
+fn read_optional_epoch() -> Result<Option<Epoch>, CalculationError> {
+    let text = read_text("Enter epoch of the state (ISO 8601, leave empty for none)");
+    if text.is_empty() { Ok(None) } else { parse_epoch(&text).map(Some) }
+}
+
+// Helper function to read a state vector entered in km and km/s
+fn read_state_vector() -> Result<StateVector, CalculationError> {
+    let position = Vector3::new(
+        read_f64("Enter position x (km)")?,
+        read_f64("Enter position y (km)")?,
+        read_f64("Enter position z (km)")?,
+    );
+    let velocity = Vector3::new(
+        read_f64("Enter velocity x (km/s)")?,
+        read_f64("Enter velocity y (km/s)")?,
+        read_f64("Enter velocity z (km/s)")?,
+    );
+    Ok(StateVector::new(position * 1000.0, velocity * 1000.0))
+}
+
+// Helper function to pick a central body by name, Earth when left empty
+fn read_body(catalog: &BodyCatalog) -> Result<CentralBody, CalculationError> {
+    print!("Enter central body [Earth]: ");
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
     
-    calculate(SpaceCalculation::OrbitalPeriod { radius })
+    let name = input.trim();
+    if name.is_empty() {
+        return Ok(CentralBody::earth());
+    }
+    match catalog.find(name) {
+        Some(body) => Ok(body.clone()),
+        None => {
+            let known: Vec<&str> = catalog.bodies().iter().map(|b| b.name.as_str()).collect();
+            println!("Unknown body '{}'. Known bodies: {}", name, known.join(", "));
+            Err(CalculationError::InvalidInput)
+        }
+    }
 }
 
-fn process_hohmann_transfer() -> Result<f64, CalculationError> {
-    let r1 = read_positive_f64("Enter initial orbital radius (km)")?;
-    let r2 = read_positive_f64("Enter final orbital radius (km)")?;
-    let central_mass = read_positive_f64("Enter central body mass (kg)")?;
+fn load_body_file(catalog: &mut BodyCatalog) {
+    print!("Enter body file path: ");
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+    
+    match catalog.load_file(input.trim()) {
+        Ok(count) => println!("Loaded {} central bodies", count),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+// Process functions for each calculation type. Each prompt names its unit
+// and the value is wrapped in the matching quantity right away.
+fn process_orbital_velocity(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let radius = Length::from_kilometers(read_positive_f64("Enter orbital radius (km)")?);
+    let body = read_body(catalog)?;
+    
+    calculate(SpaceCalculation::OrbitalVelocity { radius, body })
+}
+
+fn process_escape_velocity(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let radius = Length::from_kilometers(read_positive_f64("Enter distance from center of mass (km)")?);
+    let body = read_body(catalog)?;
+    
+    calculate(SpaceCalculation::EscapeVelocity { radius, body })
+}
+
+fn process_orbital_period(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let radius = Length::from_kilometers(read_positive_f64("Enter orbital radius (km)")?);
+    let body = read_body(catalog)?;
+    
+    calculate(SpaceCalculation::OrbitalPeriod { radius, body })
+}
+
+fn process_hohmann_transfer(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let r1 = Length::from_kilometers(read_positive_f64("Enter initial orbital radius (km)")?);
+    let r2 = Length::from_kilometers(read_positive_f64("Enter final orbital radius (km)")?);
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::Hohmann { r1, r2, central_mass })
+    calculate(SpaceCalculation::Hohmann { r1, r2, body })
 }
 
-fn process_rocket_delta_v() -> Result<f64, CalculationError> {
-    let exhaust_velocity = read_positive_f64("Enter exhaust velocity (m/s)")?;
-    let init_mass = read_positive_f64("Enter initial mass (kg)")?;
-    let final_mass = read_positive_f64("Enter final mass (kg)")?;
+fn process_rocket_delta_v() -> Result<CalculationResult, CalculationError> {
+    let exhaust_velocity = Velocity::from_meters_per_second(read_positive_f64("Enter exhaust velocity (m/s)")?);
+    let init_mass = Mass::from_kilograms(read_positive_f64("Enter initial mass (kg)")?);
+    let final_mass = Mass::from_kilograms(read_positive_f64("Enter final mass (kg)")?);
     
     if final_mass >= init_mass {
         return Err(CalculationError::InvalidInput);
     }
     calculate(SpaceCalculation::TsiolkovskyRocket { exhaust_velocity, init_mass, final_mass })
 }
-fn process_gravitational_force() -> Result<f64, CalculationError> {
-    let msat = read_positive_f64("Enter satellite mass (kg)")?;
-    let radius = read_positive_f64("Enter distance from center of mass (m)")?;
+fn process_gravitational_force(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let msat = Mass::from_kilograms(read_positive_f64("Enter satellite mass (kg)")?);
+    let radius = Length::from_kilometers(read_positive_f64("Enter distance from center of mass (km)")?);
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::GravitationalForce { msat, radius })
+    calculate(SpaceCalculation::GravitationalForce { msat, radius, body })
 }
-fn process_geostationary_orbit_radius() -> Result<f64, CalculationError> {
-    calculate(SpaceCalculation::GeostationaryOrbitRadius {})
+fn process_geostationary_orbit_radius(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let body = read_body(catalog)?;
+    
+    calculate(SpaceCalculation::GeostationaryOrbitRadius { body })
 }
-fn process_orbital_energy() -> Result<f64, CalculationError> {
-    let msat = read_positive_f64("Enter satellite mass (kg)")?;
-    let radius = read_positive_f64("Enter distance from center of mass (m)")?;
+fn process_orbital_energy(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let msat = Mass::from_kilograms(read_positive_f64("Enter satellite mass (kg)")?);
+    let radius = Length::from_kilometers(read_positive_f64("Enter orbital radius (km)")?);
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::OrbitalEnergy { msat, radius })
+    calculate(SpaceCalculation::OrbitalEnergy { msat, radius, body })
 }
-fn process_escape_energy() -> Result<f64, CalculationError> {
-    let msat = read_positive_f64("Enter satellite mass (kg)")?;
-    let radius = read_positive_f64("Enter distance from center of mass (m)")?;
+fn process_escape_energy(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let msat = Mass::from_kilograms(read_positive_f64("Enter satellite mass (kg)")?);
+    let radius = Length::from_kilometers(read_positive_f64("Enter distance from center of mass (km)")?);
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::EscapeEnergy { msat, radius })
+    calculate(SpaceCalculation::EscapeEnergy { msat, radius, body })
 }
-fn process_lin_budget_equation() -> Result<f64, CalculationError> {
-    let pt = read_positive_f64("Enter Pt (dBm)")?;
-    let gt = read_positive_f64("Enter Gt (dBi)")?;
-    let gr = read_positive_f64("Enter Gr (dBi)")?;
-    let lp = read_positive_f64("Enter Lp (dB)")?;
-    let ls = read_positive_f64("Enter Ls (dB)")?;
-    let lm = read_positive_f64("Enter Lm (dB)")?;
+fn process_lin_budget_equation() -> Result<CalculationResult, CalculationError> {
+    let pt = Power::from_dbm(read_f64("Enter Pt (dBm)")?);
+    // Entered gains are net of pointing loss; a modeled antenna enters with
+    // its pointing loss taken off
+    let gt = read_antenna_gain("Gt", false)?;
+    let gr = read_antenna_gain("Gr", false)?;
+    // Only modeled antennas depend on the frequency
+    let mut frequency = None;
+    if [&gt, &gr].iter().any(|gain| matches!(gain, AntennaGain::Modeled { .. })) {
+        let hertz = read_positive_f64("Enter frequency (GHz)")? * 1e9;
+        print_antenna_gain(&gt, hertz)?;
+        print_antenna_gain(&gr, hertz)?;
+        frequency = Some(Frequency::from_hertz(hertz));
+    }
+    let lp = Decibels::from_db(read_f64("Enter Lp (dB)")?);
+    let ls = Decibels::from_db(read_f64("Enter Ls (dB)")?);
+    let lm = Decibels::from_db(read_f64("Enter Lm (dB)")?);
     
-    calculate(SpaceCalculation::LinBudgetEquation { pt, gt, gr, lp, ls, lm })
+    calculate(SpaceCalculation::LinBudgetEquation { pt, gt, gr, frequency, lp, ls, lm })
 }
-fn process_path_loss() -> Result<f64, CalculationError> {
-    let radius = read_positive_f64("Enter distance (m)")?;
-    let frequency = read_positive_f64("Enter frequency (Hz)")?;
+fn process_path_loss() -> Result<CalculationResult, CalculationError> {
+    let radius = Length::from_kilometers(read_positive_f64("Enter distance (km)")?);
+    let frequency = Frequency::from_hertz(read_positive_f64("Enter frequency (Hz)")?);
     
     calculate(SpaceCalculation::PathLoss { radius, frequency })
 }
-fn process_incline_angle() -> Result<f64, CalculationError> {
-    let possatx = read_positive_f64("Enter satellite position x (m)")?;
-    let possaty = read_positive_f64("Enter satellite position y (m)")?;
-    let possatz = read_positive_f64("Enter satellite position z (m)")?;
-    let velsatx = read_positive_f64("Enter satellite velocity x (m/s)")?;
-    let velsaty = read_positive_f64("Enter satellite velocity y (m/s)")?;
-    let velsatz = read_positive_f64("Enter satellite velocity z (m/s)")?;
+fn process_incline_angle() -> Result<CalculationResult, CalculationError> {
+    let possatx = Length::from_meters(read_f64("Enter satellite position x (m)")?);
+    let possaty = Length::from_meters(read_f64("Enter satellite position y (m)")?);
+    let possatz = Length::from_meters(read_f64("Enter satellite position z (m)")?);
+    let velsatx = Velocity::from_meters_per_second(read_f64("Enter satellite velocity x (m/s)")?);
+    let velsaty = Velocity::from_meters_per_second(read_f64("Enter satellite velocity y (m/s)")?);
+    let velsatz = Velocity::from_meters_per_second(read_f64("Enter satellite velocity z (m/s)")?);
+    let text = read_text("Enter frame of the state (GCRF, MOD, TOD, TEME, PEF, ITRF) [GCRF]");
+    let frame = if text.is_empty() { Frame::Gcrf } else { parse_frame(&text)? };
+    // GCRF does not move, so its epoch does not matter
+    let epoch = if frame == Frame::Gcrf {
+        Epoch::from_julian_date(J2000_JD, TimeScale::Utc)
+    } else {
+        read_epoch("Enter state epoch")?
+    };
+    
+    calculate(SpaceCalculation::InclineAngle { possatx, possaty, possatz, velsatx, velsaty, velsatz, frame, epoch })
+}
+fn process_slant_range(catalog: &BodyCatalog) -> Result<CalculationResult, CalculationError> {
+    let altitude = Length::from_kilometers(read_positive_f64("Enter altitude above the ellipsoid (km)")?);
+    let elevation = Angle::from_degrees(read_f64("Enter elevation angle (degrees)")?);
+    let azimuth = Angle::from_degrees(read_f64_or("Enter azimuth (degrees)", 0.0)?);
+    let site = Geodetic {
+        latitude: read_f64_or("Enter station latitude (degrees)", 0.0)?.to_radians(),
+        longitude: read_f64_or("Enter station longitude (degrees, east positive)", 0.0)?.to_radians(),
+        altitude: read_f64_or("Enter station height above the ellipsoid (m)", 0.0)?,
+    };
+    let body = read_body(catalog)?;
+    
+    calculate(SpaceCalculation::SlantRange { altitude, elevation, azimuth, site, body })
+}
+// Report functions for tools with more than one output value
+fn report_state_to_elements(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let state = read_state_vector()?;
+    let body = read_body(catalog)?;
+    
+    let elements = OrbitalElements::from_state(&state, body.mu)?;
+    println!("{}", elements);
+    Ok(())
+}
+
+fn report_elements_to_state(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let p = read_positive_f64("Enter semi-latus rectum p (km)")?;
+    let e = read_f64("Enter eccentricity")?;
+    let i = read_f64("Enter inclination (degrees)")?;
+    let raan = read_f64("Enter RAAN (degrees)")?;
+    let argp = read_f64("Enter argument of periapsis (degrees)")?;
+    let nu = read_f64("Enter true anomaly (degrees)")?;
+    let body = read_body(catalog)?;
+    
+    let elements = OrbitalElements::from_semi_latus_rectum(
+        p * 1000.0,
+        e,
+        i.to_radians(),
+        raan.to_radians(),
+        argp.to_radians(),
+        nu.to_radians(),
+    )?;
+    let state = elements.to_state(body.mu)?;
+    println!("Position: {} km", state.position / 1000.0);
+    println!("Velocity: {} km/s", state.velocity / 1000.0);
+    Ok(())
+}
+
+fn report_kepler_propagation(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let state = read_state_vector()?;
+    let body = read_body(catalog)?;
+    let start = read_f64("Enter start time offset (s)")?;
+    let end = read_f64("Enter end time offset (s)")?;
+    let step = read_positive_f64("Enter output step (s)")?;
+    let epoch = read_optional_epoch()?;
+    
+    let points = propagate_series(&state, body.mu, start, end, step)?;
+    print_ephemeris(&points, epoch.as_ref());
+    Ok(())
+}
+
+// Print an ephemeris and offer to export it as CSV, time-stamped in UTC
+// when the epoch of the time offsets is known
+fn print_ephemeris(points: &[EphemerisPoint], epoch: Option<&Epoch>) {
+    for point in points {
+        let stamp = epoch.map(|e| format!("{}  ", *e + point.time)).unwrap_or_default();
+        println!(
+            "{}t = {:>12.1} s  r = {} km  v = {} km/s",
+            stamp,
+            point.time,
+            point.state.position / 1000.0,
+            point.state.velocity / 1000.0
+        );
+    }
+    
+    offer_export("Export CSV to file", points.len(), "states", |path| match epoch {
+        Some(epoch) => write_timestamped_ephemeris_csv(points, epoch, path),
+        None => write_ephemeris_csv(points, path),
+    });
+}
+
+fn report_j2_rates(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let a = read_positive_f64("Enter semi-major axis (km)")?;
+    let e = read_f64("Enter eccentricity")?;
+    let i = read_f64("Enter inclination (degrees)")?;
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::InclineAngle { possatx, possaty, possatz, velsatx, velsaty, velsatz })
+    let elements = OrbitalElements::new(a * 1000.0, e, i.to_radians(), 0.0, 0.0, 0.0)?;
+    println!("{}", secular_rates(&elements, &body)?);
+    Ok(())
 }
-fn process_slant_range() -> Result<f64, CalculationError> {
+
+fn report_sun_synchronous(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let altitude = read_positive_f64("Enter altitude (km)")?;
+    let e = read_f64("Enter eccentricity")?;
+    let body = read_body(catalog)?;
+    let year = read_text("Enter the body's year around the Sun (days) [365.2422]");
+    let nodal_rate = if year.is_empty() {
+        EARTH_SUN_SYNCHRONOUS_RATE
+    } else {
+        let days: f64 = year.parse().map_err(|_| CalculationError::InvalidInput)?;
+        if days <= 0.0 {
+            return Err(CalculationError::NegativeValue);
+        }
+        2.0 * std::f64::consts::PI / (days * 86400.0)
+    };
+    
+    let inclination = sun_synchronous_inclination(&body, altitude * 1000.0, e, nodal_rate)?;
+    println!("Sun-synchronous inclination: {:.4} deg", inclination.to_degrees());
+    Ok(())
+}
+
+fn report_frozen_orbit(catalog: &BodyCatalog) -> Result<(), CalculationError> {
     let altitude = read_positive_f64("Enter altitude (km)")?;
-    let elevation = read_positive_f64("Enter elevation angle (degrees)")?;
+    let i = read_f64("Enter inclination (degrees)")?;
+    let body = read_body(catalog)?;
+    
+    let frozen = frozen_orbit(&body, body.equatorial_radius + altitude * 1000.0, i.to_radians())?;
+    println!("{}", frozen);
+    Ok(())
+}
+
+fn report_j2_propagation(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let state = read_state_vector()?;
+    let body = read_body(catalog)?;
+    let end = read_f64("Enter propagation time (s, negative for backwards)")?;
+    let step = read_positive_f64("Enter output step (s)")?;
+    let epoch = read_optional_epoch()?;
+    
+    let points = propagate_j2_series(&state, &body, end, step, 10.0)?;
+    print_ephemeris(&points, epoch.as_ref());
+    Ok(())
+}
+
+fn report_lambert(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let r1 = Vector3::new(
+        read_f64("Enter departure position x (km)")?,
+        read_f64("Enter departure position y (km)")?,
+        read_f64("Enter departure position z (km)")?,
+    );
+    let r2 = Vector3::new(
+        read_f64("Enter arrival position x (km)")?,
+        read_f64("Enter arrival position y (km)")?,
+        read_f64("Enter arrival position z (km)")?,
+    );
+    let tof = read_positive_f64("Enter time of flight (s)")?;
+    let max_revolutions = read_count("Enter maximum number of revolutions")?;
+    let prograde = !read_text("Retrograde transfer? (y/N)").eq_ignore_ascii_case("y");
+    let body = read_body(catalog)?;
+    
+    let solutions = solve_lambert_all(r1 * 1000.0, r2 * 1000.0, tof, body.mu, max_revolutions, prograde)?;
+    for solution in solutions {
+        println!(
+            "{} rev: v1 = {} km/s, v2 = {} km/s",
+            solution.revolutions,
+            solution.departure_velocity / 1000.0,
+            solution.arrival_velocity / 1000.0
+        );
+    }
+    Ok(())
+}
+
+fn read_planet(prompt: &str) -> Result<Planet, CalculationError> {
+    Planet::from_name(&read_text(prompt)).ok_or(CalculationError::InvalidInput)
+}
+
+fn report_porkchop() -> Result<(), CalculationError> {
+    let from = read_planet("Enter departure planet")?;
+    let to = read_planet("Enter arrival planet")?;
+    let date = |prompt: &str| -> Result<f64, CalculationError> { Ok(read_epoch(prompt)?.julian_date(TimeScale::Utc)) };
+    let departures = DateRange::new(
+        date("Enter first departure date")?,
+        date("Enter last departure date")?,
+        read_positive_f64("Enter departure step (days)")?,
+    )?;
+    let arrivals = DateRange::new(
+        date("Enter first arrival date")?,
+        date("Enter last arrival date")?,
+        read_positive_f64("Enter arrival step (days)")?,
+    )?;
+    
+    let grid = porkchop_grid(from, to, departures, arrivals)?;
+    match minimum_c3(&grid) {
+        Some(best) => println!(
+            "Minimum C3: {:.3} km^2/s^2 departing {} arriving {} ({:.0} days, arrival v-infinity {:.3} km/s)",
+            best.c3,
+            Epoch::from_julian_date(best.departure, TimeScale::Utc).to_date(TimeScale::Utc),
+            Epoch::from_julian_date(best.arrival, TimeScale::Utc).to_date(TimeScale::Utc),
+            best.time_of_flight_days(),
+            best.arrival_v_inf
+        ),
+        None => println!("No transfer found in the given window"),
+    }
+    
+    offer_export("Export CSV to file", grid.len(), "grid points", |path| write_porkchop_csv(&grid, path));
+    Ok(())
+}
+
+fn report_bi_elliptic(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let r1 = read_positive_f64("Enter initial orbital radius (km)")?;
+    let r2 = read_positive_f64("Enter final orbital radius (km)")?;
+    let rb = read_positive_f64("Enter intermediate apoapsis radius (km)")?;
+    let body = read_body(catalog)?;
+    
+    println!("{}", bi_elliptic(&body, r1 * 1000.0, r2 * 1000.0, rb * 1000.0)?);
+    Ok(())
+}
+
+fn report_inclination_change(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let radius = read_positive_f64("Enter orbital radius (km)")?;
+    let delta_i = read_positive_f64("Enter inclination change (degrees)")?;
+    let body = read_body(catalog)?;
+    
+    println!("{}", inclination_change(&body, radius * 1000.0, delta_i.to_radians())?);
+    Ok(())
+}
+
+fn report_combined_plane_change(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let r1 = read_positive_f64("Enter initial orbital radius (km)")?;
+    let r2 = read_positive_f64("Enter final orbital radius (km)")?;
+    let delta_i = read_positive_f64("Enter inclination change (degrees)")?;
+    let body = read_body(catalog)?;
     
-    calculate(SpaceCalculation::SlantRange { altitude, elevation })
-}
\ No newline at end of file
+    let (plan, split) = combined_plane_change(&body, r1 * 1000.0, r2 * 1000.0, delta_i.to_radians())?;
+    println!("{}", plan);
+    println!("Optimal split: {:.1}% of the plane change at departure", split * 100.0);
+    Ok(())
+}
+
+fn report_phasing(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let radius = read_positive_f64("Enter orbital radius (km)")?;
+    let phase = read_f64("Enter target phase angle ahead of the chaser (degrees)")?;
+    let revolutions = read_positive_count("Enter number of phasing revolutions")?;
+    let body = read_body(catalog)?;
+    
+    println!("{}", phasing(&body, radius * 1000.0, phase.to_radians(), revolutions)?);
+    Ok(())
+}
+
+fn report_vehicle_performance() -> Result<(), CalculationError> {
+    let count = read_positive_count("Enter number of stages")? as usize;
+    let mut stages = Vec::with_capacity(count);
+    for i in 1..=count {
+        println!("Stage {} (stage 1 fires first)", i);
+        let dry_mass = read_positive_f64("  Enter dry mass (kg)")?;
+        let propellant_mass = read_positive_f64("  Enter propellant mass (kg)")?;
+        let isp = read_positive_f64("  Enter specific impulse (s)")?;
+        stages.push(Stage::new(dry_mass, propellant_mass, isp)?);
+    }
+    let payload = read_f64("Enter payload mass (kg)")?;
+    
+    let vehicle = Vehicle::new(stages, payload)?;
+    println!("{}", vehicle.performance());
+    Ok(())
+}
+
+fn report_optimal_staging() -> Result<(), CalculationError> {
+    let count = read_positive_count("Enter number of stages")? as usize;
+    let mut designs = Vec::with_capacity(count);
+    for i in 1..=count {
+        println!("Stage {} (stage 1 fires first)", i);
+        let structural_ratio = read_positive_f64("  Enter structural ratio dry/(dry+propellant)")?;
+        let isp = read_positive_f64("  Enter specific impulse (s)")?;
+        designs.push(StageDesign { structural_ratio, isp });
+    }
+    let payload = read_positive_f64("Enter payload mass (kg)")?;
+    let target = read_positive_f64("Enter target delta-v (m/s)")?;
+    
+    let vehicle = optimal_staging(&designs, payload, target)?;
+    for (i, stage) in vehicle.stages.iter().enumerate() {
+        println!(
+            "Stage {}: dry mass {:.1} kg, propellant mass {:.1} kg",
+            i + 1,
+            stage.dry_mass,
+            stage.propellant_mass
+        );
+    }
+    println!("{}", vehicle.performance());
+    Ok(())
+}
+
+fn report_link_budget() -> Result<(), CalculationError> {
+    let transmit_power_dbw = read_f64("Enter transmit power (dBW)")?;
+    let range = read_positive_f64("Enter range (km)")?;
+    let frequency = read_positive_f64("Enter frequency (GHz)")? * 1e9;
+    // Each end brings its own pointing loss, entered or modeled
+    let transmit_antenna = read_antenna_gain("transmit", true)?;
+    print_antenna_gain(&transmit_antenna, frequency)?;
+    let transmit_line_loss_db = read_f64("Enter transmit line loss (dB)")?;
+    let atmospheric_loss_db = read_f64("Enter atmospheric loss (dB)")?;
+    let polarization_loss_db = read_f64("Enter polarization loss (dB)")?;
+    let receive_antenna = read_antenna_gain("receive", true)?;
+    print_antenna_gain(&receive_antenna, frequency)?;
+    let receive_line_loss_db = read_f64("Enter receive line loss (dB)")?;
+    let system_noise_temperature = read_positive_f64("Enter system noise temperature (K)")?;
+    let data_rate = read_positive_f64("Enter data rate (kbit/s)")?;
+    let required_eb_n0_db = read_f64("Enter required Eb/N0 (dB)")?;
+    
+    let budget = link_budget(&LinkBudgetInputs {
+        transmit_power_dbw,
+        transmit_antenna,
+        transmit_line_loss_db,
+        range: range * 1000.0,
+        frequency,
+        atmospheric_loss_db,
+        polarization_loss_db,
+        receive_antenna,
+        receive_line_loss_db,
+        system_noise_temperature,
+        data_rate: data_rate * 1000.0,
+        required_eb_n0_db,
+    })?;
+    println!("{}", budget);
+    Ok(())
+}
+
+// Helper function to read an antenna model
+fn read_antenna() -> Result<Antenna, CalculationError> {
+    match read_text("Antenna: 1. Parabolic dish  2. Helix  3. Patch array").as_str() {
+        "1" => Ok(Antenna::ParabolicDish {
+            diameter: read_positive_f64("Enter dish diameter (m)")?,
+            efficiency: read_positive_f64("Enter aperture efficiency (0-1)")?,
+        }),
+        "2" => Ok(Antenna::Helix {
+            diameter: read_positive_f64("Enter helix diameter (mm)")? / 1000.0,
+            turns: read_positive_f64("Enter number of turns")?,
+            spacing: read_positive_f64("Enter spacing between turns (mm)")? / 1000.0,
+        }),
+        "3" => Ok(Antenna::PatchArray {
+            rows: element_count(read_positive_f64("Enter number of element rows")?)?,
+            columns: element_count(read_positive_f64("Enter number of element columns")?)?,
+            spacing: read_positive_f64("Enter element spacing (mm)")? / 1000.0,
+            efficiency: read_positive_f64("Enter aperture efficiency (0-1)")?,
+        }),
+        _ => Err(CalculationError::InvalidInput),
+    }
+}
+
+// Helper function to read an antenna gain in dBi, or model the antenna when left empty. With
+// `pointing_loss` set an entered gain also asks for its pointing loss; a model works it out
+// from the pointing error.
+fn read_antenna_gain(role: &str, pointing_loss: bool) -> Result<AntennaGain, CalculationError> {
+    let text = read_text(&format!("Enter {} antenna gain (dBi, leave empty to model the antenna)", role));
+    if !text.is_empty() {
+        let gain_dbi = text.parse().map_err(|_| CalculationError::InvalidInput)?;
+        let pointing_loss_db =
+            if pointing_loss { read_f64(&format!("Enter {} antenna pointing loss (dB)", role))? } else { 0.0 };
+        return Ok(AntennaGain::Given { gain_dbi, pointing_loss_db });
+    }
+    let antenna = read_antenna()?;
+    let pointing_error = read_f64(&format!("Enter {} antenna pointing error (degrees)", role))?.to_radians();
+    Ok(AntennaGain::Modeled { antenna, pointing_error })
+}
+
+// Helper function to show the gain and pointing loss a modeled antenna works out to
+fn print_antenna_gain(gain: &AntennaGain, frequency: f64) -> Result<(), CalculationError> {
+    if let AntennaGain::Modeled { antenna, pointing_error } = gain {
+        println!("{}\n{}", antenna, antenna.performance(frequency, *pointing_error)?);
+    }
+    Ok(())
+}
+
+fn report_atmospheric_loss() -> Result<(), CalculationError> {
+    let frequency = read_positive_f64("Enter frequency (GHz)")? * 1e9;
+    let elevation = read_positive_f64("Enter elevation angle (degrees)")?.to_radians();
+    let range = read_positive_f64("Enter slant range (km)")? * 1000.0;
+    let availability = read_positive_f64("Enter link availability (%)")?;
+    let rain_rate = read_f64("Enter rain rate exceeded 0.01% of the year (mm/h)")?;
+    let rain_height = read_f64("Enter rain height above sea level (km)")? * 1000.0;
+    let station_height = read_f64("Enter station height above sea level (km)")? * 1000.0;
+    let station_latitude = read_f64("Enter station latitude (degrees)")?.to_radians();
+    let polarization_tilt = read_f64("Enter polarization tilt (degrees, 45 for circular)")?.to_radians();
+    let antenna_diameter = read_positive_f64("Enter antenna diameter (m)")?;
+    let antenna_efficiency = read_positive_f64("Enter antenna efficiency (0-1)")?;
+    let wet_refractivity = read_f64("Enter wet refractivity (N-units)")?;
+    
+    let rain = RainParameters { rain_rate, rain_height, station_height, station_latitude, polarization_tilt };
+    let scintillation = ScintillationParameters { antenna_diameter, antenna_efficiency, wet_refractivity };
+    let losses = atmospheric_losses(frequency, elevation, availability, &Atmosphere::standard(), &rain, &scintillation)?;
+    let free_space = free_space_path_loss(range, frequency)?;
+    
+    println!("Free-space path loss:  {:.2} dB", free_space);
+    println!("Gaseous absorption:    {:.2} dB", losses.gaseous);
+    println!("Rain attenuation:      {:.2} dB", losses.rain);
+    println!("Scintillation fade:    {:.2} dB", losses.scintillation);
+    println!("Total atmospheric:     {:.2} dB", losses.total());
+    println!("Total path loss:       {:.2} dB", free_space + losses.total());
+    Ok(())
+}
+
+fn report_passes() -> Result<(), CalculationError> {
+    // Stations sit on the Earth, so the orbit is an Earth orbit
+    let station = read_ground_station()?;
+    let state = read_state_vector()?;
+    let body = CentralBody::earth();
+    let epoch = read_epoch("Enter state epoch")?.julian_date(TimeScale::Utc);
+    let days = read_positive_f64("Enter prediction window (days)")?;
+    let step = read_positive_f64("Enter sample step (s)")?;
+    let use_j2 = read_text("Include J2 perturbations? (y/N)").eq_ignore_ascii_case("y");
+    
+    let Some(eop) = read_eop_table() else {
+        return Ok(());
+    };
+    
+    let j2 = J2Propagator::new(state, body.clone());
+    let passes = predict_passes(&station, &body, &eop, 0.0, days * 86400.0, step, |t| {
+        let state = if use_j2 { j2.state_at(t)? } else { propagate_state(&state, body.mu, t)? };
+        Ok(FramedState::new(Frame::Gcrf, epoch + t / 86400.0, state))
+    })?;
+    print_passes(&station, epoch, &passes);
+    Ok(())
+}
+
+// Helper function to read a ground station entered in degrees and m
+fn read_ground_station() -> Result<GroundStation, CalculationError> {
+    let name = read_text("Enter ground station name");
+    let latitude = read_f64("Enter station latitude (degrees)")?;
+    let longitude = read_f64("Enter station longitude (degrees, east positive)")?;
+    let altitude = read_f64("Enter station altitude (m)")?;
+    let mask = read_f64("Enter minimum elevation (degrees)")?;
+    GroundStation::new(
+        &name,
+        latitude.to_radians(),
+        longitude.to_radians(),
+        altitude,
+        mask.to_radians(),
+    )
+}
+
+// Print a pass table and offer to export the profiles as CSV
+fn print_passes(station: &GroundStation, epoch: f64, passes: &[Pass]) {
+    println!("Passes over {}", station);
+    println!(
+        "{:<5} {:<20} {:<20} {:>10} {:>9} {:>8} {:>8}",
+        "Pass", "AOS (UTC)", "LOS (UTC)", "Duration s", "Max el", "AOS az", "LOS az"
+    );
+    for (i, pass) in passes.iter().enumerate() {
+        let first = &pass.profile[0];
+        let last = &pass.profile[pass.profile.len() - 1];
+        println!(
+            "{:<5} {:<20} {:<20} {:>10.1} {:>9.2} {:>8.1} {:>8.1}",
+            i + 1,
+            format_date_time(epoch + pass.aos / 86400.0),
+            format_date_time(epoch + pass.los / 86400.0),
+            pass.duration(),
+            pass.max_elevation.to_degrees(),
+            first.azimuth.to_degrees(),
+            last.azimuth.to_degrees()
+        );
+    }
+    if passes.is_empty() {
+        println!("No passes in the given window");
+        return;
+    }
+    
+    offer_export("Export az/el/range profiles as CSV to file", passes.len(), "passes", |path| {
+        write_passes_csv(passes, path)
+    });
+}
+
+// Helper function to load a TLE file and pick one element set, None when
+// the file cannot be read or holds none
+fn read_tle() -> Result<Option<Tle>, CalculationError> {
+    let path = read_text("Enter TLE file path");
+    let mut tles = match load_tle_file(&path) {
+        Ok(tles) => tles,
+        Err(e) => {
+            println!("Error: {}", e);
+            return Ok(None);
+        }
+    };
+    let index = match tles.len() {
+        0 => {
+            println!("No element sets in {}", path);
+            return Ok(None);
+        }
+        1 => 1,
+        count => {
+            for (i, tle) in tles.iter().enumerate() {
+                println!("{}. {} ({:05})", i + 1, tle.name.as_deref().unwrap_or("unnamed"), tle.catalog_number);
+            }
+            let index = read_positive_count("Select element set")? as usize;
+            if !(1..=count).contains(&index) {
+                return Err(CalculationError::OutOfRange);
+            }
+            index
+        }
+    };
+    let tle = tles.swap_remove(index - 1);
+    println!("{}", tle);
+    Ok(Some(tle))
+}
+    
+fn report_tle_propagation() -> Result<(), CalculationError> {
+    let tle = match read_tle()? {
+        Some(tle) => tle,
+        None => return Ok(()),
+    };
+
+    let satellite = Sgp4::from_tle(&tle)?;
+    println!("Model: {}", if satellite.is_deep_space() { "SDP4 (deep space)" } else { "SGP4 (near Earth)" });
+    println!("Epoch: {} UTC, states in TEME", format_date_time(tle.epoch));
+    let start = read_f64("Enter start time from epoch (s)")?;
+    let end = read_f64("Enter end time from epoch (s)")?;
+    let step = read_positive_f64("Enter output step (s)")?;
+    let epoch = Epoch::from_julian_date(tle.epoch, TimeScale::Utc);
+    print_ephemeris(&satellite.propagate_series(start, end, step)?, Some(&epoch));
+    
+    if read_text("Predict passes over a ground station? (y/N)").eq_ignore_ascii_case("y") {
+        let station = read_ground_station()?;
+        let sample_step = read_positive_f64("Enter sample step (s)")?;
+        let Some(eop) = read_eop_table() else {
+            return Ok(());
+        };
+        let body = CentralBody::earth();
+        let passes = predict_passes(&station, &body, &eop, start.min(end), start.max(end), sample_step, |t| {
+            satellite.propagate_framed(t)
+        })?;
+        print_passes(&station, tle.epoch, &passes);
+    }
+    Ok(())
+}
+
+fn report_batch(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let input = read_text("Enter batch file path (.json, .jsonl or .csv)");
+    let rows = match load_batch_file(&input) {
+        Ok(rows) => rows,
+        Err(e) => {
+            println!("Error: {}", e);
+            return Ok(());
+        }
+    };
+    let results = run_batch(&rows, catalog);
+    for result in &results {
+        let id = result.id.as_deref().unwrap_or("-");
+        match &result.outcome {
+            Ok(value) => println!("{:>4} {:<16} {}", result.row, id, value),
+            Err(e) => println!("{:>4} {:<16} Error: {}", result.row, id, e),
+        }
+    }
+    println!("{}", batch_summary(&results));
+    
+    offer_export("Export results to file, .jsonl or .csv", results.len(), "results", |path| {
+        write_results(&results, path)
+    });
+    Ok(())
+}
+
+fn report_orbital_lifetime() -> Result<(), CalculationError> {
+    let perigee = read_positive_f64("Enter perigee altitude (km)")?;
+    let apogee = read_positive_f64("Enter apogee altitude (km)")?;
+    let mass = read_positive_f64("Enter spacecraft mass (kg)")?;
+    let drag_coefficient = read_positive_f64("Enter drag coefficient Cd [2.2 typical]")?;
+    let area = read_positive_f64("Enter cross-sectional area (m^2)")?;
+    let b = ballistic_coefficient(mass, drag_coefficient, area)?;
+    println!("Ballistic coefficient: {:.2} kg/m^2", b);
+    
+    println!("Density model: 1. Exponential  2. Harris-Priester  3. Solar flux (F10.7, Ap)");
+    let model = match read_text("Select model [3]").as_str() {
+        "1" => DensityModel::Exponential,
+        "2" => DensityModel::HarrisPriester,
+        "" | "3" => DensityModel::SolarFlux {
+            solar_flux: read_positive_f64("Enter F10.7 solar flux (sfu) [70 low, 150 moderate, 250 high]")?,
+            ap: read_f64("Enter Ap geomagnetic index [15 typical]")?,
+        },
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    let years = read_positive_f64("Enter maximum propagation time (years) [30 covers the 25-year rule]")?;
+    let step = read_positive_f64("Enter output step (days)")?;
+    
+    let body = CentralBody::earth();
+    let estimate =
+        estimate_lifetime(perigee * 1000.0, apogee * 1000.0, &body, &model, b, years * SECONDS_PER_YEAR, step * 86400.0)?;
+    println!("Model: {}", model);
+    for point in &estimate.history {
+        println!(
+            "t = {:>8.3} years  perigee = {:>7.1} km  apogee = {:>7.1} km",
+            point.time / SECONDS_PER_YEAR,
+            point.perigee_altitude / 1000.0,
+            point.apogee_altitude / 1000.0
+        );
+    }
+    println!("{}", estimate);
+    
+    offer_export("Export decay history to CSV file", estimate.history.len(), "points", |path| {
+        write_decay_csv(&estimate.history, path)
+    });
+    Ok(())
+}
+
+fn report_ground_track() -> Result<(), CalculationError> {
+    // The track is drawn on the rotating Earth, so the orbit is an Earth orbit
+    let state = read_state_vector()?;
+    let body = CentralBody::earth();
+    let epoch = read_epoch("Enter state epoch")?.julian_date(TimeScale::Utc);
+    let period = OrbitalElements::from_state(&state, body.mu)?.period(body.mu).ok_or(CalculationError::OutOfRange)?;
+    let revolutions = read_positive_f64("Enter number of revolutions")?;
+    let step = read_positive_f64("Enter sample step (s)")?;
+    let use_j2 = read_text("Include J2 perturbations? (y/N)").eq_ignore_ascii_case("y");
+    let Some(eop) = read_eop_table() else {
+        return Ok(());
+    };
+    
+    let j2 = J2Propagator::new(state, body.clone());
+    let track = ground_track(&body, &eop, 0.0, revolutions * period, step, |t| {
+        let state = if use_j2 { j2.state_at(t)? } else { propagate_state(&state, body.mu, t)? };
+        Ok(FramedState::new(Frame::Gcrf, epoch + t / 86400.0, state))
+    })?;
+    for point in &track {
+        println!(
+            "{}  lat = {:>8.3} deg  lon = {:>8.3} deg  alt = {:>9.1} km",
+            format_date_time(epoch + point.time / 86400.0),
+            point.latitude.to_degrees(),
+            point.longitude.to_degrees(),
+            point.altitude / 1000.0
+        );
+    }
+    
+    // The swath and name are only asked for once there is a file to write
+    offer_export("Export to file, .geojson or .kml", track.len(), "track points", |path| {
+        let width = read_f64("Enter sensor swath width (km, 0 for the track only)").map_err(io::Error::other)?;
+        let swath = if width > 0.0 {
+            swath_polygons(&swath_edges(&track, &body, width * 1000.0).map_err(io::Error::other)?)
+        } else {
+            Vec::new()
+        };
+        let mut name = read_text("Enter satellite name [Satellite]");
+        if name.is_empty() {
+            name = String::from("Satellite");
+        }
+        write_ground_track(&name, &track, &swath, path)
+    });
+    Ok(())
+}
+
+fn report_eclipses() -> Result<(), CalculationError> {
+    let state = read_state_vector()?;
+    let epoch = read_epoch("Enter state epoch")?.julian_date(TimeScale::Utc);
+    let days = read_positive_f64("Enter analysis span (days)")?;
+    let step = read_positive_f64("Enter sample step (s)")?;
+    let use_j2 = read_text("Include J2 perturbations? (y/N)").eq_ignore_ascii_case("y");
+    let model = match read_text("Shadow model: 1. Cylindrical  2. Conical (umbra/penumbra) [2]").as_str() {
+        "1" => ShadowModel::Cylindrical,
+        "" | "2" => ShadowModel::Conical,
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    
+    let body = CentralBody::earth();
+    let period = OrbitalElements::from_state(&state, body.mu)?.period(body.mu).ok_or(CalculationError::OutOfRange)?;
+    let end = days * 86400.0;
+    let j2 = J2Propagator::new(state, body.clone());
+    let propagate = |t: f64| if use_j2 { j2.state_at(t) } else { propagate_state(&state, body.mu, t) };
+    let eclipses = find_eclipses(body.equatorial_radius, epoch, 0.0, end, step, model, propagate)?;
+    
+    println!("Eclipses ({} shadow model)", model);
+    println!("{:<5} {:<20} {:<20} {:>10} {:>10}", "No.", "Entry (UTC)", "Exit (UTC)", "Shadow min", "Umbra min");
+    for (i, eclipse) in eclipses.iter().enumerate() {
+        println!(
+            "{:<5} {:<20} {:<20} {:>10.2} {:>10.2}",
+            i + 1,
+            format_date_time(epoch + eclipse.entry / 86400.0),
+            format_date_time(epoch + eclipse.exit / 86400.0),
+            eclipse.duration() / 60.0,
+            eclipse.umbra_duration() / 60.0
+        );
+    }
+    if eclipses.is_empty() {
+        println!("No eclipses in the given span");
+    }
+    
+    println!("Sunlit fraction per orbit (period {:.1} min)", period / 60.0);
+    for (i, orbit) in orbit_illumination(&eclipses, 0.0, end, period).iter().enumerate() {
+        println!(
+            "Orbit {:>4}  {}  shadow {:>6.2} min  sunlit {:>6.2} %",
+            i + 1,
+            format_date_time(epoch + orbit.start / 86400.0),
+            orbit.shadow_time / 60.0,
+            orbit.sunlit_fraction() * 100.0
+        );
+    }
+    
+    println!("Beta angle history");
+    let mut day = 0.0;
+    while day <= days {
+        let t = day * 86400.0;
+        let beta = beta_angle(&propagate(t)?, sun_position(epoch + day))?;
+        println!("{}  beta = {:>7.2} deg", format_date_time(epoch + day), beta.to_degrees());
+        day += 1.0;
+    }
+    
+    offer_export("Export eclipses as CSV to file", eclipses.len(), "eclipses", |path| {
+        write_eclipses_csv(&eclipses, path)
+    });
+    Ok(())
+}
+
+// Helper function to read an optional value, falling back to `default` when empty
+fn read_f64_or(prompt: &str, default: f64) -> Result<f64, CalculationError> {
+    let text = read_text(&format!("{} [{}]", prompt, default));
+    if text.is_empty() {
+        return Ok(default);
+    }
+    text.parse().map_err(|_| CalculationError::InvalidInput)
+}
+
+fn report_power_budget() -> Result<(), CalculationError> {
+    let count = read_positive_count("Enter number of load modes")? as usize;
+    let mut modes = Vec::with_capacity(count);
+    for i in 1..=count {
+        modes.push(LoadMode {
+            name: read_text(&format!("Mode {} name", i)),
+            sunlit_power: read_f64(&format!("Mode {} power in sunlight (W)", i))?,
+            eclipse_power: read_f64(&format!("Mode {} power in eclipse (W)", i))?,
+            duty_cycle: read_f64(&format!("Mode {} duty cycle (%)", i))? / 100.0,
+        });
+    }
+    let orbit_period = read_positive_f64("Enter orbit period (min)")? * 60.0;
+    let eclipse_fraction = read_f64("Enter eclipse fraction of the orbit (%)")? / 100.0;
+    let regulation = match read_text("Regulation: 1. Direct energy transfer  2. Peak power tracking [1]").as_str() {
+        "" | "1" => Regulation::DirectEnergyTransfer,
+        "2" => Regulation::PeakPowerTracking,
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    let cell_efficiency = read_positive_f64("Enter solar cell efficiency (%)")? / 100.0;
+    let inherent_degradation = read_f64_or("Enter inherent degradation factor", TYPICAL_INHERENT_DEGRADATION)?;
+    let incidence_angle = read_f64("Enter worst-case Sun incidence angle (degrees)")?.to_radians();
+    let degradation_per_year = read_f64("Enter array degradation per year (%)")? / 100.0;
+    let mission_years = read_f64("Enter mission life (years)")?;
+    let depth_of_discharge = read_positive_f64("Enter battery depth of discharge limit (%)")? / 100.0;
+    let battery_efficiency = read_f64_or("Enter battery to load efficiency", TYPICAL_BATTERY_EFFICIENCY)?;
+    
+    let inputs = PowerBudgetInputs {
+        modes,
+        orbit_period,
+        eclipse_fraction,
+        regulation,
+        cell_efficiency,
+        inherent_degradation,
+        incidence_angle,
+        degradation_per_year,
+        mission_years,
+        depth_of_discharge,
+        battery_efficiency,
+    };
+    let budget = size_power_system(&inputs)?;
+    println!("Power budget ({}, sized for end of life)", regulation);
+    println!("{}", budget);
+    
+    let orbits = read_positive_count("Simulate battery state of charge over how many orbits?")? as usize;
+    let years = read_f64("Years into the mission for the simulation (0 = BOL)")?;
+    let points = simulate_battery(&inputs, &budget, years, orbits, 60.0)?;
+    // Minimum state of charge reached in each orbit
+    for orbit in 0..orbits {
+        let (from, to) = (orbit as f64 * orbit_period, (orbit + 1) as f64 * orbit_period);
+        let lowest = points
+            .iter()
+            .filter(|p| p.time >= from && p.time <= to)
+            .map(|p| p.state_of_charge)
+            .fold(f64::INFINITY, f64::min);
+        println!("Orbit {:>4}  minimum state of charge {:>6.1} %", orbit + 1, lowest * 100.0);
+    }
+    let lowest = points.iter().map(|p| p.state_of_charge).fold(f64::INFINITY, f64::min);
+    if budget.battery_capacity <= 0.0 {
+        if lowest < 1.0 {
+            println!("No battery is sized, and the array falls short of the sunlit load");
+        } else {
+            println!("No battery needed: there is no eclipse load to carry");
+        }
+    } else if lowest < 1.0 - depth_of_discharge - 1e-9 {
+        println!("Battery exceeds the {:.0}% depth of discharge limit", depth_of_discharge * 100.0);
+    } else {
+        println!("Battery stays within the {:.0}% depth of discharge limit", depth_of_discharge * 100.0);
+    }
+    
+    offer_export("Export state of charge as CSV to file", points.len(), "points", |path| {
+        write_battery_csv(&points, path)
+    });
+    Ok(())
+}
+
+// Helper function to read a surface's optical properties and areas
+fn read_surface(prefix: &str) -> Result<Surface, CalculationError> {
+    Ok(Surface {
+        absorptivity: read_f64(&format!("{}solar absorptivity", prefix))?,
+        emissivity: read_f64(&format!("{}IR emissivity", prefix))?,
+        sun_area: read_f64(&format!("{}area facing the Sun (m^2)", prefix))?,
+        earth_area: read_f64(&format!("{}area facing Earth (m^2)", prefix))?,
+        radiating_area: read_f64(&format!("{}radiating area (m^2)", prefix))?,
+    })
+}
+
+// Helper function to pick a node by its 1-based number
+fn read_node_index(prompt: &str, count: usize) -> Result<usize, CalculationError> {
+    let index = read_positive_count(prompt)? as usize;
+    if !(1..=count).contains(&index) {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(index - 1)
+}
+
+fn report_thermal() -> Result<(), CalculationError> {
+    let altitude = read_f64("Enter orbit altitude (km)")? * 1000.0;
+    let mut environment = ThermalEnvironment::earth_orbit(altitude, CentralBody::earth().equatorial_radius);
+    environment.solar_flux = read_f64_or("Enter solar flux (W/m^2)", environment.solar_flux)?;
+    environment.albedo = read_f64_or("Enter Earth albedo", EARTH_ALBEDO)?;
+    environment.earth_ir = read_f64_or("Enter Earth IR flux (W/m^2)", EARTH_IR)?;
+    println!("Earth view factor: {:.4}", environment.view_factor);
+    
+    let surface = read_surface("Enter ")?;
+    let dissipation = read_f64("Enter internal dissipation (W)")?;
+    let hot = equilibrium_temperature(&surface, &environment, dissipation, true)?;
+    let cold = equilibrium_temperature(&surface, &environment, dissipation, false)?;
+    println!("Sunlit equilibrium:  {} ({})", hot, hot.to_celsius());
+    println!("Eclipse equilibrium: {} ({})", cold, cold.to_celsius());
+    
+    if !read_text("Run a multi-node transient analysis? (y/N)").eq_ignore_ascii_case("y") {
+        return Ok(());
+    }
+    let count = read_positive_count("Enter number of nodes")? as usize;
+    let mut nodes = Vec::with_capacity(count);
+    for i in 1..=count {
+        let name = read_text(&format!("Node {} name", i));
+        let capacitance = read_positive_f64(&format!("Node {} heat capacity (J/K)", i))?;
+        let surface = read_surface(&format!("Node {} ", i))?;
+        let dissipation = read_f64(&format!("Node {} internal dissipation (W)", i))?;
+        let initial = Celsius(read_f64(&format!("Node {} initial temperature (C)", i))?).to_kelvin();
+        nodes.push(ThermalNode { name, capacitance, surface, dissipation, initial_temperature: initial });
+    }
+    let mut conductors = Vec::new();
+    for i in 1..=read_count("Enter number of conductive links")? {
+        conductors.push(Conductor {
+            from: read_node_index(&format!("Conductor {} from node", i), count)?,
+            to: read_node_index(&format!("Conductor {} to node", i), count)?,
+            conductance: read_f64(&format!("Conductor {} conductance (W/K)", i))?,
+        });
+    }
+    let mut radiative_links = Vec::new();
+    for i in 1..=read_count("Enter number of radiative links")? {
+        radiative_links.push(RadiativeLink {
+            from: read_node_index(&format!("Radiative link {} from node", i), count)?,
+            to: read_node_index(&format!("Radiative link {} to node", i), count)?,
+            exchange_factor: read_f64(&format!("Radiative link {} exchange factor eps*A*F (m^2)", i))?,
+        });
+    }
+    let orbit_period = read_positive_f64("Enter orbit period (min)")? * 60.0;
+    let eclipse_fraction = read_f64("Enter eclipse fraction of the orbit (%)")? / 100.0;
+    let orbits = read_positive_f64("Enter number of orbits to simulate")?;
+    let step = read_positive_f64("Enter output step (s)")?;
+    
+    let model = ThermalModel { nodes, conductors, radiative_links, environment, orbit_period, eclipse_fraction };
+    let points = simulate_transient(&model, orbits * orbit_period, step)?;
+    for point in &points {
+        let temperatures: Vec<String> =
+            point.temperatures.iter().map(|t| format!("{:>8.2}", t.to_celsius().0)).collect();
+        println!(
+            "t = {:>9.1} s  {:<7}  {} C",
+            point.time,
+            if point.sunlit { "sunlit" } else { "eclipse" },
+            temperatures.join(" ")
+        );
+    }
+    for (i, node) in model.nodes.iter().enumerate() {
+        let range = points.iter().map(|p| p.temperatures[i]);
+        let low = range.clone().fold(Kelvin(f64::INFINITY), |a, b| if b < a { b } else { a });
+        let high = range.fold(Kelvin(0.0), |a, b| if b > a { b } else { a });
+        println!("{}: {} to {}", node.name, low.to_celsius(), high.to_celsius());
+    }
+    
+    offer_export("Export node temperatures as CSV to file", points.len(), "points", |path| {
+        write_transient_csv(&model.nodes, &points, path)
+    });
+    Ok(())
+}
+
+// Helper function to read one mission maneuver, None when the list is done
+fn read_maneuver() -> Result<Option<Maneuver>, CalculationError> {
+    println!("Maneuvers: 1. Launch insertion correction  2. Hohmann transfer  3. Bi-elliptic transfer");
+    println!("           4. Plane change  5. Transfer with plane change  6. Phasing");
+    println!("           7. Station keeping  8. Disposal  9. Custom delta-v  0. Done");
+    let km = |prompt: &str| -> Result<f64, CalculationError> { Ok(read_positive_f64(prompt)? * 1000.0) };
+    let maneuver = match read_text("Add maneuver").as_str() {
+        "" | "0" => return Ok(None),
+        "1" => Maneuver::LaunchInsertion { delta_v: read_f64("  Enter insertion correction delta-v (m/s)")? },
+        "2" => Maneuver::Hohmann {
+            from_radius: km("  Enter initial orbital radius (km)")?,
+            to_radius: km("  Enter final orbital radius (km)")?,
+        },
+        "3" => Maneuver::BiElliptic {
+            from_radius: km("  Enter initial orbital radius (km)")?,
+            to_radius: km("  Enter final orbital radius (km)")?,
+            intermediate_radius: km("  Enter intermediate apoapsis radius (km)")?,
+        },
+        "4" => Maneuver::PlaneChange {
+            radius: km("  Enter orbital radius (km)")?,
+            delta_i: read_positive_f64("  Enter inclination change (degrees)")?.to_radians(),
+        },
+        "5" => Maneuver::CombinedTransfer {
+            from_radius: km("  Enter initial orbital radius (km)")?,
+            to_radius: km("  Enter final orbital radius (km)")?,
+            delta_i: read_positive_f64("  Enter inclination change (degrees)")?.to_radians(),
+        },
+        "6" => Maneuver::Phasing {
+            radius: km("  Enter orbital radius (km)")?,
+            phase_angle: read_f64("  Enter phase angle of the target ahead (degrees)")?.to_radians(),
+            revolutions: read_positive_count("  Enter number of phasing revolutions")?,
+        },
+        "7" => Maneuver::StationKeeping {
+            delta_v_per_year: read_f64("  Enter station keeping delta-v per year (m/s)")?,
+            years: read_f64("  Enter years of station keeping")?,
+        },
+        "8" => Maneuver::Disposal {
+            from_radius: km("  Enter operational orbital radius (km)")?,
+            to_radius: km("  Enter disposal perigee or graveyard radius (km)")?,
+        },
+        "9" => Maneuver::Custom { delta_v: read_f64("  Enter delta-v (m/s)")? },
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    Ok(Some(maneuver))
+}
+
+fn report_mission_budget(catalog: &BodyCatalog) -> Result<(), CalculationError> {
+    let body = read_body(catalog)?;
+    let dry_mass = read_positive_f64("Enter spacecraft dry mass (kg)")?;
+    let propellant_mass = read_f64("Enter propellant loaded (kg)")?;
+    let default_isp = read_positive_f64("Enter main thruster specific impulse (s)")?;
+    let policy = read_text("Margin: 1. None  2. Same on every maneuver  3. Deterministic/statistical [1]");
+    let margin = match policy.as_str() {
+        "" | "1" => MarginPolicy::None,
+        "2" => MarginPolicy::Uniform(read_f64("Enter delta-v margin (%)")? / 100.0),
+        "3" => MarginPolicy::ByKind {
+            deterministic: read_f64("Enter margin on deterministic maneuvers (%)")? / 100.0,
+            statistical: read_f64("Enter margin on statistical maneuvers (%)")? / 100.0,
+        },
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    let propellant_reserve = read_f64_or("Enter propellant reserve (% of propellant used)", 0.0)? / 100.0;
+    
+    let mut steps = Vec::new();
+    while let Some(maneuver) = read_maneuver()? {
+        let isp = read_f64_or("  Enter specific impulse (s)", default_isp)?;
+        steps.push(MissionStep { maneuver, isp });
+    }
+    
+    let plan = MissionPlan { body, dry_mass, propellant_mass, steps, margin, propellant_reserve };
+    let budget = mission_budget(&plan)?;
+    println!("Mission budget ({})", margin);
+    println!("{}", budget);
+    
+    offer_export("Export budget as CSV to file", budget.lines.len(), "maneuvers", |path| {
+        write_budget_csv(&budget, path)
+    });
+    Ok(())
+}
+
+// Helper function to load Earth orientation parameters, nominal when no file
+// is given and None when the file cannot be read
+fn read_eop_table() -> Option<EopTable> {
+    let path = read_text("Enter EOP CSV file (leave empty for none)");
+    if path.is_empty() {
+        return Some(EopTable::default());
+    }
+    match load_eop_file(&path) {
+        Ok(table) => Some(table),
+        Err(e) => {
+            println!("Error: {}", e);
+            None
+        }
+    }
+}
+
+fn report_frames() -> Result<(), CalculationError> {
+    let text = read_text("Enter input frame (GCRF, MOD, TOD, TEME, PEF, ITRF) [GCRF]");
+    let frame = if text.is_empty() { Frame::Gcrf } else { parse_frame(&text)? };
+    let state = read_state_vector()?;
+    let epoch = read_epoch("Enter state epoch")?.julian_date(TimeScale::Utc);
+    let Some(table) = read_eop_table() else {
+        return Ok(());
+    };
+    let eop = table.at(epoch);
+    
+    let body = CentralBody::earth();
+    let input = FramedState::new(frame, epoch, state);
+    println!(
+        "Epoch: {} UTC, UT1-UTC {:.4} s, TAI-UTC {:.0} s",
+        format_date_time(epoch),
+        eop.ut1_minus_utc,
+        eop.tai_minus_utc
+    );
+    for target in Frame::ALL {
+        println!("{}", input.to_frame(target, &eop));
+    }
+    let ecef = input.to_frame(Frame::Itrf, &eop).state.position;
+    println!("Geodetic (WGS-84): {}", Geodetic::from_ecef(ecef, &body));
+    
+    if read_text("Compute look angles from a site? (y/N)").eq_ignore_ascii_case("y") {
+        let site = Geodetic {
+            latitude: read_f64("Enter site latitude (degrees)")?.to_radians(),
+            longitude: read_f64("Enter site longitude (degrees, east positive)")?.to_radians(),
+            altitude: read_f64("Enter site altitude (m)")?,
+        };
+        let offset = ecef - site.to_ecef(&body);
+        let sez = site.sez_rotation() * offset / 1000.0;
+        let enu = site.enu_rotation() * offset / 1000.0;
+        println!("SEZ: [{:.3}, {:.3}, {:.3}] km", sez.x, sez.y, sez.z);
+        println!("ENU: [{:.3}, {:.3}, {:.3}] km", enu.x, enu.y, enu.z);
+        println!("{}", site.look_at(&body, ecef));
+    }
+    Ok(())
+}
+
+fn report_time_scales() -> Result<(), CalculationError> {
+    let epoch = read_epoch("Enter epoch")?;
+    
+    println!("{:<5} {:<28} {:<24} {:>18} {:>16}", "Scale", "Calendar", "Ordinal", "JD", "MJD");
+    for scale in TimeScale::ALL {
+        println!(
+            "{:<5} {:<28} {:<24} {:>18.9} {:>16.9}",
+            scale.to_string(),
+            epoch.to_iso(scale, 6),
+            epoch.to_ordinal(scale, 3),
+            epoch.julian_date(scale),
+            epoch.mjd(scale)
+        );
+    }
+    let (year, day_of_year) = epoch.day_of_year(TimeScale::Utc);
+    println!("Day of year (UTC): {} day {:.9}", year, day_of_year);
+    let (week, seconds) = epoch.gps_week();
+    println!("GPS week {} seconds of week {:.6}", week, seconds);
+    println!("Unix time: {:.6} s", epoch.unix_seconds());
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
+    match epoch.to_cuc() {
+        Ok(code) => println!("CCSDS CUC (TAI, P-field 1F): {}", hex(&code)),
+        Err(e) => println!("CCSDS CUC: {}", e),
+    }
+    match epoch.to_cds() {
+        Ok(code) => println!("CCSDS CDS (UTC, P-field 41): {}", hex(&code)),
+        Err(e) => println!("CCSDS CDS: {}", e),
+    }
+    Ok(())
+}
+
+// State at a time after the epoch, from whichever propagator the user picked
+type Propagator = Box<dyn Fn(f64) -> Result<FramedState, CalculationError>>;
+
+fn report_doppler() -> Result<(), CalculationError> {
+    // Ground stations sit on the Earth, so the orbit is always geocentric
+    let body = CentralBody::earth();
+    let (epoch, propagate): (f64, Propagator) =
+        match read_text("Orbit source: 1. State vector  2. TLE file [1]").as_str() {
+            "" | "1" => {
+                let state = read_state_vector()?;
+                let epoch = read_epoch("Enter state epoch")?.julian_date(TimeScale::Utc);
+                let gcrf = move |t: f64, state| FramedState::new(Frame::Gcrf, epoch + t / 86400.0, state);
+                if read_text("Include J2 perturbations? (y/N)").eq_ignore_ascii_case("y") {
+                    let j2 = J2Propagator::new(state, body.clone());
+                    (epoch, Box::new(move |t| Ok(gcrf(t, j2.state_at(t)?))))
+                } else {
+                    let mu = body.mu;
+                    (epoch, Box::new(move |t| Ok(gcrf(t, propagate_state(&state, mu, t)?))))
+                }
+            }
+            "2" => match read_tle()? {
+                Some(tle) => {
+                    let satellite = Sgp4::from_tle(&tle)?;
+                    (tle.epoch, Box::new(move |t| satellite.propagate_framed(t)))
+                }
+                None => return Ok(()),
+            },
+            _ => return Err(CalculationError::InvalidInput),
+        };
+    let station = read_ground_station()?;
+    let days = read_positive_f64("Enter prediction window (days)")?;
+    let Some(eop) = read_eop_table() else {
+        return Ok(());
+    };
+    let passes = predict_passes(&station, &body, &eop, 0.0, days * 86400.0, 30.0, &propagate)?;
+    print_passes(&station, epoch, &passes);
+    if passes.is_empty() {
+        return Ok(());
+    }
+
+    let pass = &passes[read_node_index("Select pass", passes.len())?];
+    let step = read_positive_f64("Enter tuning table step (s)")?;
+    let frequency = |prompt: &str| -> Result<Option<f64>, CalculationError> {
+        let mhz = read_f64_or(prompt, 0.0)?;
+        Ok(if mhz > 0.0 { Some(mhz * 1e6) } else { None })
+    };
+    let downlink = frequency("Enter downlink frequency (MHz, 0 for none)")?;
+    let uplink = frequency("Enter uplink frequency (MHz, 0 for none)")?;
+
+    let table = doppler_table(&station, &body, &eop, pass.aos, pass.los, step, downlink, uplink, &propagate)?;
+    let start = Epoch::from_julian_date(epoch, TimeScale::Utc);
+    println!(
+        "{:<24} {:>7} {:>6} {:>10} {:>9} {:>16} {:>10} {:>16} {:>10}",
+        "UTC", "Az", "El", "Range km", "Rdot m/s", "Downlink MHz", "Shift Hz", "Uplink MHz", "Shift Hz"
+    );
+    let column = |tuned: Option<f64>, nominal: Option<f64>| match (tuned, nominal) {
+        (Some(tuned), Some(nominal)) => (format!("{:.6}", tuned / 1e6), format!("{:.1}", tuned - nominal)),
+        _ => ("-".to_string(), "-".to_string()),
+    };
+    for point in &table.points {
+        let look = &point.look;
+        let (down, down_shift) = column(point.downlink, downlink);
+        let (up, up_shift) = column(point.uplink, uplink);
+        println!(
+            "{:<24} {:>7.2} {:>6.2} {:>10.3} {:>9.2} {:>16} {:>10} {:>16} {:>10}",
+            (start + look.time).to_iso(TimeScale::Utc, 3),
+            look.azimuth.to_degrees(),
+            look.elevation.to_degrees(),
+            look.range / 1000.0,
+            look.range_rate,
+            down,
+            down_shift,
+            up,
+            up_shift
+        );
+    }
+    let acceleration = table.max_range_acceleration();
+    for (name, carrier) in [("Downlink", downlink), ("Uplink", uplink)] {
+        if let Some(f) = carrier {
+            println!("{} maximum Doppler rate: {:.1} Hz/s", name, f * acceleration / SPEED_OF_LIGHT);
+        }
+    }
+
+    offer_export("Export tuning table as CSV to file", table.points.len(), "rows", |path| {
+        write_doppler_csv(&table, &start, path)
+    });
+    Ok(())
+}
+
+fn report_antenna() -> Result<(), CalculationError> {
+    let antenna = read_antenna()?;
+    let frequency = read_positive_f64("Enter frequency (GHz)")? * 1e9;
+    let error = read_f64("Enter pointing error (degrees)")?.to_radians();
+    let performance = antenna.performance(frequency, error)?;
+    println!("{}", antenna);
+    println!("{}", performance);
+
+    // Loss across the main lobe, to size a pointing requirement
+    println!("{:>12} {:>12} {:>12}", "Off-axis deg", "Loss dB", "Gain dBi");
+    for tenth in 0..=10 {
+        let angle = f64::from(tenth) / 10.0 * performance.beamwidth;
+        let loss = antenna.pointing_loss_db(frequency, angle)?;
+        println!("{:>12.3} {:>12.2} {:>12.2}", angle.to_degrees(), loss, performance.gain_dbi - loss);
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+// Impulsive orbit transfer and rendezvous maneuvers
+//
+// All orbits are circular at the start and end of a maneuver. Radii are in
+// m, angles in rad. Every maneuver returns a `ManeuverPlan` listing each
+// burn with the time it happens and the total transfer time.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use std::f64::consts::PI;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Burn {
+    pub label: String,
+    pub delta_v: f64, // m/s
+    pub time: f64,    // s after the first burn
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManeuverPlan {
+    pub name: &'static str,
+    pub burns: Vec<Burn>,
+    pub transfer_time: f64, // s from the first to the last burn
+}
+
+impl ManeuverPlan {
+    pub fn total_delta_v(&self) -> f64 {
+        self.burns.iter().map(|b| b.delta_v).sum()
+    }
+}
+
+impl fmt::Display for ManeuverPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        writeln!(f, "{:<40} {:>12} {:>14}", "Burn", "Delta-v m/s", "Time s")?;
+        for burn in &self.burns {
+            writeln!(f, "{:<40} {:>12.3} {:>14.1}", burn.label, burn.delta_v, burn.time)?;
+        }
+        writeln!(f, "{:<40} {:>12.3}", "Total", self.total_delta_v())?;
+        write!(f, "Transfer time: {:.1} s ({:.3} h)", self.transfer_time, self.transfer_time / 3600.0)
+    }
+}
+
+fn burn(label: impl Into<String>, delta_v: f64, time: f64) -> Burn {
+    Burn { label: label.into(), delta_v, time }
+}
+
+fn circular_speed(mu: f64, r: f64) -> f64 {
+    (mu / r).sqrt()
+}
+
+// Vis-viva speed at radius r on an orbit with semi-major axis a
+fn orbit_speed(mu: f64, r: f64, a: f64) -> f64 {
+    (mu * (2.0 / r - 1.0 / a)).sqrt()
+}
+
+fn half_period(mu: f64, a: f64) -> f64 {
+    PI * (a.powi(3) / mu).sqrt()
+}
+
+fn check_radii(body: &CentralBody, radii: &[f64]) -> Result<(), CalculationError> {
+    if radii.iter().any(|&r| !r.is_finite() || r <= 0.0) {
+        return Err(CalculationError::InvalidInput);
+    }
+    if radii.iter().any(|&r| r < body.equatorial_radius) {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(())
+}
+
+// Two-burn Hohmann transfer between coplanar circular orbits
+pub fn hohmann(body: &CentralBody, r1: f64, r2: f64) -> Result<ManeuverPlan, CalculationError> {
+    check_radii(body, &[r1, r2])?;
+    let mu = body.mu;
+    let a_transfer = (r1 + r2) / 2.0;
+    let dv1 = (orbit_speed(mu, r1, a_transfer) - circular_speed(mu, r1)).abs();
+    let dv2 = (circular_speed(mu, r2) - orbit_speed(mu, r2, a_transfer)).abs();
+    let tof = half_period(mu, a_transfer);
+
+    Ok(ManeuverPlan {
+        name: "Hohmann transfer",
+        burns: vec![burn("Departure burn", dv1, 0.0), burn("Arrival burn", dv2, tof)],
+        transfer_time: tof,
+    })
+}
+
+// Three-burn bi-elliptic transfer through an intermediate apoapsis `rb`
+pub fn bi_elliptic(body: &CentralBody, r1: f64, r2: f64, rb: f64) -> Result<ManeuverPlan, CalculationError> {
+    check_radii(body, &[r1, r2, rb])?;
+    if rb < r1.max(r2) {
+        return Err(CalculationError::OutOfRange);
+    }
+    let mu = body.mu;
+    let a1 = (r1 + rb) / 2.0;
+    let a2 = (r2 + rb) / 2.0;
+
+    let dv1 = (orbit_speed(mu, r1, a1) - circular_speed(mu, r1)).abs();
+    let dv2 = (orbit_speed(mu, rb, a2) - orbit_speed(mu, rb, a1)).abs();
+    let dv3 = (orbit_speed(mu, r2, a2) - circular_speed(mu, r2)).abs();
+    let t1 = half_period(mu, a1);
+    let t2 = half_period(mu, a2);
+
+    Ok(ManeuverPlan {
+        name: "Bi-elliptic transfer",
+        burns: vec![
+            burn("Departure burn", dv1, 0.0),
+            burn("Intermediate apoapsis burn", dv2, t1),
+            burn("Arrival burn", dv3, t1 + t2),
+        ],
+        transfer_time: t1 + t2,
+    })
+}
+
+// Pure inclination change of a circular orbit, performed at a node
+pub fn inclination_change(body: &CentralBody, radius: f64, delta_i: f64) -> Result<ManeuverPlan, CalculationError> {
+    check_radii(body, &[radius])?;
+    if !(0.0..=PI).contains(&delta_i.abs()) {
+        return Err(CalculationError::OutOfRange);
+    }
+    let dv = 2.0 * circular_speed(body.mu, radius) * (delta_i.abs() / 2.0).sin();
+
+    Ok(ManeuverPlan {
+        name: "Inclination change",
+        burns: vec![burn(format!("Plane change {:.3} deg", delta_i.abs().to_degrees()), dv, 0.0)],
+        transfer_time: 0.0,
+    })
+}
+
+// Velocity change for rotating v1 into v2 by angle theta
+fn plane_change_burn(v1: f64, v2: f64, theta: f64) -> f64 {
+    (v1 * v1 + v2 * v2 - 2.0 * v1 * v2 * theta.cos()).max(0.0).sqrt()
+}
+
+// Hohmann transfer combined with an inclination change, with the plane
+// change split between both burns so that the total delta-v is minimal.
+// Returns the plan and the fraction of the plane change done at departure.
+pub fn combined_plane_change(
+    body: &CentralBody,
+    r1: f64,
+    r2: f64,
+    delta_i: f64,
+) -> Result<(ManeuverPlan, f64), CalculationError> {
+    check_radii(body, &[r1, r2])?;
+    if !(0.0..=PI).contains(&delta_i.abs()) {
+        return Err(CalculationError::OutOfRange);
+    }
+    let mu = body.mu;
+    let delta_i = delta_i.abs();
+    let a_transfer = (r1 + r2) / 2.0;
+    let v1 = circular_speed(mu, r1);
+    let v2 = circular_speed(mu, r2);
+    let v_depart = orbit_speed(mu, r1, a_transfer);
+    let v_arrive = orbit_speed(mu, r2, a_transfer);
+
+    let total = |split: f64| {
+        plane_change_burn(v1, v_depart, split * delta_i) + plane_change_burn(v_arrive, v2, (1.0 - split) * delta_i)
+    };
+
+    // The total is convex in the split, so a golden-section search finds the optimum
+    let golden = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..100 {
+        let c = high - golden * (high - low);
+        let d = low + golden * (high - low);
+        if total(c) < total(d) {
+            high = d;
+        } else {
+            low = c;
+        }
+    }
+    let split = (low + high) / 2.0;
+
+    let dv1 = plane_change_burn(v1, v_depart, split * delta_i);
+    let dv2 = plane_change_burn(v_arrive, v2, (1.0 - split) * delta_i);
+    let tof = half_period(mu, a_transfer);
+
+    let plan = ManeuverPlan {
+        name: "Combined plane change and altitude transfer",
+        burns: vec![
+            burn(format!("Departure burn, plane change {:.3} deg", (split * delta_i).to_degrees()), dv1, 0.0),
+            burn(format!("Arrival burn, plane change {:.3} deg", ((1.0 - split) * delta_i).to_degrees()), dv2, tof),
+        ],
+        transfer_time: tof,
+    };
+    Ok((plan, split))
+}
+
+// Co-orbital phasing in a circular orbit: the chaser flies `revolutions`
+// laps of a phasing orbit so that it meets a target `phase_angle` ahead of
+// it (negative when the target trails the chaser)
+pub fn phasing(
+    body: &CentralBody,
+    radius: f64,
+    phase_angle: f64,
+    revolutions: u32,
+) -> Result<ManeuverPlan, CalculationError> {
+    check_radii(body, &[radius])?;
+    if revolutions == 0 || phase_angle.abs() >= 2.0 * PI {
+        return Err(CalculationError::InvalidInput);
+    }
+    let mu = body.mu;
+    let n = (mu / radius.powi(3)).sqrt();
+    let k = revolutions as f64;
+
+    // The target covers k laps minus the phase angle while the chaser flies k laps
+    let phasing_period = (2.0 * PI * k - phase_angle) / (k * n);
+    let a_phasing = (mu * (phasing_period / (2.0 * PI)).powi(2)).cbrt();
+    // The far side of the phasing orbit must clear the surface
+    let other_apsis = 2.0 * a_phasing - radius;
+    if other_apsis < body.equatorial_radius {
+        return Err(CalculationError::OutOfRange);
+    }
+
+    let dv = (orbit_speed(mu, radius, a_phasing) - circular_speed(mu, radius)).abs();
+    let tof = k * phasing_period;
+
+    Ok(ManeuverPlan {
+        name: "Phasing maneuver",
+        burns: vec![burn("Enter phasing orbit", dv, 0.0), burn("Return to circular orbit", dv, tof)],
+        transfer_time: tof,
+    })
+}
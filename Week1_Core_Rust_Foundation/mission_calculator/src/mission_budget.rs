@@ -0,0 +1,362 @@
+// Mission delta-v and propellant budget
+//
+// A mission is an ordered list of maneuvers flown by one spacecraft. Each
+// maneuver's delta-v comes from the impulsive maneuver models (maneuvers.rs)
+// or is given directly, a margin is added according to the chosen policy,
+// and the rocket equation turns it into propellant drawn from the current
+// vehicle mass, so later maneuvers are cheaper as the vehicle gets lighter.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::maneuvers::{bi_elliptic, combined_plane_change, hohmann, inclination_change, phasing};
+use crate::staging::G0;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+// Radii in m, angles in rad, delta-v in m/s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Maneuver {
+    // Correction of launcher insertion dispersions
+    LaunchInsertion { delta_v: f64 },
+    Hohmann { from_radius: f64, to_radius: f64 },
+    BiElliptic { from_radius: f64, to_radius: f64, intermediate_radius: f64 },
+    PlaneChange { radius: f64, delta_i: f64 },
+    // Hohmann transfer with the plane change split optimally between burns
+    CombinedTransfer { from_radius: f64, to_radius: f64, delta_i: f64 },
+    Phasing { radius: f64, phase_angle: f64, revolutions: u32 },
+    StationKeeping { delta_v_per_year: f64, years: f64 },
+    // End of life: below `from_radius` a single burn lowers the perigee to
+    // `to_radius` for re-entry, above it a Hohmann transfer raises the
+    // orbit to a graveyard orbit
+    Disposal { from_radius: f64, to_radius: f64 },
+    Custom { delta_v: f64 },
+}
+
+impl Maneuver {
+    // Statistical maneuvers correct errors that are only known as a
+    // distribution; the rest are computed from the mission geometry
+    pub fn is_statistical(&self) -> bool {
+        matches!(self, Maneuver::LaunchInsertion { .. } | Maneuver::StationKeeping { .. })
+    }
+
+    // Delta-v without margin, m/s
+    pub fn delta_v(&self, body: &CentralBody) -> Result<f64, CalculationError> {
+        let dv = match *self {
+            Maneuver::LaunchInsertion { delta_v } | Maneuver::Custom { delta_v } => delta_v,
+            Maneuver::Hohmann { from_radius, to_radius } => hohmann(body, from_radius, to_radius)?.total_delta_v(),
+            Maneuver::BiElliptic { from_radius, to_radius, intermediate_radius } => {
+                bi_elliptic(body, from_radius, to_radius, intermediate_radius)?.total_delta_v()
+            }
+            Maneuver::PlaneChange { radius, delta_i } => inclination_change(body, radius, delta_i)?.total_delta_v(),
+            Maneuver::CombinedTransfer { from_radius, to_radius, delta_i } => {
+                combined_plane_change(body, from_radius, to_radius, delta_i)?.0.total_delta_v()
+            }
+            Maneuver::Phasing { radius, phase_angle, revolutions } => {
+                phasing(body, radius, phase_angle, revolutions)?.total_delta_v()
+            }
+            Maneuver::StationKeeping { delta_v_per_year, years } => {
+                if years < 0.0 {
+                    return Err(CalculationError::NegativeValue);
+                }
+                delta_v_per_year * years
+            }
+            Maneuver::Disposal { from_radius, to_radius } => {
+                if to_radius >= from_radius {
+                    hohmann(body, from_radius, to_radius)?.total_delta_v()
+                } else {
+                    if from_radius <= 0.0 || to_radius <= 0.0 {
+                        return Err(CalculationError::InvalidInput);
+                    }
+                    // Circular speed minus apoapsis speed of the disposal ellipse
+                    let a = (from_radius + to_radius) / 2.0;
+                    (body.mu / from_radius).sqrt() - (body.mu * (2.0 / from_radius - 1.0 / a)).sqrt()
+                }
+            }
+        };
+        if dv < 0.0 {
+            return Err(CalculationError::NegativeValue);
+        }
+        Ok(dv)
+    }
+}
+
+impl fmt::Display for Maneuver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let km = |r: f64| r / 1000.0;
+        match *self {
+            Maneuver::LaunchInsertion { .. } => write!(f, "Launch insertion correction"),
+            Maneuver::Hohmann { from_radius, to_radius } => {
+                write!(f, "Hohmann {:.0} -> {:.0} km", km(from_radius), km(to_radius))
+            }
+            Maneuver::BiElliptic { from_radius, to_radius, .. } => {
+                write!(f, "Bi-elliptic {:.0} -> {:.0} km", km(from_radius), km(to_radius))
+            }
+            Maneuver::PlaneChange { delta_i, .. } => write!(f, "Plane change {:.2} deg", delta_i.to_degrees()),
+            Maneuver::CombinedTransfer { from_radius, to_radius, delta_i } => write!(
+                f,
+                "Transfer {:.0} -> {:.0} km, {:.2} deg",
+                km(from_radius),
+                km(to_radius),
+                delta_i.to_degrees()
+            ),
+            Maneuver::Phasing { phase_angle, .. } => write!(f, "Phasing {:.2} deg", phase_angle.to_degrees()),
+            Maneuver::StationKeeping { years, .. } => write!(f, "Station keeping {:.1} years", years),
+            Maneuver::Disposal { to_radius, .. } => write!(f, "Disposal to {:.0} km", km(to_radius)),
+            Maneuver::Custom { .. } => write!(f, "Custom maneuver"),
+        }
+    }
+}
+
+// How much delta-v is added on top of each maneuver, as fractions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarginPolicy {
+    None,
+    // The same fraction on every maneuver
+    Uniform(f64),
+    // Separate fractions for deterministic and statistical maneuvers
+    ByKind { deterministic: f64, statistical: f64 },
+}
+
+impl MarginPolicy {
+    pub fn margin(&self, maneuver: &Maneuver) -> f64 {
+        match *self {
+            MarginPolicy::None => 0.0,
+            MarginPolicy::Uniform(fraction) => fraction,
+            MarginPolicy::ByKind { deterministic, statistical } => {
+                if maneuver.is_statistical() { statistical } else { deterministic }
+            }
+        }
+    }
+}
+
+impl fmt::Display for MarginPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarginPolicy::None => write!(f, "no margin"),
+            MarginPolicy::Uniform(fraction) => write!(f, "{:.1}% on every maneuver", fraction * 100.0),
+            MarginPolicy::ByKind { deterministic, statistical } => write!(
+                f,
+                "{:.1}% deterministic, {:.1}% statistical",
+                deterministic * 100.0,
+                statistical * 100.0
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissionStep {
+    pub maneuver: Maneuver,
+    pub isp: f64, // s, of the thruster flying this maneuver
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissionPlan {
+    pub body: CentralBody,
+    pub dry_mass: f64,        // kg
+    pub propellant_mass: f64, // kg loaded at launch
+    pub steps: Vec<MissionStep>,
+    pub margin: MarginPolicy,
+    // Fraction of the propellant used that is held back for residuals and
+    // contingencies
+    pub propellant_reserve: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetLine {
+    pub name: String,
+    pub base_delta_v: f64, // m/s without margin
+    pub margin: f64,       // m/s
+    pub propellant: f64,   // kg
+    pub initial_mass: f64, // kg before the maneuver
+    pub final_mass: f64,   // kg after the maneuver
+    pub shortfall: f64,    // m/s of the delta-v not flown for lack of propellant
+}
+
+impl BudgetLine {
+    pub fn delta_v(&self) -> f64 {
+        self.base_delta_v + self.margin
+    }
+
+    pub fn flown_delta_v(&self) -> f64 {
+        self.delta_v() - self.shortfall
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissionBudget {
+    pub lines: Vec<BudgetLine>,
+    pub reserve: f64,           // kg
+    pub loaded_propellant: f64, // kg
+    // Index of the first step whose propellant, with the reserve held back
+    // for it, is no longer covered by what was loaded
+    pub breaks_at: Option<usize>,
+}
+
+impl MissionBudget {
+    pub fn total_delta_v(&self) -> f64 {
+        self.lines.iter().map(|l| l.delta_v()).sum()
+    }
+
+    pub fn total_margin(&self) -> f64 {
+        self.lines.iter().map(|l| l.margin).sum()
+    }
+
+    pub fn propellant_used(&self) -> f64 {
+        self.lines.iter().map(|l| l.propellant).sum()
+    }
+
+    // Propellant left after every maneuver and the reserve
+    pub fn propellant_remaining(&self) -> f64 {
+        (self.loaded_propellant - self.propellant_used() - self.reserve).max(0.0)
+    }
+
+    // Delta-v the vehicle could not fly once its tanks were empty, m/s
+    pub fn delta_v_shortfall(&self) -> f64 {
+        self.lines.iter().map(|l| l.shortfall).sum()
+    }
+
+    // Part of the reserve the loaded propellant does not cover, kg
+    pub fn reserve_shortfall(&self) -> f64 {
+        (self.propellant_used() + self.reserve - self.loaded_propellant).max(0.0)
+    }
+
+    pub fn closes(&self) -> bool {
+        self.breaks_at.is_none()
+    }
+}
+
+impl fmt::Display for MissionBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<4} {:<36} {:>10} {:>9} {:>10} {:>12} {:>12}",
+            "Step", "Maneuver", "Dv m/s", "Margin", "Prop kg", "Mass in kg", "Mass out kg"
+        )?;
+        writeln!(f, "{}", "-".repeat(99))?;
+        for (i, line) in self.lines.iter().enumerate() {
+            write!(
+                f,
+                "{:<4} {:<36} {:>10.2} {:>9.2} {:>10.3} {:>12.3} {:>12.3}",
+                i + 1,
+                line.name,
+                line.delta_v(),
+                line.margin,
+                line.propellant,
+                line.initial_mass,
+                line.final_mass
+            )?;
+            if self.breaks_at == Some(i) {
+                write!(f, "  <- budget breaks")?;
+            }
+            if line.shortfall > 0.0 {
+                write!(f, "  ({:.2} m/s not flown)", line.shortfall)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "{}", "-".repeat(99))?;
+        writeln!(
+            f,
+            "Total delta-v:        {:>10.2} m/s (margin {:.2} m/s)",
+            self.total_delta_v(),
+            self.total_margin()
+        )?;
+        writeln!(f, "Propellant used:      {:>10.3} kg", self.propellant_used())?;
+        writeln!(f, "Propellant reserve:   {:>10.3} kg", self.reserve)?;
+        writeln!(
+            f,
+            "Propellant remaining: {:>10.3} kg of {:.3} kg loaded",
+            self.propellant_remaining(),
+            self.loaded_propellant
+        )?;
+        if self.reserve_shortfall() > 0.0 {
+            writeln!(f, "Reserve short by:     {:>10.3} kg", self.reserve_shortfall())?;
+        }
+        if self.delta_v_shortfall() > 0.0 {
+            writeln!(f, "Delta-v not flown:    {:>10.2} m/s", self.delta_v_shortfall())?;
+        }
+        write!(f, "Budget {}", if self.closes() { "closes" } else { "does NOT close" })
+    }
+}
+
+// Fly the plan step by step from the wet mass. Once the propellant runs out
+// the vehicle stays at its dry mass and the rest of each maneuver's delta-v
+// is counted as not flown, so the whole shortfall shows in the table.
+pub fn mission_budget(plan: &MissionPlan) -> Result<MissionBudget, CalculationError> {
+    if plan.dry_mass <= 0.0 || plan.propellant_mass < 0.0 || !(0.0..1.0).contains(&plan.propellant_reserve) {
+        return Err(CalculationError::InvalidInput);
+    }
+    let mut mass = plan.dry_mass + plan.propellant_mass;
+    let mut lines = Vec::with_capacity(plan.steps.len());
+    for step in &plan.steps {
+        if step.isp <= 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        let base_delta_v = step.maneuver.delta_v(&plan.body)?;
+        let fraction = plan.margin.margin(&step.maneuver);
+        if fraction < 0.0 {
+            return Err(CalculationError::NegativeValue);
+        }
+        let margin = base_delta_v * fraction;
+        let exhaust_velocity = step.isp * G0;
+        // Tsiolkovsky: m_final = m_initial exp(-dv / (Isp g0))
+        let wanted_mass = mass * (-(base_delta_v + margin) / exhaust_velocity).exp();
+        let (final_mass, shortfall) = if wanted_mass >= plan.dry_mass {
+            (wanted_mass, 0.0)
+        } else {
+            let flown = exhaust_velocity * (mass / plan.dry_mass).ln();
+            (plan.dry_mass, base_delta_v + margin - flown)
+        };
+        lines.push(BudgetLine {
+            name: step.maneuver.to_string(),
+            base_delta_v,
+            margin,
+            propellant: mass - final_mass,
+            initial_mass: mass,
+            final_mass,
+            shortfall,
+        });
+        mass = final_mass;
+    }
+
+    // The reserve is a share of the propellant used, so the budget breaks at
+    // the first step where the propellant used so far plus its reserve
+    // outgrows the load, or where the tanks run dry
+    let breaks_at = lines
+        .iter()
+        .scan(0.0, |used, line| {
+            *used += line.propellant;
+            Some((*used, line.shortfall))
+        })
+        .position(|(used, shortfall)| shortfall > 0.0 || used * (1.0 + plan.propellant_reserve) > plan.propellant_mass);
+    let used: f64 = lines.iter().map(|l| l.propellant).sum();
+    Ok(MissionBudget {
+        lines,
+        reserve: used * plan.propellant_reserve,
+        loaded_propellant: plan.propellant_mass,
+        breaks_at,
+    })
+}
+
+pub fn budget_to_csv(budget: &MissionBudget) -> String {
+    let mut csv = String::from("step,maneuver,delta_v_m_s,margin_m_s,propellant_kg,initial_mass_kg,final_mass_kg,not_flown_m_s\n");
+    for (i, line) in budget.lines.iter().enumerate() {
+        let _ = writeln!(
+            csv,
+            "{},\"{}\",{:.3},{:.3},{:.4},{:.4},{:.4},{:.3}",
+            i + 1,
+            line.name.replace('"', "\"\""),
+            line.delta_v(),
+            line.margin,
+            line.propellant,
+            line.initial_mass,
+            line.final_mass,
+            line.shortfall
+        );
+    }
+    csv
+}
+
+pub fn write_budget_csv<P: AsRef<Path>>(budget: &MissionBudget, path: P) -> std::io::Result<()> {
+    fs::write(path, budget_to_csv(budget))
+}
@@ -0,0 +1,506 @@
+// Free-space and atmospheric path loss
+//
+// Free-space loss comes straight from the Friis equation. The atmospheric
+// terms follow the ITU-R recommendations used for Earth-space links:
+//   - gaseous absorption: P.676 annex 2 (oxygen up to 54 GHz, water vapour)
+//   - rain attenuation:   P.618 section 2.2.1.1 with k and alpha from P.838
+//   - scintillation:      P.618 section 2.4.1
+// Climate inputs that ITU-R takes from its digital maps (rain rate exceeded
+// 0.01% of the year, rain height, wet refractivity) are passed in directly.
+// Public functions take SI units (m, Hz, rad) and return losses in dB.
+use crate::CalculationError;
+use std::f64::consts::PI;
+
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0; // m/s
+// Effective Earth radius used for low-elevation slant paths, km
+const EFFECTIVE_EARTH_RADIUS_KM: f64 = 8500.0;
+
+// Free-space path loss in dB for a range in m and a frequency in Hz
+pub fn free_space_path_loss(range: f64, frequency: f64) -> Result<f64, CalculationError> {
+    if range <= 0.0 || frequency <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(20.0 * (4.0 * PI * range * frequency / SPEED_OF_LIGHT).log10())
+}
+
+// Same loss for a range in km and a frequency in MHz, the units the familiar
+// 20 log10(d) + 20 log10(f) + 32.45 shortcut assumes
+pub fn free_space_path_loss_km_mhz(range_km: f64, frequency_mhz: f64) -> Result<f64, CalculationError> {
+    free_space_path_loss(range_km * 1000.0, frequency_mhz * 1e6)
+}
+
+// Ground-level meteorological conditions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Atmosphere {
+    pub pressure: f64,             // hPa
+    pub temperature: f64,          // deg C
+    pub water_vapour_density: f64, // g/m^3
+}
+
+impl Atmosphere {
+    // Mean annual global reference atmosphere (ITU-R P.835)
+    pub fn standard() -> Self {
+        Atmosphere { pressure: 1013.25, temperature: 15.0, water_vapour_density: 7.5 }
+    }
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Atmosphere::standard()
+    }
+}
+
+// Specific attenuation of dry air and water vapour in dB/km (P.676 annex 2)
+pub fn gaseous_specific_attenuation(frequency: f64, atmosphere: &Atmosphere) -> Result<(f64, f64), CalculationError> {
+    let f = frequency / 1e9;
+    if !(1.0..=54.0).contains(&f) {
+        return Err(CalculationError::OutOfRange);
+    }
+    if atmosphere.pressure <= 0.0 || atmosphere.temperature <= -273.15 || atmosphere.water_vapour_density < 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let rp = atmosphere.pressure / 1013.0;
+    let rt = 288.0 / (273.0 + atmosphere.temperature);
+    let rho = atmosphere.water_vapour_density;
+
+    let phi = |a: f64, b: f64, c: f64, d: f64| rp.powf(a) * rt.powf(b) * (c * (1.0 - rp) + d * (1.0 - rt)).exp();
+    let xi1 = phi(0.0717, -1.8132, 0.0156, -1.6515);
+    let xi2 = phi(0.5146, -4.6368, -0.1921, -5.7416);
+    let xi3 = phi(0.3414, -6.5851, 0.2130, -8.5854);
+    let oxygen = (7.2 * rt.powf(2.8) / (f * f + 0.34 * rp * rp * rt.powf(1.6))
+        + 0.62 * xi3 / ((54.0 - f).powf(1.16 * xi1) + 0.83 * xi2))
+        * f
+        * f
+        * rp
+        * rp
+        * 1e-3;
+
+    let eta1 = 0.955 * rp * rt.powf(0.68) + 0.006 * rho;
+    let eta2 = 0.735 * rp * rt.sqrt() + 0.0353 * rt.powi(4) * rho;
+    let g = |fi: f64| 1.0 + ((f - fi) / (f + fi)).powi(2);
+    let line = |strength: f64, width: f64, temp: f64, fi: f64| {
+        strength * eta1 * (temp * (1.0 - rt)).exp() / ((f - fi).powi(2) + width * eta1 * eta1)
+    };
+    let wing =
+        |strength: f64, eta: f64, temp: f64, fi: f64| strength * eta * (temp * (1.0 - rt)).exp() / (f - fi).powi(2);
+    let water = (line(3.98, 9.42, 2.23, 22.235) * g(22.0)
+        + line(11.96, 11.14, 0.7, 183.31)
+        + line(0.081, 6.29, 6.44, 321.226)
+        + line(3.66, 9.22, 1.6, 325.153)
+        + wing(25.37, eta1, 1.09, 380.0)
+        + wing(17.4, eta1, 1.46, 448.0)
+        + wing(844.6, eta1, 0.17, 557.0) * g(557.0)
+        + wing(290.0, eta1, 0.41, 752.0) * g(752.0)
+        + wing(8.3328e4, eta2, 0.99, 1780.0) * g(1780.0))
+        * f
+        * f
+        * rt.powf(2.5)
+        * rho
+        * 1e-4;
+
+    Ok((oxygen, water))
+}
+
+// Zenith-equivalent heights of oxygen and water vapour in km (P.676 annex 2)
+fn equivalent_heights(f: f64, rp: f64) -> (f64, f64) {
+    let t1 = 4.64 / (1.0 + 0.066 * rp.powf(-2.3))
+        * (-((f - 59.7) / (2.87 + 12.4 * (-7.9 * rp).exp())).powi(2)).exp();
+    let t2 = 0.14 * (2.12 * rp).exp() / ((f - 118.75).powi(2) + 0.031 * (2.2 * rp).exp());
+    let t3 = 0.0114 / (1.0 + 0.14 * rp.powf(-2.6)) * f * (-0.0247 + 0.0001 * f + 1.61e-6 * f * f)
+        / (1.0 - 0.0169 * f + 4.1e-5 * f * f + 3.2e-7 * f.powi(3));
+    let h_oxygen = (6.1 / (1.0 + 0.17 * rp.powf(-1.1)) * (1.0 + t1 + t2 + t3)).min(10.7 * rp.powf(0.3));
+
+    let sigma = 1.013 / (1.0 + (-8.6 * (rp - 0.57)).exp());
+    let h_water = 1.66
+        * (1.0
+            + 1.39 * sigma / ((f - 22.235).powi(2) + 2.56 * sigma)
+            + 3.37 * sigma / ((f - 183.31).powi(2) + 4.69 * sigma)
+            + 1.58 * sigma / ((f - 325.1).powi(2) + 2.89 * sigma));
+    (h_oxygen, h_water)
+}
+
+// Gaseous absorption along a slant path at elevations of 5 to 90 degrees
+pub fn gaseous_attenuation(frequency: f64, elevation: f64, atmosphere: &Atmosphere) -> Result<f64, CalculationError> {
+    check_elevation(elevation)?;
+    let (oxygen, water) = gaseous_specific_attenuation(frequency, atmosphere)?;
+    let (h_oxygen, h_water) = equivalent_heights(frequency / 1e9, atmosphere.pressure / 1013.0);
+    Ok((oxygen * h_oxygen + water * h_water) / elevation.sin())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RainCoefficients {
+    pub k: f64,
+    pub alpha: f64,
+}
+
+// Gaussian sum regression of P.838-3: rows of (a, b, c), then m and c
+fn p838_fit(rows: &[[f64; 3]], m: f64, c: f64, log_f: f64) -> f64 {
+    rows.iter().map(|[a, b, cj]| a * (-((log_f - b) / cj).powi(2)).exp()).sum::<f64>() + m * log_f + c
+}
+
+// Power-law rain coefficients for a given elevation and polarization tilt
+// (0 for horizontal, pi/2 for vertical, pi/4 for circular), ITU-R P.838-3
+pub fn rain_coefficients(
+    frequency: f64,
+    elevation: f64,
+    polarization_tilt: f64,
+) -> Result<RainCoefficients, CalculationError> {
+    let f = frequency / 1e9;
+    if !(1.0..=1000.0).contains(&f) {
+        return Err(CalculationError::OutOfRange);
+    }
+    let log_f = f.log10();
+
+    let k_h = 10.0_f64.powf(p838_fit(
+        &[
+            [-5.33980, -0.10008, 1.13098],
+            [-0.35351, 1.26970, 0.45400],
+            [-0.23789, 0.86036, 0.15354],
+            [-0.94158, 0.64552, 0.16817],
+        ],
+        -0.18961,
+        0.71147,
+        log_f,
+    ));
+    let k_v = 10.0_f64.powf(p838_fit(
+        &[
+            [-3.80595, 0.56934, 0.81061],
+            [-3.44965, -0.22911, 0.51059],
+            [-0.39902, 0.73042, 0.11899],
+            [0.50167, 1.07319, 0.27195],
+        ],
+        -0.16398,
+        0.63297,
+        log_f,
+    ));
+    let alpha_h = p838_fit(
+        &[
+            [-0.14318, 1.82442, -0.55187],
+            [0.29591, 0.77564, 0.19822],
+            [0.32177, 0.63773, 0.13164],
+            [-5.37610, -0.96230, 1.47828],
+            [16.1721, -3.29980, 3.43990],
+        ],
+        0.67849,
+        -1.95537,
+        log_f,
+    );
+    let alpha_v = p838_fit(
+        &[
+            [-0.07771, 2.33840, -0.76284],
+            [0.56727, 0.95545, 0.54039],
+            [-0.20238, 1.14520, 0.26809],
+            [-48.2991, 0.791669, 0.116226],
+            [48.5833, 0.791459, 0.116479],
+        ],
+        -0.053739,
+        0.83433,
+        log_f,
+    );
+
+    let mix = elevation.cos().powi(2) * (2.0 * polarization_tilt).cos();
+    let k = (k_h + k_v + (k_h - k_v) * mix) / 2.0;
+    let alpha = (k_h * alpha_h + k_v * alpha_v + (k_h * alpha_h - k_v * alpha_v) * mix) / (2.0 * k);
+    Ok(RainCoefficients { k, alpha })
+}
+
+// Local rain climate and link geometry for P.618
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RainParameters {
+    pub rain_rate: f64,         // mm/h exceeded 0.01% of an average year (P.837)
+    pub rain_height: f64,       // m above mean sea level (P.839)
+    pub station_height: f64,    // m above mean sea level
+    pub station_latitude: f64,  // rad
+    pub polarization_tilt: f64, // rad
+}
+
+// Rain attenuation exceeded for (100 - availability)% of an average year,
+// for availabilities of 95% to 99.999%
+pub fn rain_attenuation(
+    frequency: f64,
+    elevation: f64,
+    availability: f64,
+    rain: &RainParameters,
+) -> Result<f64, CalculationError> {
+    check_elevation(elevation)?;
+    let p = exceedance(availability, 0.001, 5.0)?;
+    if rain.rain_rate < 0.0 {
+        return Err(CalculationError::NegativeValue);
+    }
+    let f = frequency / 1e9;
+    let rain_height = (rain.rain_height - rain.station_height) / 1000.0; // km
+    if rain_height <= 0.0 || rain.rain_rate == 0.0 {
+        return Ok(0.0);
+    }
+
+    let sin_el = elevation.sin();
+    let elevation_deg = elevation.to_degrees();
+    let latitude_deg = rain.station_latitude.to_degrees().abs();
+
+    // Slant path below the rain height and its horizontal projection, km
+    let slant = if elevation_deg >= 5.0 {
+        rain_height / sin_el
+    } else {
+        2.0 * rain_height / ((sin_el * sin_el + 2.0 * rain_height / EFFECTIVE_EARTH_RADIUS_KM).sqrt() + sin_el)
+    };
+    let horizontal = slant * elevation.cos();
+
+    let coefficients = rain_coefficients(frequency, elevation, rain.polarization_tilt)?;
+    let specific = coefficients.k * rain.rain_rate.powf(coefficients.alpha); // dB/km
+
+    let horizontal_reduction =
+        1.0 / (1.0 + 0.78 * (horizontal * specific / f).sqrt() - 0.38 * (1.0 - (-2.0 * horizontal).exp()));
+    let zeta = (rain_height / (horizontal * horizontal_reduction)).atan();
+    let adjusted = if zeta > elevation {
+        horizontal * horizontal_reduction / elevation.cos()
+    } else {
+        rain_height / sin_el
+    };
+    let chi = if latitude_deg < 36.0 { 36.0 - latitude_deg } else { 0.0 };
+    let vertical_adjustment = 1.0
+        / (1.0
+            + sin_el.sqrt()
+                * (31.0 * (1.0 - (-elevation_deg / (1.0 + chi)).exp()) * (adjusted * specific).sqrt() / (f * f)
+                    - 0.45));
+    let effective_length = adjusted * vertical_adjustment;
+    let a001 = specific * effective_length;
+
+    let beta = if p >= 1.0 || latitude_deg >= 36.0 {
+        0.0
+    } else if elevation_deg >= 25.0 {
+        -0.005 * (latitude_deg - 36.0)
+    } else {
+        -0.005 * (latitude_deg - 36.0) + 1.8 - 4.25 * sin_el
+    };
+    let exponent = 0.655 + 0.033 * p.ln() - 0.045 * a001.ln() - beta * (1.0 - p) * sin_el;
+    Ok(a001 * (p / 0.01).powf(-exponent))
+}
+
+// Receiving antenna and local refractivity for P.618 scintillation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScintillationParameters {
+    pub antenna_diameter: f64,   // m
+    pub antenna_efficiency: f64, // aperture efficiency, 0.5 when unknown
+    pub wet_refractivity: f64,   // N-units, wet term of surface refractivity (P.453)
+}
+
+// Tropospheric scintillation fade depth exceeded for (100 - availability)%
+// of the time, for availabilities of 50% to 99.99%
+pub fn scintillation_fade(
+    frequency: f64,
+    elevation: f64,
+    availability: f64,
+    scintillation: &ScintillationParameters,
+) -> Result<f64, CalculationError> {
+    check_elevation(elevation)?;
+    let p = exceedance(availability, 0.01, 50.0)?;
+    if scintillation.antenna_diameter <= 0.0
+        || !(0.0..=1.0).contains(&scintillation.antenna_efficiency)
+        || scintillation.wet_refractivity < 0.0
+    {
+        return Err(CalculationError::InvalidInput);
+    }
+    let f = frequency / 1e9;
+    let sin_el = elevation.sin();
+
+    let sigma_ref = 3.6e-3 + 1.0e-4 * scintillation.wet_refractivity;
+    // Effective path length through a turbulent layer 1000 m thick, m
+    let path = 2.0 * 1000.0 / ((sin_el * sin_el + 2.35e-4).sqrt() + sin_el);
+    let effective_diameter = scintillation.antenna_efficiency.sqrt() * scintillation.antenna_diameter;
+    let x = 1.22 * effective_diameter * effective_diameter * f / path;
+    // Antenna averaging factor; a large aperture averages the scintillation out
+    let averaging =
+        3.86 * (x * x + 1.0).powf(11.0 / 12.0) * (11.0 / 6.0 * (1.0 / x).atan()).sin() - 7.08 * x.powf(5.0 / 6.0);
+    if averaging <= 0.0 {
+        return Ok(0.0);
+    }
+    let sigma = sigma_ref * f.powf(7.0 / 12.0) * averaging.sqrt() / sin_el.powf(1.2);
+
+    let log_p = p.log10();
+    let time_percentage_factor = -0.061 * log_p.powi(3) + 0.072 * log_p.powi(2) - 1.71 * log_p + 3.0;
+    Ok(time_percentage_factor * sigma)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphericLosses {
+    pub gaseous: f64,       // dB
+    pub rain: f64,          // dB
+    pub scintillation: f64, // dB
+}
+
+impl AtmosphericLosses {
+    // Gases add directly; rain and scintillation fades combine in quadrature
+    // (P.618 section 2.5, without the cloud term)
+    pub fn total(&self) -> f64 {
+        self.gaseous + (self.rain * self.rain + self.scintillation * self.scintillation).sqrt()
+    }
+}
+
+// All atmospheric contributions on an Earth-space path at one availability
+pub fn atmospheric_losses(
+    frequency: f64,
+    elevation: f64,
+    availability: f64,
+    atmosphere: &Atmosphere,
+    rain: &RainParameters,
+    scintillation: &ScintillationParameters,
+) -> Result<AtmosphericLosses, CalculationError> {
+    Ok(AtmosphericLosses {
+        gaseous: gaseous_attenuation(frequency, elevation, atmosphere)?,
+        rain: rain_attenuation(frequency, elevation, availability, rain)?,
+        scintillation: scintillation_fade(frequency, elevation, availability, scintillation)?,
+    })
+}
+
+fn check_elevation(elevation: f64) -> Result<(), CalculationError> {
+    if !(5.0_f64.to_radians()..=PI / 2.0).contains(&elevation) {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(())
+}
+
+// Percentage of time a fade is exceeded, from a link availability in %,
+// clamped onto the valid range to absorb rounding in 100 - availability
+fn exceedance(availability: f64, min: f64, max: f64) -> Result<f64, CalculationError> {
+    let p = 100.0 - availability;
+    if p < min * (1.0 - 1e-9) || p > max * (1.0 + 1e-9) {
+        return Err(CalculationError::OutOfRange);
+    }
+    Ok(p.clamp(min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GHZ: f64 = 1e9;
+
+    fn rain() -> RainParameters {
+        RainParameters {
+            rain_rate: 42.0,
+            rain_height: 3500.0,
+            station_height: 50.0,
+            station_latitude: 40.0_f64.to_radians(),
+            polarization_tilt: PI / 4.0,
+        }
+    }
+
+    fn dish() -> ScintillationParameters {
+        ScintillationParameters { antenna_diameter: 1.2, antenna_efficiency: 0.6, wet_refractivity: 42.0 }
+    }
+
+    #[test]
+    fn free_space_matches_the_shortcut() {
+        // 20 log10(1000) + 20 log10(1000) + 32.45
+        let loss = free_space_path_loss_km_mhz(1000.0, 1000.0).unwrap();
+        assert!((loss - 152.45).abs() < 0.01, "{loss} dB");
+        let doubled = free_space_path_loss(2.0e6, 1.0 * GHZ).unwrap();
+        assert!((doubled - loss - 20.0 * 2.0_f64.log10()).abs() < 1e-9);
+        assert_eq!(free_space_path_loss(0.0, GHZ), Err(CalculationError::InvalidInput));
+        assert_eq!(free_space_path_loss(1000.0, -GHZ), Err(CalculationError::InvalidInput));
+    }
+
+    #[test]
+    fn rain_coefficients_match_the_p838_table() {
+        // (GHz, kH, alphaH, kV, alphaV) from table 5 of P.838-3
+        let table = [
+            (10.0, 0.01217, 1.2571, 0.01129, 1.2156),
+            (20.0, 0.09164, 1.0568, 0.09611, 0.9847),
+            (30.0, 0.2403, 0.9485, 0.2291, 0.9129),
+        ];
+        for (f, k_h, alpha_h, k_v, alpha_v) in table {
+            let h = rain_coefficients(f * GHZ, 0.0, 0.0).unwrap();
+            let v = rain_coefficients(f * GHZ, 0.0, PI / 2.0).unwrap();
+            assert!((h.k - k_h).abs() < 1e-4 * k_h.max(1.0) && (h.alpha - alpha_h).abs() < 1e-4, "{f} GHz: {h:?}");
+            assert!((v.k - k_v).abs() < 1e-4 * k_v.max(1.0) && (v.alpha - alpha_v).abs() < 1e-4, "{f} GHz: {v:?}");
+        }
+        assert_eq!(rain_coefficients(0.5 * GHZ, 0.0, 0.0), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn circular_polarization_averages_the_coefficients() {
+        let h = rain_coefficients(20.0 * GHZ, 0.0, 0.0).unwrap();
+        let v = rain_coefficients(20.0 * GHZ, 0.0, PI / 2.0).unwrap();
+        let c = rain_coefficients(20.0 * GHZ, 0.0, PI / 4.0).unwrap();
+        assert!((c.k - (h.k + v.k) / 2.0).abs() < 1e-12);
+        assert!((c.alpha - (h.k * h.alpha + v.k * v.alpha) / (2.0 * c.k)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gaseous_absorption_matches_the_p676_curves() {
+        let atmosphere = Atmosphere::standard();
+        // Oxygen near 0.008 dB/km at 10 GHz; water vapour near 0.18 dB/km on
+        // the 22.235 GHz line for 7.5 g/m^3
+        let (oxygen, _) = gaseous_specific_attenuation(10.0 * GHZ, &atmosphere).unwrap();
+        assert!((oxygen - 0.008).abs() < 0.001, "{oxygen} dB/km");
+        let (_, water) = gaseous_specific_attenuation(22.235 * GHZ, &atmosphere).unwrap();
+        assert!((water - 0.18).abs() < 0.02, "{water} dB/km");
+        let (_, off_line) = gaseous_specific_attenuation(15.0 * GHZ, &atmosphere).unwrap();
+        assert!(off_line < water / 3.0);
+
+        let dry = Atmosphere { water_vapour_density: 0.0, ..atmosphere };
+        assert_eq!(gaseous_specific_attenuation(22.235 * GHZ, &dry).unwrap().1, 0.0);
+        assert_eq!(gaseous_specific_attenuation(60.0 * GHZ, &atmosphere), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn gaseous_attenuation_scales_with_the_cosecant() {
+        let atmosphere = Atmosphere::standard();
+        let zenith = gaseous_attenuation(12.0 * GHZ, PI / 2.0, &atmosphere).unwrap();
+        assert!((0.04..0.08).contains(&zenith), "{zenith} dB at zenith");
+        let low = gaseous_attenuation(12.0 * GHZ, 30.0_f64.to_radians(), &atmosphere).unwrap();
+        assert!((low - 2.0 * zenith).abs() < 1e-12);
+        assert_eq!(gaseous_attenuation(12.0 * GHZ, 1.0_f64.to_radians(), &atmosphere), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn rain_fades_deepen_with_availability_and_frequency() {
+        let elevation = 30.0_f64.to_radians();
+        let mut previous = 0.0;
+        for availability in [99.0, 99.9, 99.99, 99.999] {
+            let fade = rain_attenuation(20.0 * GHZ, elevation, availability, &rain()).unwrap();
+            assert!(fade > previous, "{fade} dB at {availability}%");
+            previous = fade;
+        }
+        let ku = rain_attenuation(12.0 * GHZ, elevation, 99.99, &rain()).unwrap();
+        let ka = rain_attenuation(30.0 * GHZ, elevation, 99.99, &rain()).unwrap();
+        assert!(ka > 2.0 * ku, "Ku {ku} dB, Ka {ka} dB");
+        // Tens of dB at Ka band for 0.01% in a temperate climate
+        assert!((10.0..60.0).contains(&ka), "{ka} dB");
+    }
+
+    #[test]
+    fn no_rain_above_the_rain_height() {
+        let elevation = 30.0_f64.to_radians();
+        let dry = RainParameters { rain_rate: 0.0, ..rain() };
+        assert_eq!(rain_attenuation(20.0 * GHZ, elevation, 99.99, &dry), Ok(0.0));
+        let mountain = RainParameters { station_height: 4000.0, ..rain() };
+        assert_eq!(rain_attenuation(20.0 * GHZ, elevation, 99.99, &mountain), Ok(0.0));
+        let negative = RainParameters { rain_rate: -1.0, ..rain() };
+        assert_eq!(rain_attenuation(20.0 * GHZ, elevation, 99.99, &negative), Err(CalculationError::NegativeValue));
+        assert_eq!(rain_attenuation(20.0 * GHZ, elevation, 90.0, &rain()), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn scintillation_grows_at_low_elevation_and_averages_out_on_large_dishes() {
+        let high = scintillation_fade(20.0 * GHZ, 60.0_f64.to_radians(), 99.9, &dish()).unwrap();
+        let low = scintillation_fade(20.0 * GHZ, 10.0_f64.to_radians(), 99.9, &dish()).unwrap();
+        assert!(high > 0.0 && low > high, "{high} dB at 60 deg, {low} dB at 10 deg");
+        let large = ScintillationParameters { antenna_diameter: 30.0, ..dish() };
+        let averaged = scintillation_fade(20.0 * GHZ, 10.0_f64.to_radians(), 99.9, &large).unwrap();
+        assert!(averaged < low);
+        let bad = ScintillationParameters { antenna_efficiency: 1.5, ..dish() };
+        assert_eq!(scintillation_fade(20.0 * GHZ, 0.5, 99.9, &bad), Err(CalculationError::InvalidInput));
+    }
+
+    #[test]
+    fn fades_combine_in_quadrature() {
+        let losses = AtmosphericLosses { gaseous: 1.0, rain: 3.0, scintillation: 4.0 };
+        assert!((losses.total() - 6.0).abs() < 1e-12);
+        let elevation = 30.0_f64.to_radians();
+        let all =
+            atmospheric_losses(20.0 * GHZ, elevation, 99.9, &Atmosphere::standard(), &rain(), &dish()).unwrap();
+        assert_eq!(all.rain, rain_attenuation(20.0 * GHZ, elevation, 99.9, &rain()).unwrap());
+        assert!(all.total() > all.gaseous + all.rain.max(all.scintillation) - 1e-12);
+    }
+}
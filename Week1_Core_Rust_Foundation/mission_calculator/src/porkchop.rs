@@ -0,0 +1,126 @@
+// Porkchop plot data for interplanetary transfers
+//
+// Sweeps departure and arrival dates, solves the direct (zero revolution,
+// prograde) Lambert transfer between the two planets for every pair and
+// records the launch energy C3 and the arrival hyperbolic excess speed.
+use crate::CalculationError;
+use crate::body::CentralBody;
+use crate::ephemeris::{Planet, planet_state};
+use crate::time::{Epoch, TimeScale};
+use crate::lambert::solve_lambert;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+    pub start: f64, // Julian date
+    pub end: f64,   // Julian date
+    pub step: f64,  // days
+}
+
+impl DateRange {
+    pub fn new(start: f64, end: f64, step: f64) -> Result<Self, CalculationError> {
+        if step <= 0.0 || end < start {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(DateRange { start, end, step })
+    }
+
+    fn dates(&self) -> impl Iterator<Item = f64> + '_ {
+        let count = ((self.end - self.start) / self.step).floor() as usize;
+        (0..=count).map(move |k| self.start + self.step * k as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PorkchopPoint {
+    pub departure: f64,      // Julian date
+    pub arrival: f64,        // Julian date
+    pub c3: f64,             // km^2/s^2, NaN when no transfer was found
+    pub arrival_v_inf: f64,  // km/s, NaN when no transfer was found
+}
+
+impl PorkchopPoint {
+    pub fn time_of_flight_days(&self) -> f64 {
+        self.arrival - self.departure
+    }
+}
+
+// Evaluate every departure/arrival pair with a positive time of flight
+pub fn porkchop_grid(
+    from: Planet,
+    to: Planet,
+    departures: DateRange,
+    arrivals: DateRange,
+) -> Result<Vec<PorkchopPoint>, CalculationError> {
+    if from == to {
+        return Err(CalculationError::InvalidInput);
+    }
+    let mu_sun = CentralBody::sun().mu;
+    let mut grid = Vec::new();
+
+    // Cache arrival states, they are reused for every departure date
+    let arrival_states: Vec<(f64, _)> =
+        arrivals.dates().map(|jd| planet_state(to, jd).map(|s| (jd, s))).collect::<Result<_, _>>()?;
+
+    for departure in departures.dates() {
+        let departure_state = planet_state(from, departure)?;
+        for (arrival, arrival_state) in &arrival_states {
+            let tof_days = arrival - departure;
+            if tof_days <= 0.0 {
+                continue;
+            }
+            let transfer = solve_lambert(
+                departure_state.position,
+                arrival_state.position,
+                tof_days * 86400.0,
+                mu_sun,
+                0,
+                true,
+                true,
+            );
+            let (c3, arrival_v_inf) = match transfer {
+                Ok(solution) => {
+                    let v_inf_out = (solution.departure_velocity - departure_state.velocity).norm() / 1000.0;
+                    let v_inf_in = (solution.arrival_velocity - arrival_state.velocity).norm() / 1000.0;
+                    (v_inf_out * v_inf_out, v_inf_in)
+                }
+                Err(_) => (f64::NAN, f64::NAN),
+            };
+            grid.push(PorkchopPoint { departure, arrival: *arrival, c3, arrival_v_inf });
+        }
+    }
+    Ok(grid)
+}
+
+// Lowest-C3 transfer in a grid
+pub fn minimum_c3(grid: &[PorkchopPoint]) -> Option<&PorkchopPoint> {
+    grid.iter().filter(|p| p.c3.is_finite()).min_by(|a, b| a.c3.total_cmp(&b.c3))
+}
+
+// CSV with one row per grid point; failed transfers leave c3 and v_inf empty
+pub fn porkchop_to_csv(grid: &[PorkchopPoint]) -> String {
+    let mut csv =
+        String::from("departure_date,departure_jd,arrival_date,arrival_jd,tof_days,c3_km2_s2,arrival_v_inf_km_s\n");
+    for point in grid {
+        let optional = |v: f64| if v.is_finite() { format!("{:.6}", v) } else { String::new() };
+        let date = |julian_date: f64| Epoch::from_julian_date(julian_date, TimeScale::Utc).to_date(TimeScale::Utc);
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            date(point.departure),
+            point.departure,
+            date(point.arrival),
+            point.arrival,
+            point.time_of_flight_days(),
+            optional(point.c3),
+            optional(point.arrival_v_inf)
+        );
+    }
+    csv
+}
+
+pub fn write_porkchop_csv<P: AsRef<Path>>(grid: &[PorkchopPoint], path: P) -> std::io::Result<()> {
+    fs::write(path, porkchop_to_csv(grid))
+}
@@ -0,0 +1,297 @@
+// Spacecraft power budget, solar array and battery sizing
+//
+// Follows the sizing method of Space Mission Analysis and Design (Wertz &
+// Larson, section 11.4). The array must power the sunlit loads and recharge
+// the battery for the eclipse within the sunlit part of every orbit:
+//
+//     P_sa = (Pe Te / Xe + Pd Td / Xd) / Td
+//
+// where Xe and Xd are the efficiencies of the paths from the array through
+// the battery to the loads and directly to the loads. The array output per
+// unit area falls with cell efficiency, inherent losses, the Sun incidence
+// angle and yearly degradation, and the array is sized at end of life. The
+// battery delivers the eclipse energy without exceeding the depth of
+// discharge limit.
+//
+// Powers are in W, energies in J, times in s and areas in m^2.
+use crate::CalculationError;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::Path;
+
+pub const SOLAR_CONSTANT: f64 = 1361.0; // W/m^2 at 1 AU, mean total solar irradiance
+pub const TYPICAL_INHERENT_DEGRADATION: f64 = 0.77;
+pub const TYPICAL_BATTERY_EFFICIENCY: f64 = 0.9;
+
+// How the array is regulated, which sets the path efficiencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Regulation {
+    DirectEnergyTransfer,
+    PeakPowerTracking,
+}
+
+impl Regulation {
+    // Efficiency from the array through the battery to the loads (eclipse)
+    pub fn eclipse_efficiency(&self) -> f64 {
+        match self {
+            Regulation::DirectEnergyTransfer => 0.65,
+            Regulation::PeakPowerTracking => 0.60,
+        }
+    }
+
+    // Efficiency from the array straight to the loads (daylight)
+    pub fn daylight_efficiency(&self) -> f64 {
+        match self {
+            Regulation::DirectEnergyTransfer => 0.85,
+            Regulation::PeakPowerTracking => 0.80,
+        }
+    }
+}
+
+impl fmt::Display for Regulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Regulation::DirectEnergyTransfer => write!(f, "direct energy transfer"),
+            Regulation::PeakPowerTracking => write!(f, "peak power tracking"),
+        }
+    }
+}
+
+// An operating mode with its power draw in sunlight and in eclipse and the
+// fraction of the time it is active
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadMode {
+    pub name: String,
+    pub sunlit_power: f64,  // W
+    pub eclipse_power: f64, // W
+    pub duty_cycle: f64,    // 0 to 1
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerBudgetInputs {
+    pub modes: Vec<LoadMode>,
+    pub orbit_period: f64,     // s
+    pub eclipse_fraction: f64, // fraction of the orbit in shadow
+    pub regulation: Regulation,
+    pub cell_efficiency: f64,      // 0 to 1
+    pub inherent_degradation: f64, // 0 to 1, design and assembly losses
+    pub incidence_angle: f64,      // rad, worst-case Sun angle off the array normal
+    pub degradation_per_year: f64, // 0 to 1
+    pub mission_years: f64,
+    pub depth_of_discharge: f64, // 0 to 1, allowed battery depth of discharge
+    pub battery_efficiency: f64, // 0 to 1, battery to load transfer efficiency
+}
+
+impl PowerBudgetInputs {
+    // Duty-weighted loads in sunlight and eclipse, W
+    pub fn average_loads(&self) -> (f64, f64) {
+        let sunlit = self.modes.iter().map(|m| m.sunlit_power * m.duty_cycle).sum();
+        let eclipse = self.modes.iter().map(|m| m.eclipse_power * m.duty_cycle).sum();
+        (sunlit, eclipse)
+    }
+
+    fn validate(&self) -> Result<(), CalculationError> {
+        let fraction = |x: f64| (0.0..=1.0).contains(&x);
+        if self.modes.iter().any(|m| m.sunlit_power < 0.0 || m.eclipse_power < 0.0) {
+            return Err(CalculationError::NegativeValue);
+        }
+        if self.modes.iter().any(|m| !fraction(m.duty_cycle))
+            || !(0.0..1.0).contains(&self.eclipse_fraction)
+            || !fraction(self.degradation_per_year)
+            || self.incidence_angle.abs() >= std::f64::consts::FRAC_PI_2
+        {
+            return Err(CalculationError::OutOfRange);
+        }
+        let positive_fractions =
+            [self.cell_efficiency, self.inherent_degradation, self.depth_of_discharge, self.battery_efficiency];
+        if positive_fractions.iter().any(|&x| x <= 0.0 || x > 1.0) {
+            return Err(CalculationError::OutOfRange);
+        }
+        if self.orbit_period <= 0.0 || self.mission_years < 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerBudget {
+    pub sunlit_load: f64,          // W
+    pub eclipse_load: f64,         // W
+    pub daylight_time: f64,        // s per orbit
+    pub eclipse_time: f64,         // s per orbit
+    pub required_array_power: f64, // W at end of life
+    pub bol_power_density: f64,    // W/m^2
+    pub eol_power_density: f64,    // W/m^2
+    pub lifetime_degradation: f64, // fraction of the BOL output left at EOL
+    pub array_area: f64,           // m^2
+    pub bol_array_power: f64,      // W
+    pub eclipse_energy: f64,       // J drawn by the loads per eclipse
+    pub battery_capacity: f64,     // J
+}
+
+impl fmt::Display for PowerBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Average sunlit load:        {:>10.1} W", self.sunlit_load)?;
+        writeln!(f, "Average eclipse load:       {:>10.1} W", self.eclipse_load)?;
+        writeln!(
+            f,
+            "Daylight / eclipse time:    {:>7.1} / {:.1} min",
+            self.daylight_time / 60.0,
+            self.eclipse_time / 60.0
+        )?;
+        writeln!(f, "Required array power (EOL): {:>10.1} W", self.required_array_power)?;
+        writeln!(
+            f,
+            "Power density BOL / EOL:    {:>7.1} / {:.1} W/m^2",
+            self.bol_power_density, self.eol_power_density
+        )?;
+        writeln!(f, "Lifetime degradation:       {:>10.3}", self.lifetime_degradation)?;
+        writeln!(f, "Solar array area:           {:>10.3} m^2", self.array_area)?;
+        writeln!(f, "Array power at BOL:         {:>10.1} W", self.bol_array_power)?;
+        writeln!(f, "Eclipse energy:             {:>10.1} Wh", self.eclipse_energy / 3600.0)?;
+        if self.battery_capacity > 0.0 {
+            write!(f, "Battery capacity:           {:>10.1} Wh", self.battery_capacity / 3600.0)
+        } else {
+            write!(f, "Battery capacity:           {:>10} (no eclipse load)", "none")
+        }
+    }
+}
+
+// Array output per unit area after `years` in orbit, W/m^2
+fn power_density(inputs: &PowerBudgetInputs, years: f64) -> f64 {
+    SOLAR_CONSTANT
+        * inputs.cell_efficiency
+        * inputs.inherent_degradation
+        * inputs.incidence_angle.cos()
+        * (1.0 - inputs.degradation_per_year).powf(years)
+}
+
+pub fn size_power_system(inputs: &PowerBudgetInputs) -> Result<PowerBudget, CalculationError> {
+    inputs.validate()?;
+    let (sunlit_load, eclipse_load) = inputs.average_loads();
+    let eclipse_time = inputs.orbit_period * inputs.eclipse_fraction;
+    let daylight_time = inputs.orbit_period - eclipse_time;
+
+    let regulation = inputs.regulation;
+    let required_array_power = (eclipse_load * eclipse_time / regulation.eclipse_efficiency()
+        + sunlit_load * daylight_time / regulation.daylight_efficiency())
+        / daylight_time;
+    let bol_power_density = power_density(inputs, 0.0);
+    let eol_power_density = power_density(inputs, inputs.mission_years);
+    if eol_power_density <= 0.0 {
+        return Err(CalculationError::DivisionByZero);
+    }
+    let array_area = required_array_power / eol_power_density;
+
+    let eclipse_energy = eclipse_load * eclipse_time;
+    Ok(PowerBudget {
+        sunlit_load,
+        eclipse_load,
+        daylight_time,
+        eclipse_time,
+        required_array_power,
+        bol_power_density,
+        eol_power_density,
+        lifetime_degradation: eol_power_density / bol_power_density,
+        array_area,
+        bol_array_power: array_area * bol_power_density,
+        eclipse_energy,
+        battery_capacity: eclipse_energy / (inputs.depth_of_discharge * inputs.battery_efficiency),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryPoint {
+    pub time: f64, // s since the start
+    pub sunlit: bool,
+    pub array_power: f64,     // W
+    pub load: f64,            // W
+    pub state_of_charge: f64, // 0 to 1
+}
+
+// Battery state of charge over `orbits` revolutions, starting full, with the
+// array degraded to `years` into the mission. Each orbit is daylight then
+// eclipse. In daylight the array feeds the loads and whatever is left,
+// through the battery charge path, recharges the battery; in eclipse the
+// battery carries the loads. The charge is clamped to the capacity and
+// may go below zero if the system is undersized, which shows as a negative
+// state of charge.
+pub fn simulate_battery(
+    inputs: &PowerBudgetInputs,
+    budget: &PowerBudget,
+    years: f64,
+    orbits: usize,
+    step: f64,
+) -> Result<Vec<BatteryPoint>, CalculationError> {
+    inputs.validate()?;
+    if step <= 0.0 || years < 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let array_power = budget.array_area * power_density(inputs, years);
+    let regulation = inputs.regulation;
+    let capacity = budget.battery_capacity;
+    // With nothing to carry through eclipse no battery is sized; report it as
+    // full while the array covers the loads and empty once it falls short
+    let state_of_charge = |charge: f64| {
+        if capacity > 0.0 {
+            charge / capacity
+        } else if charge < 0.0 {
+            0.0
+        } else {
+            1.0
+        }
+    };
+    let mut charge = capacity;
+    let mut points = Vec::new();
+
+    for orbit in 0..orbits {
+        let orbit_start = orbit as f64 * inputs.orbit_period;
+        let mut t = 0.0;
+        while t < inputs.orbit_period {
+            let sunlit = t < budget.daylight_time;
+            let (power, load) = if sunlit { (array_power, budget.sunlit_load) } else { (0.0, budget.eclipse_load) };
+            points.push(BatteryPoint {
+                time: orbit_start + t,
+                sunlit,
+                array_power: power,
+                load,
+                state_of_charge: state_of_charge(charge),
+            });
+
+            // Stop each step at the daylight/eclipse switch and the end of the orbit
+            let boundary = if sunlit { budget.daylight_time } else { inputs.orbit_period };
+            let dt = step.min(boundary - t);
+            // Power reaching the loads straight from the array
+            let direct = power * regulation.daylight_efficiency();
+            let rate = if direct >= load {
+                (power - load / regulation.daylight_efficiency()) * regulation.eclipse_efficiency()
+                    / inputs.battery_efficiency
+            } else {
+                -(load - direct) / inputs.battery_efficiency
+            };
+            charge = (charge + rate * dt).min(capacity);
+            t += dt;
+        }
+    }
+    points.push(BatteryPoint {
+        time: orbits as f64 * inputs.orbit_period,
+        sunlit: true,
+        array_power,
+        load: budget.sunlit_load,
+        state_of_charge: state_of_charge(charge),
+    });
+    Ok(points)
+}
+
+pub fn battery_to_csv(points: &[BatteryPoint]) -> String {
+    let mut csv = String::from("time_s,sunlit,array_power_w,load_w,state_of_charge\n");
+    for p in points {
+        let _ = writeln!(csv, "{},{},{:.3},{:.3},{:.6}", p.time, p.sunlit, p.array_power, p.load, p.state_of_charge);
+    }
+    csv
+}
+
+pub fn write_battery_csv<P: AsRef<Path>>(points: &[BatteryPoint], path: P) -> std::io::Result<()> {
+    fs::write(path, battery_to_csv(points))
+}
@@ -0,0 +1,362 @@
+// Two-body (Keplerian) propagation with universal variables
+//
+// One formulation covers elliptic, parabolic and hyperbolic orbits, forward
+// and backward in time (Vallado, algorithm 8 "KEPLER"; Curtis, section 3.7).
+// The universal Kepler equation is solved with Newton iterations safeguarded
+// by bisection, so the solver always converges even from a poor first guess.
+use crate::CalculationError;
+use crate::elements::{OrbitalElements, PARABOLIC_TOLERANCE, StateVector, eccentric_to_true_anomaly, wrap_pi};
+use crate::time::{Epoch, TimeScale};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const MAX_ITERATIONS: usize = 200;
+const TOLERANCE: f64 = 1e-12;
+
+// Stumpff functions C(z) and S(z), with series expansions near z = 0
+pub fn stumpff_c(z: f64) -> f64 {
+    if z > 1e-6 {
+        (1.0 - z.sqrt().cos()) / z
+    } else if z < -1e-6 {
+        ((-z).sqrt().cosh() - 1.0) / -z
+    } else {
+        1.0 / 2.0 - z / 24.0 + z * z / 720.0
+    }
+}
+
+pub fn stumpff_s(z: f64) -> f64 {
+    if z > 1e-6 {
+        let sz = z.sqrt();
+        (sz - sz.sin()) / sz.powi(3)
+    } else if z < -1e-6 {
+        let sz = (-z).sqrt();
+        (sz.sinh() - sz) / sz.powi(3)
+    } else {
+        1.0 / 6.0 - z / 120.0 + z * z / 5040.0
+    }
+}
+
+// Propagate a state vector by `dt` seconds (negative dt goes backwards)
+pub fn propagate_state(state: &StateVector, mu: f64, dt: f64) -> Result<StateVector, CalculationError> {
+    let r0 = state.position;
+    let v0 = state.velocity;
+    let r0_mag = r0.norm();
+    if mu <= 0.0 || r0_mag == 0.0 || !dt.is_finite() {
+        return Err(CalculationError::InvalidInput);
+    }
+    if dt == 0.0 {
+        return Ok(*state);
+    }
+
+    let sqrt_mu = mu.sqrt();
+    let v0_sq = v0.dot(v0);
+    let rv = r0.dot(v0) / sqrt_mu;
+    // Reciprocal of the semi-major axis; zero for a parabola
+    let alpha = 2.0 / r0_mag - v0_sq / mu;
+
+    // Whole revolutions of a closed orbit bring the state back unchanged;
+    // dropping them keeps chi small and the solution accurate
+    let mut dt = dt;
+    if alpha > 1e-12 {
+        let period = 2.0 * std::f64::consts::PI / (alpha.powi(3) * mu).sqrt();
+        dt %= period;
+        if dt == 0.0 {
+            return Ok(*state);
+        }
+    }
+
+    let chi = solve_universal_kepler(r0_mag, rv, alpha, sqrt_mu * dt)?;
+
+    let z = alpha * chi * chi;
+    let c = stumpff_c(z);
+    let s = stumpff_s(z);
+
+    // Lagrange coefficients
+    let f = 1.0 - chi * chi / r0_mag * c;
+    let g = dt - chi.powi(3) / sqrt_mu * s;
+    let r = r0 * f + v0 * g;
+    let r_mag = r.norm();
+    let f_dot = sqrt_mu / (r_mag * r0_mag) * chi * (z * s - 1.0);
+    let g_dot = 1.0 - chi * chi / r_mag * c;
+    let v = r0 * f_dot + v0 * g_dot;
+
+    Ok(StateVector::new(r, v))
+}
+
+// Propagate an element set by `dt` seconds
+pub fn propagate_elements(elements: &OrbitalElements, mu: f64, dt: f64) -> Result<OrbitalElements, CalculationError> {
+    let state = elements.to_state(mu)?;
+    let state = propagate_state(&state, mu, dt)?;
+    OrbitalElements::from_state(&state, mu)
+}
+
+// Solve the universal Kepler equation for the universal anomaly chi:
+//
+//     sqrt(mu) dt = rv chi^2 C(z) + (1 - alpha r0) chi^3 S(z) + r0 chi,   z = alpha chi^2
+//
+// where rv = r0 . v0 / sqrt(mu). The left side increases monotonically with
+// chi, which lets the Newton step fall back to bisection inside a bracket.
+fn solve_universal_kepler(r0: f64, rv: f64, alpha: f64, sqrt_mu_dt: f64) -> Result<f64, CalculationError> {
+    let time_of = |chi: f64| {
+        let z = alpha * chi * chi;
+        rv * chi * chi * stumpff_c(z) + (1.0 - alpha * r0) * chi.powi(3) * stumpff_s(z) + r0 * chi
+    };
+    // d(time)/d(chi) is the radius r(chi)
+    let radius_of = |chi: f64| {
+        let z = alpha * chi * chi;
+        chi * chi * stumpff_c(z) + rv * chi * (1.0 - z * stumpff_s(z)) + r0 * (1.0 - z * stumpff_c(z))
+    };
+
+    // Bracket the root: chi has the sign of dt. For very open orbits the
+    // Stumpff functions overflow long before chi does, so a non-finite time
+    // is treated as lying beyond the root.
+    let beyond = |chi: f64| {
+        let t = time_of(chi);
+        !t.is_finite() || (t - sqrt_mu_dt) * sqrt_mu_dt.signum() >= 0.0
+    };
+    let mut bound = 1.0;
+    let mut expansions = 0;
+    while !beyond(bound * sqrt_mu_dt.signum()) {
+        bound *= 2.0;
+        expansions += 1;
+        if expansions > 1000 {
+            return Err(CalculationError::OutOfRange);
+        }
+    }
+    let (low, high) = if sqrt_mu_dt > 0.0 { (0.0, bound) } else { (-bound, 0.0) };
+
+    // Initial guess (Vallado): elliptic orbits scale linearly with time,
+    // open orbits start from the radius-based estimate
+    let guess = if alpha > 1e-12 { sqrt_mu_dt * alpha } else { sqrt_mu_dt / r0 };
+    newton_bisect(|chi| time_of(chi) - sqrt_mu_dt, radius_of, low, high, guess)
+}
+
+// Solve Kepler's equation M = E - e sin E (elliptic) or M = e sinh F - F
+// (hyperbolic) for the eccentric/hyperbolic anomaly. Parabolic orbits use
+// Barker's equation M = D + D^3 / 3, which has a closed-form solution.
+pub fn solve_kepler(mean_anomaly: f64, eccentricity: f64) -> Result<f64, CalculationError> {
+    let e = eccentricity;
+    let m = mean_anomaly;
+    if e < 0.0 || !m.is_finite() {
+        return Err(CalculationError::InvalidInput);
+    }
+
+    if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+        let b = 1.5 * m;
+        let y = (b + (b * b + 1.0).sqrt()).cbrt();
+        return Ok(y - 1.0 / y);
+    }
+
+    if e < 1.0 {
+        // Solve on (-pi, pi] and add back the whole revolutions
+        let m_wrapped = wrap_pi(m);
+        let guess = if e > 0.8 { std::f64::consts::PI.copysign(m_wrapped) } else { m_wrapped };
+        let anomaly = newton_bisect(
+            |x| x - e * x.sin() - m_wrapped,
+            |x| 1.0 - e * x.cos(),
+            m_wrapped - e - 1e-12,
+            m_wrapped + e + 1e-12,
+            guess,
+        )?;
+        Ok(anomaly + (m - m_wrapped))
+    } else {
+        let guess = (2.0 * m.abs() / e + 1.8).ln().copysign(m);
+        let bound = 2.0 * (m.abs() / e + 1.0).asinh() + 1.0;
+        newton_bisect(|x| e * x.sinh() - x - m, |x| e * x.cosh() - 1.0, -bound, bound, guess)
+    }
+}
+
+// Newton iteration for an increasing function, falling back to bisection
+// whenever a step would leave the bracket [low, high]
+fn newton_bisect<F, D>(f: F, df: D, mut low: f64, mut high: f64, guess: f64) -> Result<f64, CalculationError>
+where
+    F: Fn(f64) -> f64,
+    D: Fn(f64) -> f64,
+{
+    let mut x = if guess > low && guess < high { guess } else { 0.5 * (low + high) };
+    for _ in 0..MAX_ITERATIONS {
+        let residual = f(x);
+        if !residual.is_finite() || residual > 0.0 {
+            high = x;
+        } else {
+            low = x;
+        }
+        let mut next = x - residual / df(x);
+        if !next.is_finite() || next <= low || next >= high {
+            next = 0.5 * (low + high);
+        }
+        if (next - x).abs() <= TOLERANCE * (1.0 + x.abs()) {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(CalculationError::OutOfRange)
+}
+
+// True anomaly for a given mean anomaly, for any conic
+pub fn mean_to_true_anomaly(mean_anomaly: f64, eccentricity: f64) -> Result<f64, CalculationError> {
+    Ok(eccentric_to_true_anomaly(solve_kepler(mean_anomaly, eccentricity)?, eccentricity))
+}
+
+// A propagated state at a time offset from the initial epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EphemerisPoint {
+    pub time: f64, // s since the initial state
+    pub state: StateVector,
+}
+
+// Evenly spaced time series from `start` to `end` (either order) with `step` spacing
+pub fn propagate_series(
+    state: &StateVector,
+    mu: f64,
+    start: f64,
+    end: f64,
+    step: f64,
+) -> Result<Vec<EphemerisPoint>, CalculationError> {
+    if step <= 0.0 || !start.is_finite() || !end.is_finite() {
+        return Err(CalculationError::InvalidInput);
+    }
+    let span = end - start;
+    let count = (span.abs() / step).floor() as usize;
+    let direction = span.signum();
+
+    let mut points = Vec::with_capacity(count + 2);
+    for k in 0..=count {
+        let time = start + direction * step * k as f64;
+        points.push(EphemerisPoint { time, state: propagate_state(state, mu, time)? });
+    }
+    // Always finish exactly at the requested end time
+    if points.last().map(|p| p.time) != Some(end) {
+        points.push(EphemerisPoint { time: end, state: propagate_state(state, mu, end)? });
+    }
+    Ok(points)
+}
+
+// CSV with one row per point: time in s, position in m, velocity in m/s
+pub fn ephemeris_to_csv(points: &[EphemerisPoint]) -> String {
+    let mut csv = String::from("time_s,x_m,y_m,z_m,vx_m_s,vy_m_s,vz_m_s\n");
+    for point in points {
+        let r = point.state.position;
+        let v = point.state.velocity;
+        let _ = writeln!(csv, "{},{},{},{},{},{},{}", point.time, r.x, r.y, r.z, v.x, v.y, v.z);
+    }
+    csv
+}
+
+pub fn write_ephemeris_csv<P: AsRef<Path>>(points: &[EphemerisPoint], path: P) -> std::io::Result<()> {
+    fs::write(path, ephemeris_to_csv(points))
+}
+
+// Same as `ephemeris_to_csv` with a leading ISO 8601 UTC time stamp, the
+// offsets being counted from `epoch`
+pub fn timestamped_ephemeris_to_csv(points: &[EphemerisPoint], epoch: &Epoch) -> String {
+    let mut csv = String::from("utc,time_s,x_m,y_m,z_m,vx_m_s,vy_m_s,vz_m_s\n");
+    for point in points {
+        let r = point.state.position;
+        let v = point.state.velocity;
+        let stamp = (*epoch + point.time).to_iso(TimeScale::Utc, 3);
+        let _ = writeln!(csv, "{},{},{},{},{},{},{},{}", stamp, point.time, r.x, r.y, r.z, v.x, v.y, v.z);
+    }
+    csv
+}
+
+pub fn write_timestamped_ephemeris_csv<P: AsRef<Path>>(
+    points: &[EphemerisPoint],
+    epoch: &Epoch,
+    path: P,
+) -> std::io::Result<()> {
+    fs::write(path, timestamped_ephemeris_to_csv(points, epoch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Vector3;
+    use std::f64::consts::PI;
+
+    const MU: f64 = 3.986004418e14; // m^3/s^2, Earth
+
+    fn km(x: f64, y: f64, z: f64) -> Vector3 {
+        Vector3::new(x, y, z) * 1000.0
+    }
+
+    #[test]
+    fn vallado_example_2_4() {
+        let state = StateVector::new(km(1131.340, -2282.343, 6672.423), km(-5.64305, 4.30333, 2.42879));
+        let later = propagate_state(&state, MU, 40.0 * 60.0).unwrap();
+        assert!((later.position - km(-4219.7527, 4363.0292, -3958.7666)).norm() < 1.0);
+        assert!((later.velocity - km(3.689866, -1.916735, -6.112511)).norm() < 1e-3);
+    }
+
+    #[test]
+    fn curtis_example_3_7() {
+        let state = StateVector::new(km(7000.0, -12124.0, 0.0), km(2.6679, 4.6210, 0.0));
+        let later = propagate_state(&state, 398600e9, 3600.0).unwrap();
+        // The book rounds the universal anomaly, hence the loose velocity check
+        assert!((later.position - km(-3297.8, 7413.4, 0.0)).norm() < 100.0);
+        assert!((later.velocity - km(-8.2977, -0.96309, 0.0)).norm() < 2.0);
+    }
+
+    #[test]
+    fn one_period_returns_to_the_start() {
+        let elements = OrbitalElements::new(26560e3, 0.7, 1.1, 0.3, 4.7, 0.2).unwrap();
+        let state = elements.to_state(MU).unwrap();
+        let period = elements.period(MU).unwrap();
+        for revolutions in [1.0, -1.0, 3.0] {
+            let back = propagate_state(&state, MU, revolutions * period).unwrap();
+            assert!((back.position - state.position).norm() < 1e-3, "{} rev", revolutions);
+            assert!((back.velocity - state.velocity).norm() < 1e-6, "{} rev", revolutions);
+        }
+        // Half a period from periapsis lands on apoapsis
+        let periapsis = OrbitalElements::new(26560e3, 0.7, 1.1, 0.3, 4.7, 0.0).unwrap();
+        let apoapsis = propagate_state(&periapsis.to_state(MU).unwrap(), MU, period / 2.0).unwrap();
+        assert!((apoapsis.position.norm() - periapsis.apoapsis_radius()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn hyperbolic_propagation_runs_both_ways() {
+        let elements = OrbitalElements::new(-20000e3, 1.8, 0.5, 1.0, 2.0, 0.3).unwrap();
+        let state = elements.to_state(MU).unwrap();
+        for dt in [3600.0, -3600.0, 86400.0] {
+            let moved = propagate_state(&state, MU, dt).unwrap();
+            let back = propagate_state(&moved, MU, -dt).unwrap();
+            assert!((back.position - state.position).norm() < 1e-3, "dt = {}", dt);
+            assert!((back.velocity - state.velocity).norm() < 1e-6, "dt = {}", dt);
+
+            // The mean anomaly advances at the hyperbolic mean motion
+            let after = OrbitalElements::from_state(&moved, MU).unwrap();
+            let n = (MU / (-elements.semi_major_axis()).powi(3)).sqrt();
+            assert!((after.mean_anomaly() - elements.mean_anomaly() - n * dt).abs() < 1e-9, "dt = {}", dt);
+        }
+    }
+
+    #[test]
+    fn kepler_equation_is_solved() {
+        // Vallado example 2-1
+        let anomaly = solve_kepler(235.4f64.to_radians(), 0.4).unwrap();
+        assert!((wrap_pi(anomaly).to_degrees() + 360.0 - 220.512074767522).abs() < 1e-9);
+        for e in [0.0, 0.5, 0.99, 1.0, 1.5, 10.0] {
+            for m in [-20.0, -PI, -0.1, 0.0, 1e-3, 2.0, 50.0] {
+                let x = solve_kepler(m, e).unwrap();
+                let residual = if e < 1.0 - PARABOLIC_TOLERANCE {
+                    x - e * x.sin() - m
+                } else if e > 1.0 + PARABOLIC_TOLERANCE {
+                    e * x.sinh() - x - m
+                } else {
+                    x + x.powi(3) / 3.0 - m
+                };
+                assert!(residual.abs() < 1e-9 * (1.0 + m.abs()), "e = {}, M = {}: {}", e, m, residual);
+            }
+        }
+    }
+
+    #[test]
+    fn series_ends_on_the_end_time() {
+        let state = StateVector::new(km(7000.0, 0.0, 0.0), km(0.0, 7.5, 0.0));
+        let times: Vec<f64> = propagate_series(&state, MU, 0.0, 250.0, 100.0).unwrap().iter().map(|p| p.time).collect();
+        assert_eq!(times, [0.0, 100.0, 200.0, 250.0]);
+        let times: Vec<f64> = propagate_series(&state, MU, 0.0, -200.0, 100.0).unwrap().iter().map(|p| p.time).collect();
+        assert_eq!(times, [0.0, -100.0, -200.0]);
+    }
+}
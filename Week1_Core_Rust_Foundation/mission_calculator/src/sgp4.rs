@@ -0,0 +1,1196 @@
+// SGP4/SDP4 analytical propagation of Two-Line Element sets
+//
+// A port of the reference implementation from Vallado, Crawford, Hujsak and
+// Kelso, "Revisiting Spacetrack Report #3" (AIAA 2006-6753), with WGS-72
+// constants and the improved ("i") operation mode. Orbits with a period of
+// 225 minutes or more use the deep-space (SDP4) lunar-solar and resonance
+// terms. TLE mean elements only make sense with this model: feeding them to
+// the two-body propagator gives errors of tens of kilometers.
+//
+// States come out in the True Equator, Mean Equinox (TEME) frame of date,
+// in m and m/s like the rest of the library.
+use crate::CalculationError;
+use crate::elements::StateVector;
+use crate::frames::{Frame, FramedState, greenwich_sidereal_angle};
+use crate::propagate::EphemerisPoint;
+use crate::tle::Tle;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+
+const TWO_PI: f64 = 2.0 * PI;
+const X2O3: f64 = 2.0 / 3.0;
+
+// WGS-72 constants the element sets are generated with
+const MU: f64 = 398600.8; // km^3/s^2
+const RADIUS: f64 = 6378.135; // km
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+const J3OJ2: f64 = J3 / J2;
+
+// Earth radii per minute, the SGP4 unit of speed
+fn xke() -> f64 {
+    60.0 / (RADIUS * RADIUS * RADIUS / MU).sqrt()
+}
+
+// Lunar-solar and resonance constants
+const ZNS: f64 = 1.19459e-5;
+const ZES: f64 = 0.01675;
+const ZNL: f64 = 1.5835218e-4;
+const ZEL: f64 = 0.05490;
+const RPTIM: f64 = 4.375_269_088_011_3e-3; // Earth rotation, rad/min
+const STEPP: f64 = 720.0;
+const STEPN: f64 = -720.0;
+const STEP2: f64 = 259200.0;
+const FASX2: f64 = 0.13130908;
+const FASX4: f64 = 2.8843198;
+const FASX6: f64 = 0.37448087;
+const G22: f64 = 5.7686396;
+const G32: f64 = 0.95240898;
+const G44: f64 = 1.8014998;
+const G52: f64 = 1.0508330;
+const G54: f64 = 4.4108898;
+
+// Deep-space lunar-solar periodic coefficients (dscom outputs)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LunarSolar {
+    e3: f64,
+    ee2: f64,
+    peo: f64,
+    pgho: f64,
+    pho: f64,
+    pinco: f64,
+    plo: f64,
+    se2: f64,
+    se3: f64,
+    sgh2: f64,
+    sgh3: f64,
+    sgh4: f64,
+    sh2: f64,
+    sh3: f64,
+    si2: f64,
+    si3: f64,
+    sl2: f64,
+    sl3: f64,
+    sl4: f64,
+    xgh2: f64,
+    xgh3: f64,
+    xgh4: f64,
+    xh2: f64,
+    xh3: f64,
+    xi2: f64,
+    xi3: f64,
+    xl2: f64,
+    xl3: f64,
+    xl4: f64,
+    zmol: f64,
+    zmos: f64,
+}
+
+// Deep-space secular rates and resonance integrator constants (dsinit outputs)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Resonance {
+    irez: u8, // 0 none, 1 one-day (synchronous), 2 half-day
+    d2201: f64,
+    d2211: f64,
+    d3210: f64,
+    d3222: f64,
+    d4410: f64,
+    d4422: f64,
+    d5220: f64,
+    d5232: f64,
+    d5421: f64,
+    d5433: f64,
+    dedt: f64,
+    didt: f64,
+    dmdt: f64,
+    dnodt: f64,
+    domdt: f64,
+    del1: f64,
+    del2: f64,
+    del3: f64,
+    xfact: f64,
+    xlamo: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sgp4 {
+    pub epoch: f64, // Julian date of the element set, UTC
+    deep_space: bool,
+    isimp: bool,
+    // Mean elements at epoch, radians and radians per minute
+    bstar: f64,
+    ecco: f64,
+    argpo: f64,
+    inclo: f64,
+    mo: f64,
+    no: f64,
+    nodeo: f64,
+    // Near-Earth coefficients
+    aycof: f64,
+    con41: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    mdot: f64,
+    nodedot: f64,
+    xlcof: f64,
+    xmcof: f64,
+    nodecf: f64,
+    gsto: f64,
+    lunar_solar: LunarSolar,
+    resonance: Resonance,
+}
+
+impl Sgp4 {
+    pub fn from_tle(tle: &Tle) -> Result<Self, CalculationError> {
+        // rev/day to rad/min
+        let no_kozai = tle.mean_motion * TWO_PI / 1440.0;
+        Sgp4::new(
+            tle.epoch,
+            tle.bstar,
+            tle.eccentricity,
+            tle.arg_perigee,
+            tle.inclination,
+            tle.mean_anomaly,
+            no_kozai,
+            tle.raan,
+        )
+    }
+
+    // Initialize from mean elements at `epoch` (Julian date); angles in
+    // rad, Kozai mean motion in rad/min, B* in 1/earth radii (sgp4init)
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        epoch: f64,
+        bstar: f64,
+        ecco: f64,
+        argpo: f64,
+        inclo: f64,
+        mo: f64,
+        no_kozai: f64,
+        nodeo: f64,
+    ) -> Result<Self, CalculationError> {
+        if !(0.0..1.0).contains(&ecco) || no_kozai <= 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        let xke = xke();
+        let ss = 78.0 / RADIUS + 1.0;
+        let qzms2t = ((120.0 - 78.0) / RADIUS).powi(4);
+
+        // initl: recover the original (Brouwer) mean motion from the Kozai one
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = inclo.cos();
+        let cosio2 = cosio * cosio;
+        let ak = (xke / no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+        let no = no_kozai / (1.0 + del);
+
+        let ao = (xke / no).powf(X2O3);
+        let sinio = inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+        // Days since 1950 Jan 0.0, the SGP4 epoch convention
+        let epoch_1950 = epoch - 2433281.5;
+        let gsto = greenwich_sidereal_angle(epoch);
+
+        let mut sat = Sgp4 {
+            epoch,
+            deep_space: false,
+            isimp: rp < 220.0 / RADIUS + 1.0,
+            bstar,
+            ecco,
+            argpo,
+            inclo,
+            mo,
+            no,
+            nodeo,
+            aycof: 0.0,
+            con41,
+            cc1: 0.0,
+            cc4: 0.0,
+            cc5: 0.0,
+            d2: 0.0,
+            d3: 0.0,
+            d4: 0.0,
+            delmo: 0.0,
+            eta: 0.0,
+            argpdot: 0.0,
+            omgcof: 0.0,
+            sinmao: 0.0,
+            t2cof: 0.0,
+            t3cof: 0.0,
+            t4cof: 0.0,
+            t5cof: 0.0,
+            x1mth2: 0.0,
+            x7thm1: 0.0,
+            mdot: 0.0,
+            nodedot: 0.0,
+            xlcof: 0.0,
+            xmcof: 0.0,
+            nodecf: 0.0,
+            gsto,
+            lunar_solar: LunarSolar::default(),
+            resonance: Resonance::default(),
+        };
+
+        // For perigees below 156 km the atmosphere parameters s and q0 change
+        let perigee = (rp - 1.0) * RADIUS;
+        let (sfour, qzms24) = if perigee < 156.0 {
+            let s = if perigee < 98.0 { 20.0 } else { perigee - 78.0 };
+            (s / RADIUS + 1.0, ((120.0 - s) / RADIUS).powi(4))
+        } else {
+            (ss, qzms2t)
+        };
+
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        sat.eta = ao * ecco * tsi;
+        let etasq = sat.eta * sat.eta;
+        let eeta = ecco * sat.eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        sat.cc1 = bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 { -2.0 * coef * tsi * J3OJ2 * no * sinio / ecco } else { 0.0 };
+        sat.x1mth2 = 1.0 - cosio2;
+        sat.cc4 = 2.0
+            * no
+            * coef1
+            * ao
+            * omeosq
+            * (sat.eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75 * sat.x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * argpo).cos()));
+        sat.cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+        sat.mdot = no
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        sat.argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        sat.nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let xpidot = sat.argpdot + sat.nodedot;
+        sat.omgcof = bstar * cc3 * argpo.cos();
+        sat.xmcof = if ecco > 1.0e-4 { -X2O3 * coef * bstar / eeta } else { 0.0 };
+        sat.nodecf = 3.5 * omeosq * xhdot1 * sat.cc1;
+        sat.t2cof = 1.5 * sat.cc1;
+        sat.xlcof = long_period_xlcof(sinio, cosio);
+        sat.aycof = -0.5 * J3OJ2 * sinio;
+        sat.delmo = (1.0 + sat.eta * mo.cos()).powi(3);
+        sat.sinmao = mo.sin();
+        sat.x7thm1 = 7.0 * cosio2 - 1.0;
+
+        if TWO_PI / no >= 225.0 {
+            sat.deep_space = true;
+            sat.isimp = true;
+            let (lunar_solar, common) = dscom(epoch_1950, ecco, argpo, 0.0, inclo, nodeo, no);
+            sat.lunar_solar = lunar_solar;
+            sat.resonance = dsinit(&sat, &common, xpidot, eccsq);
+        }
+
+        if !sat.isimp {
+            let cc1sq = sat.cc1 * sat.cc1;
+            sat.d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = sat.d2 * tsi * sat.cc1 / 3.0;
+            sat.d3 = (17.0 * ao + sfour) * temp;
+            sat.d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * sat.cc1;
+            sat.t3cof = sat.d2 + 2.0 * cc1sq;
+            sat.t4cof = 0.25 * (3.0 * sat.d3 + sat.cc1 * (12.0 * sat.d2 + 10.0 * cc1sq));
+            sat.t5cof =
+                0.2 * (3.0 * sat.d4 + 12.0 * sat.cc1 * sat.d3 + 6.0 * sat.d2 * sat.d2 + 15.0 * cc1sq * (2.0 * sat.d2 + cc1sq));
+        }
+
+        // The reference implementation propagates to epoch once to catch
+        // element sets that are already invalid
+        sat.propagate(0.0)?;
+        Ok(sat)
+    }
+
+    pub fn is_deep_space(&self) -> bool {
+        self.deep_space
+    }
+
+    // TEME state `time` seconds after the element set epoch. Fails with
+    // OutOfRange once the orbit has decayed or the eccentricity leaves [0, 1).
+    pub fn propagate(&self, time: f64) -> Result<StateVector, CalculationError> {
+        let t = time / 60.0; // minutes since epoch
+        let xke = xke();
+
+        // Secular gravity and atmospheric drag
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let mut nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ = templ + self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let mut nm = self.no;
+        let mut em = self.ecco;
+        let mut inclm = self.inclo;
+        if self.deep_space {
+            dspace(self, t, &mut em, &mut argpm, &mut inclm, &mut mm, &mut nodem, &mut nm);
+        }
+
+        if nm <= 0.0 {
+            return Err(CalculationError::OutOfRange);
+        }
+        let am = (xke / nm).powf(X2O3) * tempa * tempa;
+        nm = xke / am.powf(1.5);
+        em -= tempe;
+        if !(-0.001..1.0).contains(&em) {
+            return Err(CalculationError::OutOfRange);
+        }
+        em = em.max(1.0e-6);
+        mm += self.no * templ;
+        let xlm = mm + argpm + nodem;
+
+        nodem %= TWO_PI;
+        argpm %= TWO_PI;
+        let xlm = xlm % TWO_PI;
+        mm = (xlm - argpm - nodem) % TWO_PI;
+
+        // Lunar-solar periodics
+        let mut ep = em;
+        let mut xincp = inclm;
+        let mut argpp = argpm;
+        let mut nodep = nodem;
+        let mut mp = mm;
+        let mut aycof = self.aycof;
+        let mut xlcof = self.xlcof;
+        let mut con41 = self.con41;
+        let mut x1mth2 = self.x1mth2;
+        let mut x7thm1 = self.x7thm1;
+        if self.deep_space {
+            dpper(&self.lunar_solar, t, false, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp);
+            if xincp < 0.0 {
+                xincp = -xincp;
+                nodep += PI;
+                argpp -= PI;
+            }
+            if !(0.0..=1.0).contains(&ep) {
+                return Err(CalculationError::OutOfRange);
+            }
+            let (sinip, cosip) = xincp.sin_cos();
+            aycof = -0.5 * J3OJ2 * sinip;
+            xlcof = long_period_xlcof(sinip, cosip);
+        }
+        let (sinip, cosip) = xincp.sin_cos();
+
+        // Long-period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * aycof;
+        let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+        // Kepler's equation in equinoctial form
+        let u = (xl - nodep) % TWO_PI;
+        let mut eo1 = u;
+        let mut tem5: f64 = 9999.9;
+        let mut ktr = 1;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        while tem5.abs() >= 1.0e-12 && ktr <= 10 {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+            tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+            tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+            tem5 = tem5.clamp(-0.95, 0.95);
+            eo1 += tem5;
+            ktr += 1;
+        }
+
+        // Short-period preliminary quantities
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(CalculationError::OutOfRange);
+        }
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        // Short-period periodics
+        if self.deep_space {
+            let cosisq = cosip * cosip;
+            con41 = 3.0 * cosisq - 1.0;
+            x1mth2 = 1.0 - cosisq;
+            x7thm1 = 7.0 * cosisq - 1.0;
+        }
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        su -= 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+        // Orientation vectors
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let u_vec = Vector3::new(xmx * sinsu + cnod * cossu, xmy * sinsu + snod * cossu, sini * sinsu);
+        let v_vec = Vector3::new(xmx * cossu - cnod * sinsu, xmy * cossu - snod * sinsu, sini * cossu);
+
+        // A radius below one earth radius means the satellite has decayed
+        if mrt < 1.0 {
+            return Err(CalculationError::OutOfRange);
+        }
+        let speed_unit = RADIUS * xke / 60.0; // km/s per earth radius per minute
+        let position = u_vec * (mrt * RADIUS * 1000.0);
+        let velocity = (u_vec * mvt + v_vec * rvdot) * (speed_unit * 1000.0);
+        Ok(StateVector::new(position, velocity))
+    }
+
+    // The same state tagged with its frame and UTC date, for pass
+    // prediction, ground tracks and frame conversions
+    pub fn propagate_framed(&self, time: f64) -> Result<FramedState, CalculationError> {
+        Ok(FramedState::new(Frame::Teme, self.epoch + time / 86400.0, self.propagate(time)?))
+    }
+
+    // Evenly spaced TEME states from `start` to `end` seconds after epoch
+    pub fn propagate_series(&self, start: f64, end: f64, step: f64) -> Result<Vec<EphemerisPoint>, CalculationError> {
+        if step <= 0.0 || !start.is_finite() || !end.is_finite() {
+            return Err(CalculationError::InvalidInput);
+        }
+        let span = end - start;
+        let count = (span.abs() / step).floor() as usize;
+        let direction = span.signum();
+
+        let mut points = Vec::with_capacity(count + 2);
+        for k in 0..=count {
+            let time = start + direction * step * k as f64;
+            points.push(EphemerisPoint { time, state: self.propagate(time)? });
+        }
+        if points.last().map(|p| p.time) != Some(end) {
+            points.push(EphemerisPoint { time: end, state: self.propagate(end)? });
+        }
+        Ok(points)
+    }
+}
+
+// Long-period coefficient for the mean longitude, guarded at 180 deg inclination
+fn long_period_xlcof(sinio: f64, cosio: f64) -> f64 {
+    let denominator = if (cosio + 1.0).abs() > 1.5e-12 { 1.0 + cosio } else { 1.5e-12 };
+    -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / denominator
+}
+
+// Intermediate dscom values dsinit needs
+struct DeepSpaceCommon {
+    sinim: f64,
+    cosim: f64,
+    emsq: f64,
+    s1: f64,
+    s2: f64,
+    s3: f64,
+    s4: f64,
+    s5: f64,
+    ss1: f64,
+    ss2: f64,
+    ss3: f64,
+    ss4: f64,
+    ss5: f64,
+    sz1: f64,
+    sz3: f64,
+    sz11: f64,
+    sz13: f64,
+    sz21: f64,
+    sz23: f64,
+    sz31: f64,
+    sz33: f64,
+    z1: f64,
+    z3: f64,
+    z11: f64,
+    z13: f64,
+    z21: f64,
+    z23: f64,
+    z31: f64,
+    z33: f64,
+}
+
+// Lunar and solar perturbation terms common to the deep-space routines
+fn dscom(epoch: f64, ep: f64, argpp: f64, tc: f64, inclp: f64, nodep: f64, np: f64) -> (LunarSolar, DeepSpaceCommon) {
+    const C1SS: f64 = 2.9864797e-6;
+    const C1L: f64 = 4.7968065e-7;
+    const ZSINIS: f64 = 0.39785416;
+    const ZCOSIS: f64 = 0.91744867;
+    const ZCOSGS: f64 = 0.1945905;
+    const ZSINGS: f64 = -0.98088458;
+
+    let nm = np;
+    let em = ep;
+    let (snodm, cnodm) = nodep.sin_cos();
+    let (sinomm, cosomm) = argpp.sin_cos();
+    let (sinim, cosim) = inclp.sin_cos();
+    let emsq = em * em;
+    let betasq = 1.0 - emsq;
+    let rtemsq = betasq.sqrt();
+
+    let day = epoch + 18261.5 + tc / 1440.0;
+    let xnodce = (4.5236020 - 9.2422029e-4 * day) % TWO_PI;
+    let (stem, ctem) = xnodce.sin_cos();
+    let zcosil = 0.91375164 - 0.03568096 * ctem;
+    let zsinil = (1.0 - zcosil * zcosil).sqrt();
+    let zsinhl = 0.089683511 * stem / zsinil;
+    let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+    let gam = 5.8351514 + 0.0019443680 * day;
+    let zx = 0.39785416 * stem / zsinil;
+    let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+    let zx = gam + zx.atan2(zy) - xnodce;
+    let (zsingl, zcosgl) = zx.sin_cos();
+
+    // First pass uses the Sun, second the Moon
+    let mut zcosg = ZCOSGS;
+    let mut zsing = ZSINGS;
+    let mut zcosi = ZCOSIS;
+    let mut zsini = ZSINIS;
+    let mut zcosh = cnodm;
+    let mut zsinh = snodm;
+    let mut cc = C1SS;
+    let xnoi = 1.0 / nm;
+
+    let mut solar = [0.0; 19];
+    let mut lunar = [0.0; 19];
+    for pass in 0..2 {
+        let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+        let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+        let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+        let a8 = zsing * zsini;
+        let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+        let a10 = zcosg * zsini;
+        let a2 = cosim * a7 + sinim * a8;
+        let a4 = cosim * a9 + sinim * a10;
+        let a5 = -sinim * a7 + cosim * a8;
+        let a6 = -sinim * a9 + cosim * a10;
+
+        let x1 = a1 * cosomm + a2 * sinomm;
+        let x2 = a3 * cosomm + a4 * sinomm;
+        let x3 = -a1 * sinomm + a2 * cosomm;
+        let x4 = -a3 * sinomm + a4 * cosomm;
+        let x5 = a5 * sinomm;
+        let x6 = a6 * sinomm;
+        let x7 = a5 * cosomm;
+        let x8 = a6 * cosomm;
+
+        let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+        let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+        let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+        let z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+        let z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+        let z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+        let z11 = -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+        let z12 = -6.0 * (a1 * a6 + a3 * a5) + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+        let z13 = -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+        let z21 = 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+        let z22 = 6.0 * (a4 * a5 + a2 * a6) + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+        let z23 = 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+        let z1 = z1 + z1 + betasq * z31;
+        let z2 = z2 + z2 + betasq * z32;
+        let z3 = z3 + z3 + betasq * z33;
+        let s3 = cc * xnoi;
+        let s2 = -0.5 * s3 / rtemsq;
+        let s4 = s3 * rtemsq;
+        let s1 = -15.0 * em * s4;
+        let s5 = x1 * x3 + x2 * x4;
+        let s6 = x2 * x3 + x1 * x4;
+        let s7 = x2 * x4 - x1 * x3;
+
+        let terms = [s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33];
+        if pass == 0 {
+            solar = terms;
+            zcosg = zcosgl;
+            zsing = zsingl;
+            zcosi = zcosil;
+            zsini = zsinil;
+            zcosh = zcoshl * cnodm + zsinhl * snodm;
+            zsinh = snodm * zcoshl - cnodm * zsinhl;
+            cc = C1L;
+        } else {
+            lunar = terms;
+        }
+    }
+    let [ss1, ss2, ss3, ss4, ss5, ss6, ss7, sz1, sz2, sz3, sz11, sz12, sz13, sz21, sz22, sz23, sz31, sz32, sz33] = solar;
+    let [s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33] = lunar;
+
+    let lunar_solar = LunarSolar {
+        zmol: (4.7199672 + 0.22997150 * day - gam) % TWO_PI,
+        zmos: (6.2565837 + 0.017201977 * day) % TWO_PI,
+        se2: 2.0 * ss1 * ss6,
+        se3: 2.0 * ss1 * ss7,
+        si2: 2.0 * ss2 * sz12,
+        si3: 2.0 * ss2 * (sz13 - sz11),
+        sl2: -2.0 * ss3 * sz2,
+        sl3: -2.0 * ss3 * (sz3 - sz1),
+        sl4: -2.0 * ss3 * (-21.0 - 9.0 * emsq) * ZES,
+        sgh2: 2.0 * ss4 * sz32,
+        sgh3: 2.0 * ss4 * (sz33 - sz31),
+        sgh4: -18.0 * ss4 * ZES,
+        sh2: -2.0 * ss2 * sz22,
+        sh3: -2.0 * ss2 * (sz23 - sz21),
+        ee2: 2.0 * s1 * s6,
+        e3: 2.0 * s1 * s7,
+        xi2: 2.0 * s2 * z12,
+        xi3: 2.0 * s2 * (z13 - z11),
+        xl2: -2.0 * s3 * z2,
+        xl3: -2.0 * s3 * (z3 - z1),
+        xl4: -2.0 * s3 * (-21.0 - 9.0 * emsq) * ZEL,
+        xgh2: 2.0 * s4 * z32,
+        xgh3: 2.0 * s4 * (z33 - z31),
+        xgh4: -18.0 * s4 * ZEL,
+        xh2: -2.0 * s2 * z22,
+        xh3: -2.0 * s2 * (z23 - z21),
+        peo: 0.0,
+        pgho: 0.0,
+        pho: 0.0,
+        pinco: 0.0,
+        plo: 0.0,
+    };
+    let common = DeepSpaceCommon {
+        sinim,
+        cosim,
+        emsq,
+        s1,
+        s2,
+        s3,
+        s4,
+        s5,
+        ss1,
+        ss2,
+        ss3,
+        ss4,
+        ss5,
+        sz1,
+        sz3,
+        sz11,
+        sz13,
+        sz21,
+        sz23,
+        sz31,
+        sz33,
+        z1,
+        z3,
+        z11,
+        z13,
+        z21,
+        z23,
+        z31,
+        z33,
+    };
+    (lunar_solar, common)
+}
+
+// Lunar-solar periodic perturbations of the mean elements
+#[allow(clippy::too_many_arguments)]
+fn dpper(
+    ls: &LunarSolar,
+    t: f64,
+    init: bool,
+    ep: &mut f64,
+    inclp: &mut f64,
+    nodep: &mut f64,
+    argpp: &mut f64,
+    mp: &mut f64,
+) {
+    let periodics = |zm: f64, ze: f64| {
+        let zf = zm + 2.0 * ze * zm.sin();
+        let sinzf = zf.sin();
+        (sinzf, 0.5 * sinzf * sinzf - 0.25, -0.5 * sinzf * zf.cos())
+    };
+
+    let zm = if init { ls.zmos } else { ls.zmos + ZNS * t };
+    let (sinzf, f2, f3) = periodics(zm, ZES);
+    let ses = ls.se2 * f2 + ls.se3 * f3;
+    let sis = ls.si2 * f2 + ls.si3 * f3;
+    let sls = ls.sl2 * f2 + ls.sl3 * f3 + ls.sl4 * sinzf;
+    let sghs = ls.sgh2 * f2 + ls.sgh3 * f3 + ls.sgh4 * sinzf;
+    let shs = ls.sh2 * f2 + ls.sh3 * f3;
+
+    let zm = if init { ls.zmol } else { ls.zmol + ZNL * t };
+    let (sinzf, f2, f3) = periodics(zm, ZEL);
+    let sel = ls.ee2 * f2 + ls.e3 * f3;
+    let sil = ls.xi2 * f2 + ls.xi3 * f3;
+    let sll = ls.xl2 * f2 + ls.xl3 * f3 + ls.xl4 * sinzf;
+    let sghl = ls.xgh2 * f2 + ls.xgh3 * f3 + ls.xgh4 * sinzf;
+    let shll = ls.xh2 * f2 + ls.xh3 * f3;
+
+    if init {
+        return;
+    }
+    let pe = ses + sel - ls.peo;
+    let pinc = sis + sil - ls.pinco;
+    let pl = sls + sll - ls.plo;
+    let mut pgh = sghs + sghl - ls.pgho;
+    let mut ph = shs + shll - ls.pho;
+    *inclp += pinc;
+    *ep += pe;
+    let (sinip, cosip) = inclp.sin_cos();
+
+    if *inclp >= 0.2 {
+        ph /= sinip;
+        pgh -= cosip * ph;
+        *argpp += pgh;
+        *nodep += ph;
+        *mp += pl;
+    } else {
+        // Lyddane modification for low inclinations
+        let (sinop, cosop) = nodep.sin_cos();
+        let mut alfdp = sinip * sinop;
+        let mut betdp = sinip * cosop;
+        let dalf = ph * cosop + pinc * cosip * sinop;
+        let dbet = -ph * sinop + pinc * cosip * cosop;
+        alfdp += dalf;
+        betdp += dbet;
+        *nodep %= TWO_PI;
+        let mut xls = *mp + *argpp + cosip * *nodep;
+        let dls = pl + pgh - pinc * *nodep * sinip;
+        xls += dls;
+        let xnoh = *nodep;
+        *nodep = alfdp.atan2(betdp);
+        if (xnoh - *nodep).abs() > PI {
+            if *nodep < xnoh {
+                *nodep += TWO_PI;
+            } else {
+                *nodep -= TWO_PI;
+            }
+        }
+        *mp += pl;
+        *argpp = xls - *mp - cosip * *nodep;
+    }
+}
+
+// Deep-space secular rates and resonance setup
+fn dsinit(sat: &Sgp4, c: &DeepSpaceCommon, xpidot: f64, eccsq: f64) -> Resonance {
+    const Q22: f64 = 1.7891679e-6;
+    const Q31: f64 = 2.1460748e-6;
+    const Q33: f64 = 2.2123015e-7;
+    const ROOT22: f64 = 1.7891679e-6;
+    const ROOT44: f64 = 7.3636953e-9;
+    const ROOT54: f64 = 2.1765803e-9;
+    const ROOT32: f64 = 3.7393792e-7;
+    const ROOT52: f64 = 1.1428639e-7;
+
+    let nm = sat.no;
+    let em = sat.ecco;
+    let inclm = sat.inclo;
+    let (sinim, cosim, emsq) = (c.sinim, c.cosim, c.emsq);
+    let mut res = Resonance::default();
+
+    if nm < 0.0052359877 && nm > 0.0034906585 {
+        res.irez = 1;
+    }
+    if (8.26e-3..=9.24e-3).contains(&nm) && em >= 0.5 {
+        res.irez = 2;
+    }
+
+    // Solar terms
+    let ses = c.ss1 * ZNS * c.ss5;
+    let sis = c.ss2 * ZNS * (c.sz11 + c.sz13);
+    let sls = -ZNS * c.ss3 * (c.sz1 + c.sz3 - 14.0 - 6.0 * emsq);
+    let sghs = c.ss4 * ZNS * (c.sz31 + c.sz33 - 6.0);
+    let near_equatorial = !(5.2359877e-2..=PI - 5.2359877e-2).contains(&inclm);
+    let mut shs = if near_equatorial { 0.0 } else { -ZNS * c.ss2 * (c.sz21 + c.sz23) };
+    if sinim != 0.0 {
+        shs /= sinim;
+    }
+    let sgs = sghs - cosim * shs;
+
+    // Lunar terms
+    res.dedt = ses + c.s1 * ZNL * c.s5;
+    res.didt = sis + c.s2 * ZNL * (c.z11 + c.z13);
+    res.dmdt = sls - ZNL * c.s3 * (c.z1 + c.z3 - 14.0 - 6.0 * emsq);
+    let sghl = c.s4 * ZNL * (c.z31 + c.z33 - 6.0);
+    let shll = if near_equatorial { 0.0 } else { -ZNL * c.s2 * (c.z21 + c.z23) };
+    res.domdt = sgs + sghl;
+    res.dnodt = shs;
+    if sinim != 0.0 {
+        res.domdt -= cosim / sinim * shll;
+        res.dnodt += shll / sinim;
+    }
+
+    if res.irez == 0 {
+        return res;
+    }
+    let theta = sat.gsto % TWO_PI;
+    let aonv = (nm / xke()).powf(X2O3);
+
+    if res.irez == 2 {
+        // Geopotential resonance for 12 hour orbits
+        let cosisq = cosim * cosim;
+        let em = sat.ecco;
+        let emsq = eccsq;
+        let eoc = em * emsq;
+        let g201 = -0.306 - (em - 0.64) * 0.440;
+        let (g211, g310, g322, g410, g422, g520);
+        if em <= 0.65 {
+            g211 = 3.616 - 13.2470 * em + 16.2900 * emsq;
+            g310 = -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc;
+            g322 = -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc;
+            g410 = -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc;
+            g422 = -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc;
+            g520 = -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc;
+        } else {
+            g211 = -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc;
+            g310 = -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc;
+            g322 = -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc;
+            g410 = -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc;
+            g422 = -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc;
+            g520 = if em > 0.715 {
+                -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+            } else {
+                1464.74 - 4664.75 * em + 3763.64 * emsq
+            };
+        }
+        let (g533, g521, g532) = if em < 0.7 {
+            (
+                -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc,
+                -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc,
+                -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc,
+            )
+        } else {
+            (
+                -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc,
+                -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc,
+                -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc,
+            )
+        };
+
+        let sini2 = sinim * sinim;
+        let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+        let f221 = 1.5 * sini2;
+        let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+        let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+        let f441 = 35.0 * sini2 * f220;
+        let f442 = 39.3750 * sini2 * sini2;
+        let f522 = 9.84375
+            * sinim
+            * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq) + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+        let f523 = sinim
+            * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+        let f542 = 29.53125 * sinim * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+        let f543 = 29.53125 * sinim * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+        let xno2 = nm * nm;
+        let ainv2 = aonv * aonv;
+        let mut temp1 = 3.0 * xno2 * ainv2;
+        let mut temp = temp1 * ROOT22;
+        res.d2201 = temp * f220 * g201;
+        res.d2211 = temp * f221 * g211;
+        temp1 *= aonv;
+        temp = temp1 * ROOT32;
+        res.d3210 = temp * f321 * g310;
+        res.d3222 = temp * f322 * g322;
+        temp1 *= aonv;
+        temp = 2.0 * temp1 * ROOT44;
+        res.d4410 = temp * f441 * g410;
+        res.d4422 = temp * f442 * g422;
+        temp1 *= aonv;
+        temp = temp1 * ROOT52;
+        res.d5220 = temp * f522 * g520;
+        res.d5232 = temp * f523 * g532;
+        temp = 2.0 * temp1 * ROOT54;
+        res.d5421 = temp * f542 * g521;
+        res.d5433 = temp * f543 * g533;
+        res.xlamo = (sat.mo + sat.nodeo + sat.nodeo - theta - theta) % TWO_PI;
+        res.xfact = sat.mdot + res.dmdt + 2.0 * (sat.nodedot + res.dnodt - RPTIM) - sat.no;
+    }
+
+    if res.irez == 1 {
+        // Synchronous resonance terms
+        let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+        let g310 = 1.0 + 2.0 * emsq;
+        let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+        let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+        let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+        let f330 = 1.875 * (1.0 + cosim).powi(3);
+        let del1 = 3.0 * nm * nm * aonv * aonv;
+        res.del2 = 2.0 * del1 * f220 * g200 * Q22;
+        res.del3 = 3.0 * del1 * f330 * g300 * Q33 * aonv;
+        res.del1 = del1 * f311 * g310 * Q31 * aonv;
+        res.xlamo = (sat.mo + sat.nodeo + sat.argpo - theta) % TWO_PI;
+        res.xfact = sat.mdot + xpidot - RPTIM + res.dmdt + res.domdt + res.dnodt - sat.no;
+    }
+    res
+}
+
+// Deep-space secular effects and resonance integration (Euler-Maclaurin
+// steps of 720 minutes from epoch)
+#[allow(clippy::too_many_arguments)]
+fn dspace(
+    sat: &Sgp4,
+    t: f64,
+    em: &mut f64,
+    argpm: &mut f64,
+    inclm: &mut f64,
+    mm: &mut f64,
+    nodem: &mut f64,
+    nm: &mut f64,
+) {
+    let res = &sat.resonance;
+    let theta = (sat.gsto + t * RPTIM) % TWO_PI;
+    *em += res.dedt * t;
+    *inclm += res.didt * t;
+    *argpm += res.domdt * t;
+    *nodem += res.dnodt * t;
+    *mm += res.dmdt * t;
+
+    if res.irez == 0 {
+        return;
+    }
+    let mut atime = 0.0;
+    let mut xni = sat.no;
+    let mut xli = res.xlamo;
+    let delt = if t > 0.0 { STEPP } else { STEPN };
+
+    let (xndt, xldot, xnddt, ft) = loop {
+        let (xndt, xldot, xnddt);
+        if res.irez != 2 {
+            xndt = res.del1 * (xli - FASX2).sin()
+                + res.del2 * (2.0 * (xli - FASX4)).sin()
+                + res.del3 * (3.0 * (xli - FASX6)).sin();
+            xldot = xni + res.xfact;
+            xnddt = (res.del1 * (xli - FASX2).cos()
+                + 2.0 * res.del2 * (2.0 * (xli - FASX4)).cos()
+                + 3.0 * res.del3 * (3.0 * (xli - FASX6)).cos())
+                * xldot;
+        } else {
+            let xomi = sat.argpo + sat.argpdot * atime;
+            let x2omi = xomi + xomi;
+            let x2li = xli + xli;
+            xndt = res.d2201 * (x2omi + xli - G22).sin()
+                + res.d2211 * (xli - G22).sin()
+                + res.d3210 * (xomi + xli - G32).sin()
+                + res.d3222 * (-xomi + xli - G32).sin()
+                + res.d4410 * (x2omi + x2li - G44).sin()
+                + res.d4422 * (x2li - G44).sin()
+                + res.d5220 * (xomi + xli - G52).sin()
+                + res.d5232 * (-xomi + xli - G52).sin()
+                + res.d5421 * (xomi + x2li - G54).sin()
+                + res.d5433 * (-xomi + x2li - G54).sin();
+            xldot = xni + res.xfact;
+            xnddt = (res.d2201 * (x2omi + xli - G22).cos()
+                + res.d2211 * (xli - G22).cos()
+                + res.d3210 * (xomi + xli - G32).cos()
+                + res.d3222 * (-xomi + xli - G32).cos()
+                + res.d5220 * (xomi + xli - G52).cos()
+                + res.d5232 * (-xomi + xli - G52).cos()
+                + 2.0
+                    * (res.d4410 * (x2omi + x2li - G44).cos()
+                        + res.d4422 * (x2li - G44).cos()
+                        + res.d5421 * (xomi + x2li - G54).cos()
+                        + res.d5433 * (-xomi + x2li - G54).cos()))
+                * xldot;
+        }
+
+        if (t - atime).abs() < STEPP {
+            break (xndt, xldot, xnddt, t - atime);
+        }
+        xli += xldot * delt + xndt * STEP2;
+        xni += xndt * delt + xnddt * STEP2;
+        atime += delt;
+    };
+
+    let nm_resonant = xni + xndt * ft + xnddt * ft * ft * 0.5;
+    let xl = xli + xldot * ft + xndt * ft * ft * 0.5;
+    *mm = if res.irez != 1 { xl - 2.0 * *nodem + 2.0 * theta } else { xl - *nodem - *argpm + theta };
+    *nm = nm_resonant;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tle::parse_tle;
+
+    // Minutes since epoch, then TEME r (km) and v (km/s) from the SGP4-VER
+    // verification output (Vallado et al., AIAA 2006-6753)
+    type Vector = (f64, [f64; 3], [f64; 3]);
+
+    fn check(line1: &str, line2: &str, deep_space: bool, vectors: &[Vector]) {
+        let satellite = Sgp4::from_tle(&parse_tle(None, line1, line2).unwrap()).unwrap();
+        assert_eq!(satellite.is_deep_space(), deep_space);
+        for &(minutes, r, v) in vectors {
+            let state = satellite.propagate(minutes * 60.0).unwrap();
+            let (position, velocity) = (state.position / 1000.0, state.velocity / 1000.0);
+            let position_error = (position - Vector3::new(r[0], r[1], r[2])).norm();
+            let velocity_error = (velocity - Vector3::new(v[0], v[1], v[2])).norm();
+            assert!(position_error < 1e-6, "r off by {:e} km at {} min", position_error, minutes);
+            assert!(velocity_error < 1e-9, "v off by {:e} km/s at {} min", velocity_error, minutes);
+        }
+    }
+
+    #[test]
+    fn near_earth_00005() {
+        check(
+            "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+            "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+            false,
+            &[
+                (0.0, [7022.46529266, -1400.08296755, 0.03995155], [1.893841015, 6.405893759, 4.534807250]),
+                (360.0, [-7154.03120202, -3783.17682504, -3536.19412294], [4.741887409, -4.151817765, -2.093935425]),
+                (720.0, [-7134.59340119, 6531.68641334, 3260.27186483], [-4.113793027, -2.911922039, -2.557327851]),
+                (1080.0, [5568.53901181, 4492.06992591, 3863.87641983], [-4.209106476, 5.159719888, 2.744852980]),
+                (1440.0, [-938.55923943, -6268.18748831, -4294.02924751], [7.536105209, -0.427127707, 0.989878080]),
+                (2880.0, [-8650.73082219, -1914.93811525, -3007.03603443], [3.067165127, -4.828384068, -2.515322836]),
+                (4320.0, [-9060.47373569, 4658.70952502, 813.68673153], [-2.232832783, -4.110453490, -3.157345433]),
+            ],
+        );
+    }
+
+    #[test]
+    fn deep_space_11801() {
+        check(
+            "1 11801U          80230.29629788  .01431103  00000-0  14311-1 0    13",
+            "2 11801  46.7916 230.4354 7318036  47.4722  10.4117  2.28537848    13",
+            true,
+            &[
+                (0.0, [7473.37102491, 428.94748312, 5828.74846783], [5.107155391, 6.444680305, -0.186133297]),
+                (720.0, [14271.29083858, 24110.44309009, -4725.76320143], [-0.320504528, 2.679841539, -2.084054355]),
+                (1440.0, [9787.87836256, 33753.32249667, -15030.79874625], [-1.094251553, 0.923589906, -1.522311008]),
+            ],
+        );
+    }
+
+    fn resonance(line1: &str, line2: &str) -> u8 {
+        Sgp4::from_tle(&parse_tle(None, line1, line2).unwrap()).unwrap().resonance.irez
+    }
+
+    // Half-day (12 hour) resonance, Molniya orbits
+    #[test]
+    fn half_day_resonance_09880() {
+        let (line1, line2) = (
+            "1 09880U 77021A   06176.56157475  .00000421  00000-0  10000-3 0  9814",
+            "2 09880  64.5968 349.3786 7069051 270.0229  16.3320  2.00813614112380",
+        );
+        assert_eq!(resonance(line1, line2), 2);
+        check(
+            line1,
+            line2,
+            true,
+            &[
+                (0.0, [13020.06750784, -2449.07193500, 1.15896030], [4.247363935, 1.597178501, 4.956708611]),
+                (360.0, [328.74217398, 19554.92047380, 40558.26246145], [-1.593281066, 0.126772913, -0.359627307]),
+                (720.0, [13725.09398980, -2180.70877090, 863.29684523], [3.878478111, 1.656846496, 4.944867241]),
+                (1440.0, [14369.90303735, -1903.85601062, 1722.15319852], [3.543393116, 1.701687176, 4.913881358]),
+                (2880.0, [15500.53445068, -1332.90981042, 3419.72315308], [2.960917974, 1.758331634, 4.813698638]),
+            ],
+        );
+    }
+
+    #[test]
+    fn half_day_resonance_08195() {
+        let (line1, line2) = (
+            "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+            "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+        );
+        assert_eq!(resonance(line1, line2), 2);
+        check(
+            line1,
+            line2,
+            true,
+            &[
+                (0.0, [2349.89483350, -14785.93811562, 0.02119378], [2.721488096, -3.256811655, 4.498416672]),
+                (1440.0, [2890.80638268, -15446.43952300, 948.77010176], [2.654407490, -2.909344895, 4.486437362]),
+                (2880.0, [3417.20931586, -16038.79510665, 1894.74934058], [2.585515864, -2.596818146, 4.456882556]),
+            ],
+        );
+    }
+
+    // One-day (synchronous) resonance, geostationary orbit
+    #[test]
+    fn one_day_resonance_28626() {
+        let (line1, line2) = (
+            "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190",
+            "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891",
+        );
+        assert_eq!(resonance(line1, line2), 1);
+        check(
+            line1,
+            line2,
+            true,
+            &[
+                (0.0, [42080.71852213, -2646.86387436, 0.81851294], [0.193105177, 3.068688251, 0.000438449]),
+                (720.0, [-42103.20138132, 2291.06228893, -0.13274964], [-0.166974816, -3.070104560, -0.000311007]),
+                (1440.0, [42119.96263499, -1925.77567263, -0.19827433], [0.140521206, 3.071541613, 0.000179561]),
+            ],
+        );
+    }
+
+    // One-day resonance integrated backwards from the epoch
+    #[test]
+    fn one_day_resonance_backwards_09998() {
+        let (line1, line2) = (
+            "1 09998U 74033F   05148.79417928 -.00000112  00000-0  00000+0 0  4480",
+            "2 09998   9.4958 313.1750 0270971 327.5225  30.8097  1.16186785 45878",
+        );
+        assert_eq!(resonance(line1, line2), 1);
+        check(
+            line1,
+            line2,
+            true,
+            &[
+                (-1440.0, [-11362.18265118, -35117.55867813, -5413.62537994], [3.137861261, -1.011678260, 0.267510059]),
+                (-720.0, [-8535.81598158, 38171.79073851, 3331.00311285], [-3.043839958, -0.644462527, -0.445808894]),
+            ],
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// Multi-stage launch vehicle performance
+//
+// Extends the single-stage `TsiolkovskyRocket` calculation to serial
+// staging: each stage burns its propellant with its own specific impulse and
+// is dropped at burnout. Stages are listed bottom (first to fire) to top.
+use crate::CalculationError;
+use std::fmt;
+
+// Standard gravity used to convert specific impulse to exhaust velocity, m/s^2
+pub const G0: f64 = 9.80665;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stage {
+    pub dry_mass: f64,        // kg
+    pub propellant_mass: f64, // kg
+    pub isp: f64,             // s
+}
+
+impl Stage {
+    pub fn new(dry_mass: f64, propellant_mass: f64, isp: f64) -> Result<Self, CalculationError> {
+        if dry_mass < 0.0 || propellant_mass <= 0.0 || isp <= 0.0 || dry_mass + propellant_mass <= 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(Stage { dry_mass, propellant_mass, isp })
+    }
+
+    pub fn exhaust_velocity(&self) -> f64 {
+        self.isp * G0
+    }
+
+    pub fn total_mass(&self) -> f64 {
+        self.dry_mass + self.propellant_mass
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vehicle {
+    pub stages: Vec<Stage>,
+    pub payload: f64, // kg
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StagePerformance {
+    pub ignition_mass: f64, // kg, everything still attached when the stage lights
+    pub burnout_mass: f64,  // kg, just before the stage is dropped
+    pub delta_v: f64,       // m/s
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehiclePerformance {
+    pub stages: Vec<StagePerformance>,
+    pub gross_mass: f64, // kg at liftoff
+    pub payload: f64,    // kg
+}
+
+impl VehiclePerformance {
+    pub fn total_delta_v(&self) -> f64 {
+        self.stages.iter().map(|s| s.delta_v).sum()
+    }
+
+    pub fn payload_fraction(&self) -> f64 {
+        self.payload / self.gross_mass
+    }
+}
+
+impl fmt::Display for VehiclePerformance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<6} {:>14} {:>14} {:>12}", "Stage", "Ignition kg", "Burnout kg", "Delta-v m/s")?;
+        for (i, stage) in self.stages.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<6} {:>14.1} {:>14.1} {:>12.1}",
+                i + 1,
+                stage.ignition_mass,
+                stage.burnout_mass,
+                stage.delta_v
+            )?;
+        }
+        writeln!(f, "Total delta-v:    {:.1} m/s", self.total_delta_v())?;
+        writeln!(f, "Gross mass:       {:.1} kg", self.gross_mass)?;
+        write!(f, "Payload fraction: {:.4} ({:.2}%)", self.payload_fraction(), self.payload_fraction() * 100.0)
+    }
+}
+
+impl Vehicle {
+    pub fn new(stages: Vec<Stage>, payload: f64) -> Result<Self, CalculationError> {
+        if stages.is_empty() || payload < 0.0 {
+            return Err(CalculationError::InvalidInput);
+        }
+        // The fields are public, so stages may not have come through `Stage::new`
+        for stage in &stages {
+            Stage::new(stage.dry_mass, stage.propellant_mass, stage.isp)?;
+        }
+        Ok(Vehicle { stages, payload })
+    }
+
+    pub fn gross_mass(&self) -> f64 {
+        self.payload + self.stages.iter().map(|s| s.total_mass()).sum::<f64>()
+    }
+
+    pub fn performance(&self) -> VehiclePerformance {
+        let mut ignition_mass = self.gross_mass();
+        let mut stages = Vec::with_capacity(self.stages.len());
+
+        for stage in &self.stages {
+            let burnout_mass = ignition_mass - stage.propellant_mass;
+            let delta_v = stage.exhaust_velocity() * (ignition_mass / burnout_mass).ln();
+            stages.push(StagePerformance { ignition_mass, burnout_mass, delta_v });
+            ignition_mass = burnout_mass - stage.dry_mass;
+        }
+
+        VehiclePerformance { stages, gross_mass: self.gross_mass(), payload: self.payload }
+    }
+}
+
+// Stage technology used when sizing a vehicle: structural ratio
+// dry / (dry + propellant) and specific impulse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageDesign {
+    pub structural_ratio: f64,
+    pub isp: f64, // s
+}
+
+// Size the stages that reach `target_delta_v` (m/s) with the smallest
+// gross mass for the given payload (Lagrange multiplier method, Curtis
+// "Orbital Mechanics for Engineering Students", section 11.6).
+pub fn optimal_staging(designs: &[StageDesign], payload: f64, target_delta_v: f64) -> Result<Vehicle, CalculationError> {
+    if designs.is_empty() || payload <= 0.0 || target_delta_v <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    if designs.iter().any(|d| !(d.structural_ratio > 0.0 && d.structural_ratio < 1.0) || d.isp <= 0.0) {
+        return Err(CalculationError::InvalidInput);
+    }
+
+    let c: Vec<f64> = designs.iter().map(|d| d.isp * G0).collect();
+    let eps: Vec<f64> = designs.iter().map(|d| d.structural_ratio).collect();
+
+    // Even with infinite gross mass each stage cannot beat c ln(1 / eps)
+    let max_delta_v: f64 = c.iter().zip(&eps).map(|(c, e)| c * (1.0 / e).ln()).sum();
+    if target_delta_v >= max_delta_v {
+        return Err(CalculationError::OutOfRange);
+    }
+
+    // Stage mass ratio for multiplier eta; delta-v grows monotonically with eta
+    let mass_ratio = |i: usize, eta: f64| (c[i] * eta - 1.0) / (c[i] * eps[i] * eta);
+    let delta_v = |eta: f64| (0..c.len()).map(|i| c[i] * mass_ratio(i, eta).ln()).sum::<f64>();
+
+    let c_min = c.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mut low = 1.0 / c_min;
+    let mut high = 2.0 / c_min;
+    while delta_v(high) < target_delta_v {
+        high *= 2.0;
+        if !high.is_finite() {
+            return Err(CalculationError::OutOfRange);
+        }
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (low + high);
+        if delta_v(mid) < target_delta_v {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    let eta = 0.5 * (low + high);
+
+    // A stage with a mass ratio at or below 1 would need negative mass: the
+    // other stages reach the target better without it
+    if (0..c.len()).any(|i| mass_ratio(i, eta) <= 1.0) {
+        return Err(CalculationError::OutOfRange);
+    }
+
+    // Build the stack from the top down: each stage carries everything above it
+    let mut stages = Vec::with_capacity(designs.len());
+    let mut carried = payload;
+    for i in (0..designs.len()).rev() {
+        let n = mass_ratio(i, eta);
+        let stage_mass = (n - 1.0) / (1.0 - n * eps[i]) * carried;
+        stages.push(Stage::new(eps[i] * stage_mass, (1.0 - eps[i]) * stage_mass, designs[i].isp)?);
+        carried += stage_mass;
+    }
+    stages.reverse();
+
+    Vehicle::new(stages, payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimal_stages_reach_the_target() {
+        let designs = [
+            StageDesign { structural_ratio: 0.15, isp: 300.0 },
+            StageDesign { structural_ratio: 0.10, isp: 350.0 },
+        ];
+        let vehicle = optimal_staging(&designs, 1000.0, 9000.0).unwrap();
+        let performance = vehicle.performance();
+        assert!((performance.total_delta_v() - 9000.0).abs() < 1e-6);
+        for (stage, design) in vehicle.stages.iter().zip(&designs) {
+            assert!((stage.dry_mass / stage.total_mass() - design.structural_ratio).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rejects_a_stage_with_negative_mass() {
+        // The efficient stage does the whole job, and the Lagrange solution
+        // gives the other one a mass ratio below 1
+        let designs = [
+            StageDesign { structural_ratio: 0.1, isp: 450.0 },
+            StageDesign { structural_ratio: 0.3, isp: 200.0 },
+        ];
+        assert_eq!(optimal_staging(&designs, 1000.0, 1000.0), Err(CalculationError::OutOfRange));
+        let reversed = [designs[1], designs[0]];
+        assert_eq!(optimal_staging(&reversed, 1000.0, 1000.0), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn vehicle_checks_every_stage() {
+        let good = Stage::new(100.0, 900.0, 300.0).unwrap();
+        let negative = Stage { dry_mass: -292.7, propellant_mass: -682.9, isp: 200.0 };
+        assert_eq!(Vehicle::new(vec![good, negative], 1000.0), Err(CalculationError::InvalidInput));
+        assert!(Vehicle::new(vec![good], 1000.0).is_ok());
+    }
+}
@@ -0,0 +1,315 @@
+// Spacecraft radiative thermal analysis
+//
+// A surface absorbs direct sunlight and Earth-reflected sunlight (albedo)
+// with its solar absorptivity, and Earth infrared with its emissivity (an
+// IR absorber is as good an emitter), on top of any internal dissipation.
+// It radiates to deep space from its radiating area, so in equilibrium
+//
+//     alpha (S A_sun + a S F A_earth) + eps q_IR F A_earth + Q = eps sigma A_rad T^4
+//
+// The transient solver treats the spacecraft as lumped nodes with a heat
+// capacity each, joined by conductors (W/K) and radiative exchange factors
+// (m^2), and integrates the node temperatures over sunlit and eclipse
+// phases of the orbit with a fourth-order Runge-Kutta scheme.
+//
+// Temperatures are returned as the `Kelvin` type shared with the
+// SpaceSensor temperature converter.
+use crate::CalculationError;
+use crate::power::SOLAR_CONSTANT;
+use spacesensor_temperature_converter::Kelvin;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+pub const STEFAN_BOLTZMANN: f64 = 5.670374419e-8; // W/m^2/K^4
+pub const EARTH_ALBEDO: f64 = 0.30;
+pub const EARTH_IR: f64 = 237.0; // W/m^2, mean outgoing longwave radiation
+
+// Fraction of the explicit stability limit used for the integration step
+const STEP_SAFETY: f64 = 0.5;
+
+// Radiation environment of an Earth orbit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalEnvironment {
+    pub solar_flux: f64,  // W/m^2
+    pub albedo: f64,      // fraction of sunlight reflected by Earth
+    pub earth_ir: f64,    // W/m^2 emitted by Earth
+    pub view_factor: f64, // view factor from the Earth-facing area to Earth
+}
+
+impl ThermalEnvironment {
+    // Mean values at 1 AU with a nadir-facing plate at `altitude` (m)
+    pub fn earth_orbit(altitude: f64, radius: f64) -> Self {
+        ThermalEnvironment {
+            solar_flux: SOLAR_CONSTANT,
+            albedo: EARTH_ALBEDO,
+            earth_ir: EARTH_IR,
+            view_factor: earth_view_factor(altitude, radius),
+        }
+    }
+}
+
+// View factor from a flat plate facing nadir to a spherical planet
+pub fn earth_view_factor(altitude: f64, radius: f64) -> f64 {
+    let ratio = radius / (radius + altitude.max(0.0));
+    ratio * ratio
+}
+
+// Optical properties and areas (m^2) of a surface or node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Surface {
+    pub absorptivity: f64,
+    pub emissivity: f64,
+    pub sun_area: f64,       // projected area facing the Sun
+    pub earth_area: f64,     // area facing Earth
+    pub radiating_area: f64, // area radiating to space
+}
+
+impl Surface {
+    fn validate(&self) -> Result<(), CalculationError> {
+        if !(0.0..=1.0).contains(&self.absorptivity) || !(0.0..=1.0).contains(&self.emissivity) {
+            return Err(CalculationError::OutOfRange);
+        }
+        if self.sun_area < 0.0 || self.earth_area < 0.0 || self.radiating_area < 0.0 {
+            return Err(CalculationError::NegativeValue);
+        }
+        Ok(())
+    }
+
+    // Environmental heat absorbed in W; Earth IR reaches the night side
+    // too, sunlight and albedo only the sunlit part of the orbit
+    pub fn absorbed_heat(&self, environment: &ThermalEnvironment, sunlit: bool) -> f64 {
+        let infrared = self.emissivity * environment.earth_ir * environment.view_factor * self.earth_area;
+        if !sunlit {
+            return infrared;
+        }
+        let solar = environment.solar_flux * self.sun_area;
+        let albedo = environment.albedo * environment.solar_flux * environment.view_factor * self.earth_area;
+        self.absorptivity * (solar + albedo) + infrared
+    }
+
+    // Heat radiated to space at `temperature`, W
+    pub fn emitted_heat(&self, temperature: f64) -> f64 {
+        self.emissivity * STEFAN_BOLTZMANN * self.radiating_area * temperature.powi(4)
+    }
+}
+
+// Temperature at which the surface radiates what it absorbs plus
+// `dissipation` (W) of internal heat
+pub fn equilibrium_temperature(
+    surface: &Surface,
+    environment: &ThermalEnvironment,
+    dissipation: f64,
+    sunlit: bool,
+) -> Result<Kelvin, CalculationError> {
+    surface.validate()?;
+    if dissipation < 0.0 {
+        return Err(CalculationError::NegativeValue);
+    }
+    let radiating = surface.emissivity * STEFAN_BOLTZMANN * surface.radiating_area;
+    if radiating <= 0.0 {
+        return Err(CalculationError::DivisionByZero);
+    }
+    let heat = surface.absorbed_heat(environment, sunlit) + dissipation;
+    Ok(Kelvin((heat / radiating).powf(0.25)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalNode {
+    pub name: String,
+    pub capacitance: f64, // J/K, mass times specific heat
+    pub surface: Surface,
+    pub dissipation: f64, // W
+    pub initial_temperature: Kelvin,
+}
+
+// Conduction between two nodes (indices into the node list)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conductor {
+    pub from: usize,
+    pub to: usize,
+    pub conductance: f64, // W/K
+}
+
+// Radiative exchange between two nodes, with the exchange factor
+// eps A F (m^2) folding in emissivities, area and view factor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadiativeLink {
+    pub from: usize,
+    pub to: usize,
+    pub exchange_factor: f64, // m^2
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalModel {
+    pub nodes: Vec<ThermalNode>,
+    pub conductors: Vec<Conductor>,
+    pub radiative_links: Vec<RadiativeLink>,
+    pub environment: ThermalEnvironment,
+    pub orbit_period: f64,     // s
+    pub eclipse_fraction: f64, // fraction of each orbit in shadow, at its end
+}
+
+impl ThermalModel {
+    fn validate(&self) -> Result<(), CalculationError> {
+        if self.nodes.is_empty() || self.orbit_period <= 0.0 || !(0.0..1.0).contains(&self.eclipse_fraction) {
+            return Err(CalculationError::InvalidInput);
+        }
+        for node in &self.nodes {
+            node.surface.validate()?;
+            if node.capacitance <= 0.0 || !node.initial_temperature.is_valid() {
+                return Err(CalculationError::InvalidInput);
+            }
+        }
+        let valid = |from: usize, to: usize| from < self.nodes.len() && to < self.nodes.len() && from != to;
+        if !self.conductors.iter().all(|c| valid(c.from, c.to) && c.conductance >= 0.0)
+            || !self.radiative_links.iter().all(|r| valid(r.from, r.to) && r.exchange_factor >= 0.0)
+        {
+            return Err(CalculationError::InvalidInput);
+        }
+        Ok(())
+    }
+
+    pub fn is_sunlit(&self, time: f64) -> bool {
+        time.rem_euclid(self.orbit_period) < self.orbit_period * (1.0 - self.eclipse_fraction)
+    }
+
+    // Net heat flow into every node, W
+    fn heat_flows(&self, temperatures: &[f64], sunlit: bool) -> Vec<f64> {
+        let mut flows: Vec<f64> = self
+            .nodes
+            .iter()
+            .zip(temperatures)
+            .map(|(node, &t)| {
+                node.surface.absorbed_heat(&self.environment, sunlit) + node.dissipation - node.surface.emitted_heat(t)
+            })
+            .collect();
+        for c in &self.conductors {
+            let q = c.conductance * (temperatures[c.to] - temperatures[c.from]);
+            flows[c.from] += q;
+            flows[c.to] -= q;
+        }
+        for r in &self.radiative_links {
+            let q = STEFAN_BOLTZMANN * r.exchange_factor * (temperatures[r.to].powi(4) - temperatures[r.from].powi(4));
+            flows[r.from] += q;
+            flows[r.to] -= q;
+        }
+        flows
+    }
+
+    // Largest stable explicit step: a fraction of the shortest node time
+    // constant C / (sum of linearized conductances)
+    fn stable_step(&self, temperatures: &[f64]) -> f64 {
+        let mut conductance: Vec<f64> = self
+            .nodes
+            .iter()
+            .zip(temperatures)
+            .map(|(node, &t)| 4.0 * node.surface.emissivity * STEFAN_BOLTZMANN * node.surface.radiating_area * t.powi(3))
+            .collect();
+        for c in &self.conductors {
+            conductance[c.from] += c.conductance;
+            conductance[c.to] += c.conductance;
+        }
+        for r in &self.radiative_links {
+            let t = temperatures[r.from].max(temperatures[r.to]);
+            let linear = 4.0 * STEFAN_BOLTZMANN * r.exchange_factor * t.powi(3);
+            conductance[r.from] += linear;
+            conductance[r.to] += linear;
+        }
+        self.nodes
+            .iter()
+            .zip(&conductance)
+            .filter(|(_, g)| **g > 0.0)
+            .map(|(node, &g)| STEP_SAFETY * node.capacitance / g)
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransientPoint {
+    pub time: f64, // s since the start
+    pub sunlit: bool,
+    pub temperatures: Vec<Kelvin>,
+}
+
+// Node temperatures from the initial state for `duration` seconds, one
+// point every `output_step`. Steps never straddle a sunlit/eclipse switch.
+pub fn simulate_transient(
+    model: &ThermalModel,
+    duration: f64,
+    output_step: f64,
+) -> Result<Vec<TransientPoint>, CalculationError> {
+    model.validate()?;
+    if duration <= 0.0 || output_step <= 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    let daylight = model.orbit_period * (1.0 - model.eclipse_fraction);
+    let next_switch = |t: f64| {
+        let orbit_start = (t / model.orbit_period).floor() * model.orbit_period;
+        if t - orbit_start < daylight { orbit_start + daylight } else { orbit_start + model.orbit_period }
+    };
+    let record = |time: f64, temperatures: &[f64]| TransientPoint {
+        time,
+        sunlit: model.is_sunlit(time),
+        temperatures: temperatures.iter().map(|&t| Kelvin(t)).collect(),
+    };
+
+    let mut temperatures: Vec<f64> = model.nodes.iter().map(|n| n.initial_temperature.0).collect();
+    let mut points = vec![record(0.0, &temperatures)];
+    let mut time = 0.0;
+    let mut next_output = output_step.min(duration);
+
+    while time < duration - 1e-9 {
+        let sunlit = model.is_sunlit(time);
+        let h = model.stable_step(&temperatures).min(next_output - time).min(next_switch(time) - time).max(1e-6);
+
+        let derivative = |temps: &[f64]| -> Vec<f64> {
+            model.heat_flows(temps, sunlit).iter().zip(&model.nodes).map(|(q, node)| q / node.capacitance).collect()
+        };
+        let shifted = |k: &[f64], factor: f64| -> Vec<f64> {
+            temperatures.iter().zip(k).map(|(t, d)| (t + factor * d).max(0.0)).collect()
+        };
+        let k1 = derivative(&temperatures);
+        let k2 = derivative(&shifted(&k1, 0.5 * h));
+        let k3 = derivative(&shifted(&k2, 0.5 * h));
+        let k4 = derivative(&shifted(&k3, h));
+        for i in 0..temperatures.len() {
+            temperatures[i] = (temperatures[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i])).max(0.0);
+        }
+        if !temperatures.iter().all(|t| t.is_finite()) {
+            return Err(CalculationError::OutOfRange);
+        }
+
+        time += h;
+        if time >= next_output - 1e-9 {
+            points.push(record(time, &temperatures));
+            next_output = (next_output + output_step).min(duration);
+        }
+    }
+    Ok(points)
+}
+
+// CSV with one row per output time and one column per node in K
+pub fn transient_to_csv(nodes: &[ThermalNode], points: &[TransientPoint]) -> String {
+    let mut csv = String::from("time_s,sunlit");
+    for node in nodes {
+        let _ = write!(csv, ",{}_k", node.name.replace([',', '"', '\n'], "_"));
+    }
+    csv.push('\n');
+    for point in points {
+        let _ = write!(csv, "{},{}", point.time, point.sunlit);
+        for temperature in &point.temperatures {
+            let _ = write!(csv, ",{:.3}", temperature.0);
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn write_transient_csv<P: AsRef<Path>>(
+    nodes: &[ThermalNode],
+    points: &[TransientPoint],
+    path: P,
+) -> std::io::Result<()> {
+    fs::write(path, transient_to_csv(nodes, points))
+}
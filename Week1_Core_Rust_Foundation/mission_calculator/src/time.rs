@@ -0,0 +1,655 @@
+// Astronomical time scales and epochs
+//
+// An `Epoch` is one instant, held as a TAI day number (MJD) and the seconds
+// into that day so nanoseconds survive over centuries. It converts to and
+// from the time scales used in mission analysis:
+//
+// - UTC: civil time, TAI minus the leap seconds of the IERS table below.
+//   During a leap second the clock reads 23:59:60.
+// - TAI: International Atomic Time, uniform.
+// - TT: Terrestrial Time, TAI + 32.184 s, the argument of the precession
+//   and nutation series.
+// - TDB: Barycentric Dynamical Time, TT plus a periodic term under 2 ms
+//   (Vallado, eq. 3-47).
+// - GPS: GPS system time, TAI - 19 s, also given as week and seconds of week.
+//
+// Epochs are written and read as ISO 8601 calendar and ordinal dates, which
+// are also the CCSDS ASCII time codes A and B (CCSDS 301.0-B-4), and as the
+// binary CCSDS unsegmented (CUC) and day segmented (CDS) codes. Leap seconds
+// before 1972, when UTC ran at a different rate, are not modeled: earlier
+// epochs use the 1972 offset.
+use crate::CalculationError;
+use crate::ephemeris::J2000_JD;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+pub const MJD_OFFSET: f64 = 2400000.5; // JD of MJD 0
+pub const TT_MINUS_TAI: f64 = 32.184; // s
+pub const TAI_MINUS_GPS: f64 = 19.0; // s
+pub const GPS_EPOCH_MJD: i64 = 44244; // 1980-01-06
+pub const CCSDS_EPOCH_MJD: i64 = 36204; // 1958-01-01
+pub const UNIX_EPOCH_MJD: i64 = 40587; // 1970-01-01
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+const SECONDS_PER_WEEK: f64 = 7.0 * SECONDS_PER_DAY;
+
+// UTC day (MJD) from which TAI-UTC takes the given value, in s
+const LEAP_SECONDS: [(i64, f64); 28] = [
+    (41317, 10.0), // 1972-01-01
+    (41499, 11.0), // 1972-07-01
+    (41683, 12.0), // 1973-01-01
+    (42048, 13.0), // 1974-01-01
+    (42413, 14.0), // 1975-01-01
+    (42778, 15.0), // 1976-01-01
+    (43144, 16.0), // 1977-01-01
+    (43509, 17.0), // 1978-01-01
+    (43874, 18.0), // 1979-01-01
+    (44239, 19.0), // 1980-01-01
+    (44786, 20.0), // 1981-07-01
+    (45151, 21.0), // 1982-07-01
+    (45516, 22.0), // 1983-07-01
+    (46247, 23.0), // 1985-07-01
+    (47161, 24.0), // 1988-01-01
+    (47892, 25.0), // 1990-01-01
+    (48257, 26.0), // 1991-01-01
+    (48804, 27.0), // 1992-07-01
+    (49169, 28.0), // 1993-07-01
+    (49534, 29.0), // 1994-07-01
+    (50083, 30.0), // 1996-01-01
+    (50630, 31.0), // 1997-07-01
+    (51179, 32.0), // 1999-01-01
+    (53736, 33.0), // 2006-01-01
+    (54832, 34.0), // 2009-01-01
+    (56109, 35.0), // 2012-07-01
+    (57204, 36.0), // 2015-07-01
+    (57754, 37.0), // 2017-01-01
+];
+
+// TAI-UTC in s on a UTC Modified Julian Date
+pub fn tai_minus_utc(mjd: f64) -> f64 {
+    let day = mjd.floor() as i64;
+    LEAP_SECONDS.iter().rev().find(|(start, _)| day >= *start).map_or(LEAP_SECONDS[0].1, |&(_, dat)| dat)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeScale {
+    Utc,
+    Tai,
+    Tt,
+    Tdb,
+    Gps,
+}
+
+impl TimeScale {
+    pub const ALL: [TimeScale; 5] = [TimeScale::Utc, TimeScale::Tai, TimeScale::Tt, TimeScale::Tdb, TimeScale::Gps];
+}
+
+impl fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TimeScale::Utc => "UTC",
+            TimeScale::Tai => "TAI",
+            TimeScale::Tt => "TT",
+            TimeScale::Tdb => "TDB",
+            TimeScale::Gps => "GPS",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub fn parse_time_scale(text: &str) -> Result<TimeScale, CalculationError> {
+    match text.trim().to_ascii_uppercase().as_str() {
+        "UTC" | "Z" => Ok(TimeScale::Utc),
+        "TAI" => Ok(TimeScale::Tai),
+        "TT" | "TDT" => Ok(TimeScale::Tt),
+        "TDB" => Ok(TimeScale::Tdb),
+        "GPS" | "GPST" => Ok(TimeScale::Gps),
+        _ => Err(CalculationError::InvalidInput),
+    }
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date (Hinnant's
+// days_from_civil)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// Year, month and day of a count of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// Length of a UTC day in s, 86401 when it ends with a leap second
+fn utc_day_length(mjd: i64) -> f64 {
+    SECONDS_PER_DAY + tai_minus_utc((mjd + 1) as f64) - tai_minus_utc(mjd as f64)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Calendar date and time of day in one time scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalendarTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub day_of_year: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: f64, // up to 60.999... during a UTC leap second
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Epoch {
+    day: i64,     // MJD in TAI
+    seconds: f64, // TAI seconds into the day, in [0, 86400)
+}
+
+impl Epoch {
+    fn from_tai_parts(day: i64, seconds: f64) -> Self {
+        let carry = (seconds / SECONDS_PER_DAY).floor();
+        Epoch { day: day + carry as i64, seconds: seconds - carry * SECONDS_PER_DAY }
+    }
+
+    // `seconds` into the day `day` (MJD) of the scale. For UTC, `seconds`
+    // may reach 86401 on a day that ends with a leap second.
+    pub fn from_day_seconds(day: i64, seconds: f64, scale: TimeScale) -> Self {
+        match scale {
+            TimeScale::Utc => Epoch::from_tai_parts(day, seconds + tai_minus_utc(day as f64)),
+            TimeScale::Tai => Epoch::from_tai_parts(day, seconds),
+            TimeScale::Tt => Epoch::from_tai_parts(day, seconds - TT_MINUS_TAI),
+            TimeScale::Gps => Epoch::from_tai_parts(day, seconds + TAI_MINUS_GPS),
+            TimeScale::Tdb => {
+                // The periodic term is evaluated at TDB, which differs from
+                // TT by under 2 ms
+                let tt = Epoch::from_tai_parts(day, seconds - TT_MINUS_TAI);
+                tt - tt.tdb_minus_tt()
+            }
+        }
+    }
+
+    pub fn from_mjd(mjd: f64, scale: TimeScale) -> Self {
+        let day = mjd.floor();
+        Epoch::from_day_seconds(day as i64, (mjd - day) * SECONDS_PER_DAY, scale)
+    }
+
+    pub fn from_julian_date(julian_date: f64, scale: TimeScale) -> Self {
+        Epoch::from_mjd(julian_date - MJD_OFFSET, scale)
+    }
+
+    pub fn from_calendar(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: f64,
+        scale: TimeScale,
+    ) -> Result<Self, CalculationError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) || hour > 23 || minute > 59 {
+            return Err(CalculationError::OutOfRange);
+        }
+        let mjd = days_from_civil(year, month, day) + UNIX_EPOCH_MJD;
+        // Only the last minute of a UTC day that ends with a leap second
+        // reaches 60 s
+        let leap = scale == TimeScale::Utc && hour == 23 && minute == 59 && utc_day_length(mjd) > SECONDS_PER_DAY;
+        if !(0.0..if leap { 61.0 } else { 60.0 }).contains(&second) {
+            return Err(CalculationError::OutOfRange);
+        }
+        let seconds = hour as f64 * 3600.0 + minute as f64 * 60.0 + second;
+        Ok(Epoch::from_day_seconds(mjd, seconds, scale))
+    }
+
+    pub fn from_day_of_year(year: i64, day_of_year: f64, scale: TimeScale) -> Result<Self, CalculationError> {
+        let days = if is_leap_year(year) { 366.0 } else { 365.0 };
+        if !(1.0..days + 1.0).contains(&day_of_year) {
+            return Err(CalculationError::OutOfRange);
+        }
+        let first = days_from_civil(year, 1, 1) + UNIX_EPOCH_MJD;
+        let whole = day_of_year.floor();
+        Ok(Epoch::from_day_seconds(first + whole as i64 - 1, (day_of_year - whole) * SECONDS_PER_DAY, scale))
+    }
+
+    // UTC as Unix time, which has no leap seconds
+    pub fn from_unix(seconds: f64) -> Self {
+        let days = (seconds / SECONDS_PER_DAY).floor();
+        Epoch::from_day_seconds(UNIX_EPOCH_MJD + days as i64, seconds - days * SECONDS_PER_DAY, TimeScale::Utc)
+    }
+
+    pub fn from_gps_week(week: u32, seconds_of_week: f64) -> Self {
+        Epoch::from_day_seconds(GPS_EPOCH_MJD + 7 * week as i64, seconds_of_week, TimeScale::Gps)
+    }
+
+    // TDB-TT in s (Vallado, eq. 3-47)
+    pub fn tdb_minus_tt(&self) -> f64 {
+        let tt = self.day as f64 + (self.seconds + TT_MINUS_TAI) / SECONDS_PER_DAY;
+        let t = (tt + MJD_OFFSET - J2000_JD) / 36525.0;
+        let mean_anomaly = (357.5277233 + 35999.05034 * t).to_radians();
+        0.001657 * mean_anomaly.sin() + 0.00001385 * (2.0 * mean_anomaly).sin()
+    }
+
+    // Day (MJD) and seconds into the day in a time scale. In UTC the
+    // seconds run past 86400 during a leap second.
+    pub fn day_seconds(&self, scale: TimeScale) -> (i64, f64) {
+        let shifted = |offset: f64| {
+            let e = Epoch::from_tai_parts(self.day, self.seconds + offset);
+            (e.day, e.seconds)
+        };
+        match scale {
+            TimeScale::Tai => (self.day, self.seconds),
+            TimeScale::Tt => shifted(TT_MINUS_TAI),
+            TimeScale::Tdb => shifted(TT_MINUS_TAI + self.tdb_minus_tt()),
+            TimeScale::Gps => shifted(-TAI_MINUS_GPS),
+            TimeScale::Utc => {
+                // Latest offset whose start, in TAI, is not after this instant
+                let index =
+                    LEAP_SECONDS.iter().rposition(|&(start, dat)| (start, dat) <= (self.day, self.seconds)).unwrap_or(0);
+                let (day, seconds) = shifted(-LEAP_SECONDS[index].1);
+                match LEAP_SECONDS.get(index + 1) {
+                    // Between the end of the last UTC day and the new offset:
+                    // the leap second itself
+                    Some(&(next, _)) if day >= next => (day - 1, seconds + SECONDS_PER_DAY),
+                    _ => (day, seconds),
+                }
+            }
+        }
+    }
+
+    pub fn mjd(&self, scale: TimeScale) -> f64 {
+        let (day, seconds) = self.day_seconds(scale);
+        // A leap second is folded into the last second of its day
+        day as f64 + seconds.min(SECONDS_PER_DAY - 1e-9) / SECONDS_PER_DAY
+    }
+
+    pub fn julian_date(&self, scale: TimeScale) -> f64 {
+        self.mjd(scale) + MJD_OFFSET
+    }
+
+    // Julian centuries since J2000 in a time scale
+    pub fn centuries_since_j2000(&self, scale: TimeScale) -> f64 {
+        (self.julian_date(scale) - J2000_JD) / 36525.0
+    }
+
+    pub fn calendar(&self, scale: TimeScale) -> CalendarTime {
+        let (day, seconds) = self.day_seconds(scale);
+        calendar_from_day_seconds(day, seconds)
+    }
+
+    // Year and fractional day of year, 1.0 at the start of January 1
+    pub fn day_of_year(&self, scale: TimeScale) -> (i64, f64) {
+        let (day, seconds) = self.day_seconds(scale);
+        let calendar = calendar_from_day_seconds(day, seconds);
+        let fraction = seconds.min(SECONDS_PER_DAY - 1e-9) / SECONDS_PER_DAY;
+        (calendar.year, calendar.day_of_year as f64 + fraction)
+    }
+
+    pub fn gps_week(&self) -> (u32, f64) {
+        let (day, seconds) = self.day_seconds(TimeScale::Gps);
+        let elapsed = (day - GPS_EPOCH_MJD) as f64 * SECONDS_PER_DAY + seconds;
+        let week = (elapsed / SECONDS_PER_WEEK).floor();
+        (week.max(0.0) as u32, elapsed - week * SECONDS_PER_WEEK)
+    }
+
+    pub fn unix_seconds(&self) -> f64 {
+        let (day, seconds) = self.day_seconds(TimeScale::Utc);
+        (day - UNIX_EPOCH_MJD) as f64 * SECONDS_PER_DAY + seconds.min(SECONDS_PER_DAY)
+    }
+
+    // ISO 8601 calendar date and time, also CCSDS ASCII time code A, with
+    // `decimals` digits of seconds and a Z suffix in UTC
+    pub fn to_iso(&self, scale: TimeScale, decimals: usize) -> String {
+        let (calendar, second) = self.rounded_calendar(scale, decimals);
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{}{}",
+            calendar.year,
+            calendar.month,
+            calendar.day,
+            calendar.hour,
+            calendar.minute,
+            second,
+            if scale == TimeScale::Utc { "Z" } else { "" }
+        )
+    }
+
+    // Calendar date alone, "YYYY-MM-DD"
+    pub fn to_date(&self, scale: TimeScale) -> String {
+        let calendar = self.calendar(scale);
+        format!("{:04}-{:02}-{:02}", calendar.year, calendar.month, calendar.day)
+    }
+
+    // ISO 8601 ordinal date and time, also CCSDS ASCII time code B
+    pub fn to_ordinal(&self, scale: TimeScale, decimals: usize) -> String {
+        let (calendar, second) = self.rounded_calendar(scale, decimals);
+        format!(
+            "{:04}-{:03}T{:02}:{:02}:{}{}",
+            calendar.year,
+            calendar.day_of_year,
+            calendar.hour,
+            calendar.minute,
+            second,
+            if scale == TimeScale::Utc { "Z" } else { "" }
+        )
+    }
+
+    // Round the time of day first so 59.9999 s does not print as 60
+    fn rounded_calendar(&self, scale: TimeScale, decimals: usize) -> (CalendarTime, String) {
+        let (mut day, seconds) = self.day_seconds(scale);
+        let factor = 10f64.powi(decimals as i32);
+        let mut seconds = (seconds * factor).round() / factor;
+        let length = if scale == TimeScale::Utc { utc_day_length(day) } else { SECONDS_PER_DAY };
+        if seconds >= length {
+            seconds -= length;
+            day += 1;
+        }
+        let calendar = calendar_from_day_seconds(day, seconds);
+        let width = if decimals > 0 { decimals + 3 } else { 2 };
+        (calendar, format!("{:0width$.decimals$}", calendar.second, width = width, decimals = decimals))
+    }
+
+    // CCSDS unsegmented time code: 4 octets of TAI seconds and 3 octets of
+    // binary fraction since 1958-01-01, P-field 0x1F (not included)
+    pub fn to_cuc(&self) -> Result<[u8; 7], CalculationError> {
+        // Whole seconds and the fraction separately: summed into one f64,
+        // 2e9 s would leave no room for the 60 ns fine time
+        let whole = self.seconds.floor();
+        let mut coarse = (self.day - CCSDS_EPOCH_MJD) * SECONDS_PER_DAY as i64 + whole as i64;
+        let mut fine = ((self.seconds - whole) * 16777216.0).round() as u64;
+        if fine == 1 << 24 {
+            coarse += 1;
+            fine = 0;
+        }
+        let coarse = u32::try_from(coarse).map_err(|_| CalculationError::OutOfRange)?.to_be_bytes();
+        let fine = (fine as u32).to_be_bytes();
+        Ok([coarse[0], coarse[1], coarse[2], coarse[3], fine[1], fine[2], fine[3]])
+    }
+
+    pub fn from_cuc(code: &[u8; 7]) -> Self {
+        let coarse = u32::from_be_bytes([code[0], code[1], code[2], code[3]]) as f64;
+        let fine = u32::from_be_bytes([0, code[4], code[5], code[6]]) as f64 / 16777216.0;
+        let days = (coarse / SECONDS_PER_DAY).floor();
+        Epoch::from_tai_parts(CCSDS_EPOCH_MJD + days as i64, coarse - days * SECONDS_PER_DAY + fine)
+    }
+
+    // CCSDS day segmented time code in UTC: 2 octets of days since
+    // 1958-01-01, 4 octets of milliseconds of the day and 2 octets of
+    // microseconds of the millisecond, P-field 0x41 (not included)
+    pub fn to_cds(&self) -> Result<[u8; 8], CalculationError> {
+        let (day, seconds) = self.day_seconds(TimeScale::Utc);
+        let micros = (seconds * 1e6).round() as u64;
+        let length = utc_day_length(day) as u64 * 1_000_000;
+        let (day, micros) = if micros >= length { (day + 1, micros - length) } else { (day, micros) };
+        let days = u16::try_from(day - CCSDS_EPOCH_MJD).map_err(|_| CalculationError::OutOfRange)?.to_be_bytes();
+        let millis = ((micros / 1000) as u32).to_be_bytes();
+        let sub = ((micros % 1000) as u16).to_be_bytes();
+        Ok([days[0], days[1], millis[0], millis[1], millis[2], millis[3], sub[0], sub[1]])
+    }
+
+    pub fn from_cds(code: &[u8; 8]) -> Self {
+        let days = u16::from_be_bytes([code[0], code[1]]) as i64;
+        let millis = u32::from_be_bytes([code[2], code[3], code[4], code[5]]) as f64;
+        let micros = u16::from_be_bytes([code[6], code[7]]) as f64;
+        Epoch::from_day_seconds(CCSDS_EPOCH_MJD + days, millis / 1e3 + micros / 1e6, TimeScale::Utc)
+    }
+}
+
+fn calendar_from_day_seconds(day: i64, seconds: f64) -> CalendarTime {
+    let (year, month, day_of_month) = civil_from_days(day - UNIX_EPOCH_MJD);
+    let day_of_year = (day - UNIX_EPOCH_MJD - days_from_civil(year, 1, 1) + 1) as u32;
+    // Keep a leap second in the last minute of the day
+    let whole = seconds.min(SECONDS_PER_DAY - 1.0).floor();
+    let hour = (whole / 3600.0) as u32;
+    let minute = (whole / 60.0) as u32 % 60;
+    let second = seconds - (hour * 3600 + minute * 60) as f64;
+    CalendarTime { year, month, day: day_of_month, day_of_year, hour, minute, second }
+}
+
+impl Add<f64> for Epoch {
+    type Output = Epoch;
+    // Seconds of TAI, which runs uniformly
+    fn add(self, seconds: f64) -> Epoch {
+        Epoch::from_tai_parts(self.day, self.seconds + seconds)
+    }
+}
+
+impl Sub<f64> for Epoch {
+    type Output = Epoch;
+    fn sub(self, seconds: f64) -> Epoch {
+        self + -seconds
+    }
+}
+
+impl Sub for Epoch {
+    type Output = f64;
+    // Elapsed seconds between two epochs
+    fn sub(self, other: Epoch) -> f64 {
+        (self.day - other.day) as f64 * SECONDS_PER_DAY + (self.seconds - other.seconds)
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_iso(TimeScale::Utc, 3))
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, CalculationError> {
+    if text.is_empty() || text.starts_with(['+', '-']) {
+        return Err(CalculationError::InvalidInput);
+    }
+    text.parse().map_err(|_| CalculationError::InvalidInput)
+}
+
+// Parse an epoch written as
+//
+// - an ISO 8601 / CCSDS A calendar date "2024-03-01T12:34:56.789Z",
+// - an ISO 8601 / CCSDS B ordinal date "2024-061T12:34:56.789",
+// - a date alone, with an optional fractional day ("2024-03-01.25"),
+// - a Julian or Modified Julian Date ("JD 2460371.0", "MJD 60370.5"),
+//
+// with a space instead of the T allowed. A trailing Z or time scale name
+// ("2024-03-01T12:00:00 TT") sets the scale, which is UTC otherwise.
+pub fn parse_epoch(text: &str) -> Result<Epoch, CalculationError> {
+    let mut text = text.trim();
+    let mut scale = TimeScale::Utc;
+    if let Some((rest, last)) = text.rsplit_once(' ')
+        && let Ok(parsed) = parse_time_scale(last)
+    {
+        scale = parsed;
+        text = rest.trim();
+    }
+    if let Some(rest) = text.strip_suffix(['Z', 'z']) {
+        text = rest;
+    }
+
+    let upper = text.to_ascii_uppercase();
+    if let Some(value) = upper.strip_prefix("MJD") {
+        return Ok(Epoch::from_mjd(value.trim().parse().map_err(|_| CalculationError::InvalidInput)?, scale));
+    }
+    if let Some(value) = upper.strip_prefix("JD") {
+        return Ok(Epoch::from_julian_date(value.trim().parse().map_err(|_| CalculationError::InvalidInput)?, scale));
+    }
+
+    let (date, time) = match text.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (text, None),
+    };
+    let parts: Vec<&str> = date.split('-').collect();
+    let year: i64 = match parts.first() {
+        Some(year) if year.len() == 4 => parse_number(year)?,
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    let (hour, minute, second) = match time {
+        Some(time) => {
+            let fields: Vec<&str> = time.split(':').collect();
+            if !(2..=3).contains(&fields.len()) || fields[0].len() != 2 || fields[1].len() != 2 {
+                return Err(CalculationError::InvalidInput);
+            }
+            let second = if fields.len() == 3 { parse_number(fields[2])? } else { 0.0 };
+            (parse_number(fields[0])?, parse_number(fields[1])?, second)
+        }
+        None => (0, 0, 0.0),
+    };
+
+    let (month, day, fraction) = match parts.len() {
+        // Ordinal date
+        2 if parts[1].split('.').next().map(str::len) == Some(3) => {
+            let day_of_year: f64 = parse_number(parts[1])?;
+            let days = if is_leap_year(year) { 366.0 } else { 365.0 };
+            if !(1.0..days + 1.0).contains(&day_of_year) {
+                return Err(CalculationError::OutOfRange);
+            }
+            let (_, month, day) = civil_from_days(days_from_civil(year, 1, 1) + day_of_year as i64 - 1);
+            (month, day, day_of_year.fract())
+        }
+        3 if parts[1].len() == 2 => {
+            let day: f64 = parse_number(parts[2])?;
+            (parse_number(parts[1])?, day.floor() as u32, day.fract())
+        }
+        _ => return Err(CalculationError::InvalidInput),
+    };
+    // A fractional day and a time of day cannot both be given
+    if time.is_some() && fraction != 0.0 {
+        return Err(CalculationError::InvalidInput);
+    }
+    Ok(Epoch::from_calendar(year, month, day, hour, minute, second, scale)? + fraction * SECONDS_PER_DAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> Epoch {
+        parse_epoch(text).unwrap()
+    }
+
+    #[test]
+    fn leap_second_at_the_end_of_2016() {
+        let before = utc("2016-12-31T23:59:59Z");
+        let leap = utc("2016-12-31T23:59:60.5Z");
+        let after = utc("2017-01-01T00:00:00Z");
+        assert!((leap - before - 1.5).abs() < 1e-9);
+        assert!((after - before - 2.0).abs() < 1e-9);
+        assert_eq!(leap.to_iso(TimeScale::Utc, 1), "2016-12-31T23:59:60.5Z");
+        assert_eq!(leap.to_iso(TimeScale::Tai, 1), "2017-01-01T00:00:36.5");
+        assert_eq!(leap.calendar(TimeScale::Utc).second, 60.5);
+        assert_eq!(tai_minus_utc(before.mjd(TimeScale::Utc)), 36.0);
+        assert_eq!(tai_minus_utc(after.mjd(TimeScale::Utc)), 37.0);
+        // Rounding up inside the leap second stays in it; past it rolls over
+        assert_eq!(utc("2016-12-31T23:59:60.96Z").to_iso(TimeScale::Utc, 1), "2017-01-01T00:00:00.0Z");
+        assert_eq!(utc("2016-12-31T23:59:59.96Z").to_iso(TimeScale::Utc, 1), "2016-12-31T23:59:60.0Z");
+    }
+
+    #[test]
+    fn no_second_sixty_on_ordinary_days() {
+        assert_eq!(parse_epoch("2017-12-31T23:59:60Z"), Err(CalculationError::OutOfRange));
+        assert_eq!(parse_epoch("2016-12-31T23:58:60Z"), Err(CalculationError::OutOfRange));
+        // TAI has no leap seconds
+        assert_eq!(parse_epoch("2016-12-31T23:59:60 TAI"), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn rejects_impossible_dates() {
+        for text in ["2024-02-30", "2023-02-29", "2024-04-31", "2024-13-01", "2024-00-10", "2023-366", "2024-03-01T24:00"] {
+            assert_eq!(parse_epoch(text), Err(CalculationError::OutOfRange), "{text}");
+        }
+        for text in ["24-03-01", "2024-3-1", "2024-03-01.5T12:00", "2024-03-01T-1:00", "yesterday"] {
+            assert_eq!(parse_epoch(text), Err(CalculationError::InvalidInput), "{text}");
+        }
+        assert!(parse_epoch("2024-02-29").is_ok());
+    }
+
+    #[test]
+    fn parses_every_written_form() {
+        let noon = utc("2024-03-01T12:00:00Z");
+        for text in ["2024-03-01 12:00", "2024-061T12:00:00", "2024-03-01.5", "2024-061.5", "MJD 60370.5", "JD 2460371.0"] {
+            assert!((utc(text) - noon).abs() < 1e-6, "{text}");
+        }
+        let tt = utc("2024-03-01T12:00:00 TT");
+        assert!((noon - tt - (37.0 + TT_MINUS_TAI)).abs() < 1e-9);
+        assert_eq!(noon.to_ordinal(TimeScale::Utc, 0), "2024-061T12:00:00Z");
+    }
+
+    #[test]
+    fn j2000_is_noon_tt() {
+        let j2000 = utc("2000-01-01T11:58:55.816Z");
+        assert!((j2000.julian_date(TimeScale::Tt) - J2000_JD).abs() * SECONDS_PER_DAY < 1e-5);
+        assert!(j2000.tdb_minus_tt().abs() < 1.7e-3);
+        assert_eq!(utc("2000-01-01T00:00:00Z").unix_seconds(), 946684800.0);
+    }
+
+    #[test]
+    fn gps_weeks() {
+        assert_eq!(Epoch::from_gps_week(0, 0.0).to_iso(TimeScale::Utc, 0), "1980-01-06T00:00:00Z");
+        // GPS runs 18 s ahead of UTC from 2017
+        let (week, seconds) = utc("2017-01-01T00:00:00Z").gps_week();
+        assert_eq!(week, 1930);
+        assert!((seconds - 18.0).abs() < 1e-9);
+        let back = Epoch::from_gps_week(week, seconds);
+        assert!((back - utc("2017-01-01T00:00:00Z")).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cuc_round_trips() {
+        assert_eq!(Epoch::from_day_seconds(CCSDS_EPOCH_MJD, 0.0, TimeScale::Tai).to_cuc(), Ok([0; 7]));
+        for text in ["2016-12-31T23:59:60.25Z", "2024-03-01T12:34:56.789Z", "1970-01-01T00:00:00Z"] {
+            let epoch = utc(text);
+            let code = epoch.to_cuc().unwrap();
+            assert!((Epoch::from_cuc(&code) - epoch).abs() <= 0.5 / 16777216.0 + 1e-9, "{text}");
+        }
+        // Half a second is exactly 0x800000 of the fine time
+        let half = Epoch::from_day_seconds(CCSDS_EPOCH_MJD + 1, 0.5, TimeScale::Tai).to_cuc().unwrap();
+        assert_eq!(half, [0, 1, 0x51, 0x80, 0x80, 0, 0]);
+        assert_eq!(utc("1957-12-31T00:00:00Z").to_cuc(), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn cds_round_trips_through_the_leap_second() {
+        let leap = utc("2016-12-31T23:59:60.123456Z");
+        let code = leap.to_cds().unwrap();
+        let days = u16::from_be_bytes([code[0], code[1]]) as i64;
+        let millis = u32::from_be_bytes([code[2], code[3], code[4], code[5]]);
+        assert_eq!(days, 57753 - CCSDS_EPOCH_MJD);
+        assert_eq!(millis, 86_400_123);
+        assert_eq!(u16::from_be_bytes([code[6], code[7]]), 456);
+        assert!((Epoch::from_cds(&code) - leap).abs() < 1e-6);
+
+        let ordinary = utc("2024-03-01T12:34:56.789012Z");
+        assert!((Epoch::from_cds(&ordinary.to_cds().unwrap()) - ordinary).abs() < 1e-6);
+        assert_eq!(utc("1957-12-31T00:00:00Z").to_cds(), Err(CalculationError::OutOfRange));
+    }
+
+    #[test]
+    fn every_scale_round_trips() {
+        let epoch = utc("2024-03-01T12:34:56.789Z");
+        for scale in TimeScale::ALL {
+            let (day, seconds) = epoch.day_seconds(scale);
+            assert!((Epoch::from_day_seconds(day, seconds, scale) - epoch).abs() < 1e-9, "{scale}");
+            assert_eq!(parse_time_scale(&scale.to_string()), Ok(scale));
+        }
+    }
+}
@@ -0,0 +1,347 @@
+// Two-Line Element sets
+//
+// Parses the fixed-column NORAD format, with or without a title line:
+//
+//     ISS (ZARYA)
+//     1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+//     2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+//
+// Every line is checked against its modulo-10 checksum and every field is
+// validated, so a bad element set is reported with the line and columns of
+// the offending field instead of propagating garbage.
+use crate::time::{Epoch, TimeScale};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const LINE_LENGTH: usize = 69;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tle {
+    pub name: Option<String>,
+    pub catalog_number: u32,
+    pub classification: char,
+    pub international_designator: String,
+    pub epoch: f64,            // Julian date, UTC
+    pub mean_motion_dot: f64,  // rev/day^2, first derivative of mean motion / 2
+    pub mean_motion_ddot: f64, // rev/day^3, second derivative of mean motion / 6
+    pub bstar: f64,            // 1/earth radii, drag term
+    pub ephemeris_type: u8,
+    pub element_set_number: u32,
+    pub inclination: f64,   // rad
+    pub raan: f64,          // rad
+    pub eccentricity: f64,
+    pub arg_perigee: f64,   // rad
+    pub mean_anomaly: f64,  // rad
+    pub mean_motion: f64,   // rev/day
+    pub revolution_number: u32,
+}
+
+impl fmt::Display for Tle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "{}", name)?;
+        }
+        writeln!(f, "Catalog number:  {:05}{} ({})", self.catalog_number, self.classification, self.international_designator)?;
+        writeln!(f, "Epoch:           JD {:.8}", self.epoch)?;
+        writeln!(f, "Inclination:     {:.4} deg", self.inclination.to_degrees())?;
+        writeln!(f, "RAAN:            {:.4} deg", self.raan.to_degrees())?;
+        writeln!(f, "Eccentricity:    {:.7}", self.eccentricity)?;
+        writeln!(f, "Arg of perigee:  {:.4} deg", self.arg_perigee.to_degrees())?;
+        writeln!(f, "Mean anomaly:    {:.4} deg", self.mean_anomaly.to_degrees())?;
+        writeln!(f, "Mean motion:     {:.8} rev/day", self.mean_motion)?;
+        write!(f, "B*:              {:.5e} 1/ER", self.bstar)
+    }
+}
+
+// Errors raised while reading element sets. `line` is the line number in
+// the parsed text and `columns` the 1-based column range of the field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TleError {
+    Io(String),
+    MissingLine { line: usize },
+    LineLength { line: usize, length: usize },
+    LineNumber { line: usize, expected: char },
+    Checksum { line: usize, computed: u32, found: char },
+    Field { line: usize, field: &'static str, columns: (usize, usize), text: String },
+    CatalogMismatch { line: usize },
+}
+
+impl fmt::Display for TleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TleError::Io(message) => write!(f, "could not read TLE file: {}", message),
+            TleError::MissingLine { line } => write!(f, "line {}: element set is missing its second line", line),
+            TleError::LineLength { line, length } => {
+                write!(f, "line {}: expected {} characters, found {}", line, LINE_LENGTH, length)
+            }
+            TleError::LineNumber { line, expected } => write!(f, "line {}: expected line number '{}'", line, expected),
+            TleError::Checksum { line, computed, found } => {
+                write!(f, "line {}: checksum is {} but the line ends in '{}'", line, computed, found)
+            }
+            TleError::Field { line, field, columns, text } => {
+                write!(f, "line {}, columns {}-{}: invalid {} '{}'", line, columns.0, columns.1, field, text)
+            }
+            TleError::CatalogMismatch { line } => {
+                write!(f, "line {}: catalog number differs from the first line", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TleError {}
+
+// Modulo-10 checksum of the first 68 columns: digits count their value,
+// minus signs count one, everything else zero
+pub fn checksum(line: &str) -> u32 {
+    line.chars()
+        .take(LINE_LENGTH - 1)
+        .map(|c| match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            '-' => 1,
+            _ => 0,
+        })
+        .sum::<u32>()
+        % 10
+}
+
+// One line of an element set with its number in the parsed text
+struct Line<'a> {
+    text: &'a str,
+    number: usize,
+}
+
+impl Line<'_> {
+    fn field(&self, field: &'static str, first: usize, last: usize) -> Result<&str, TleError> {
+        self.text.get(first - 1..last).map(str::trim).ok_or_else(|| self.error(field, first, last))
+    }
+
+    fn error(&self, field: &'static str, first: usize, last: usize) -> TleError {
+        TleError::Field {
+            line: self.number,
+            field,
+            columns: (first, last),
+            text: self.text.get(first - 1..last).unwrap_or("").to_string(),
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&self, field: &'static str, first: usize, last: usize) -> Result<T, TleError> {
+        self.field(field, first, last)?.parse().map_err(|_| self.error(field, first, last))
+    }
+
+    // Angle in degrees within [0, max]
+    fn angle(&self, field: &'static str, first: usize, last: usize, max: f64) -> Result<f64, TleError> {
+        let value: f64 = self.parse(field, first, last)?;
+        if !(0.0..=max).contains(&value) {
+            return Err(self.error(field, first, last));
+        }
+        Ok(value.to_radians())
+    }
+
+    // Number with an assumed leading decimal point and a power of ten,
+    // e.g. " 12345-3" = 0.12345e-3 and "-11606-4" = -0.11606e-4
+    fn exponential(&self, field: &'static str, first: usize, last: usize) -> Result<f64, TleError> {
+        let text = self.field(field, first, last)?;
+        let invalid = || self.error(field, first, last);
+        if text.len() < 3 {
+            return Err(invalid());
+        }
+        let (mantissa, exponent) = text.split_at(text.len() - 2);
+        let (sign, digits) = match mantissa.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let mantissa: f64 = format!("0.{}", digits).parse().map_err(|_| invalid())?;
+        let exponent: i32 = exponent.trim_start_matches('+').parse().map_err(|_| invalid())?;
+        Ok(sign * mantissa * 10.0_f64.powi(exponent))
+    }
+
+    fn check(&self, expected: char) -> Result<(), TleError> {
+        let length = self.text.chars().count();
+        if length != LINE_LENGTH || !self.text.is_ascii() {
+            return Err(TleError::LineLength { line: self.number, length });
+        }
+        if !self.text.starts_with(expected) || self.text.as_bytes()[1] != b' ' {
+            return Err(TleError::LineNumber { line: self.number, expected });
+        }
+        let found = self.text.as_bytes()[LINE_LENGTH - 1] as char;
+        let computed = checksum(self.text);
+        if found.to_digit(10) != Some(computed) {
+            return Err(TleError::Checksum { line: self.number, computed, found });
+        }
+        Ok(())
+    }
+}
+
+// Parse one element set from its two data lines
+pub fn parse_tle(name: Option<&str>, line1: &str, line2: &str) -> Result<Tle, TleError> {
+    parse_lines(name, Line { text: line1.trim_end(), number: 1 }, Line { text: line2.trim_end(), number: 2 })
+}
+
+fn parse_lines(name: Option<&str>, line1: Line, line2: Line) -> Result<Tle, TleError> {
+    line1.check('1')?;
+    line2.check('2')?;
+
+    let catalog_number: u32 = line1.parse("catalog number", 3, 7)?;
+    let classification = line1.text.as_bytes()[7] as char;
+    if !matches!(classification, 'U' | 'C' | 'S' | ' ') {
+        return Err(line1.error("classification", 8, 8));
+    }
+    let international_designator = line1.field("international designator", 10, 17)?.to_string();
+
+    let year: i32 = line1.parse("epoch year", 19, 20)?;
+    let day: f64 = line1.parse("epoch day", 21, 32)?;
+    // Two-digit years 57-99 are 1957-1999, 00-56 are 2000-2056
+    let year = if year < 57 { 2000 + year } else { 1900 + year };
+    let epoch = Epoch::from_day_of_year(year.into(), day, TimeScale::Utc)
+        .map_err(|_| line1.error("epoch day", 21, 32))?
+        .julian_date(TimeScale::Utc);
+
+    let mean_motion_dot: f64 = line1.parse("mean motion derivative", 34, 43)?;
+    let mean_motion_ddot = line1.exponential("mean motion second derivative", 45, 52)?;
+    let bstar = line1.exponential("B* drag term", 54, 61)?;
+    let ephemeris_type = match line1.field("ephemeris type", 63, 63)? {
+        "" => 0,
+        text => text.parse().map_err(|_| line1.error("ephemeris type", 63, 63))?,
+    };
+    let element_set_number = match line1.field("element set number", 65, 68)? {
+        "" => 0,
+        text => text.parse().map_err(|_| line1.error("element set number", 65, 68))?,
+    };
+
+    let second_catalog_number: u32 = line2.parse("catalog number", 3, 7)?;
+    if second_catalog_number != catalog_number {
+        return Err(TleError::CatalogMismatch { line: line2.number });
+    }
+    let inclination = line2.angle("inclination", 9, 16, 180.0)?;
+    let raan = line2.angle("right ascension of the ascending node", 18, 25, 360.0)?;
+    let eccentricity_digits = line2.field("eccentricity", 27, 33)?;
+    if eccentricity_digits.is_empty() || !eccentricity_digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(line2.error("eccentricity", 27, 33));
+    }
+    let eccentricity: f64 = format!("0.{}", eccentricity_digits).parse().map_err(|_| line2.error("eccentricity", 27, 33))?;
+    let arg_perigee = line2.angle("argument of perigee", 35, 42, 360.0)?;
+    let mean_anomaly = line2.angle("mean anomaly", 44, 51, 360.0)?;
+    let mean_motion: f64 = line2.parse("mean motion", 53, 63)?;
+    if mean_motion <= 0.0 {
+        return Err(line2.error("mean motion", 53, 63));
+    }
+    let revolution_number = match line2.field("revolution number", 64, 68)? {
+        "" => 0,
+        text => text.parse().map_err(|_| line2.error("revolution number", 64, 68))?,
+    };
+
+    Ok(Tle {
+        name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        catalog_number,
+        classification,
+        international_designator,
+        epoch,
+        mean_motion_dot,
+        mean_motion_ddot,
+        bstar,
+        ephemeris_type,
+        element_set_number,
+        inclination,
+        raan,
+        eccentricity,
+        arg_perigee,
+        mean_anomaly,
+        mean_motion,
+        revolution_number,
+    })
+}
+
+// Parse every element set in a text, in two-line or three-line (title
+// line first) format. Blank lines are ignored.
+pub fn parse_tles(text: &str) -> Result<Vec<Tle>, TleError> {
+    let lines: Vec<(usize, &str)> =
+        text.lines().enumerate().map(|(i, l)| (i + 1, l.trim_end())).filter(|(_, l)| !l.trim().is_empty()).collect();
+    let mut tles = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let (number, text) = lines[i];
+        let name = if text.starts_with("1 ") { None } else { Some(text.strip_prefix("0 ").unwrap_or(text)) };
+        let first = if name.is_some() { i + 1 } else { i };
+        if first + 1 >= lines.len() {
+            return Err(TleError::MissingLine { line: number });
+        }
+        let line1 = Line { text: lines[first].1, number: lines[first].0 };
+        let line2 = Line { text: lines[first + 1].1, number: lines[first + 1].0 };
+        tles.push(parse_lines(name, line1, line2)?);
+        i = first + 2;
+    }
+    Ok(tles)
+}
+
+pub fn load_tle_file<P: AsRef<Path>>(path: P) -> Result<Vec<Tle>, TleError> {
+    let text = fs::read_to_string(path).map_err(|e| TleError::Io(e.to_string()))?;
+    parse_tles(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+    const LINE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+    // Replace the text starting at 1-based `column` and fix the checksum
+    fn edit(line: &str, column: usize, text: &str) -> String {
+        let mut edited = line.to_string();
+        edited.replace_range(column - 1..column - 1 + text.len(), text);
+        let body = &edited[..LINE_LENGTH - 1];
+        format!("{}{}", body, checksum(body))
+    }
+
+    #[test]
+    fn parses_fields() {
+        let tle = parse_tle(Some("VANGUARD 1"), LINE1, LINE2).unwrap();
+        assert_eq!(tle.name.as_deref(), Some("VANGUARD 1"));
+        assert_eq!(tle.catalog_number, 5);
+        assert_eq!(tle.international_designator, "58002B");
+        assert!((tle.bstar - 0.28098e-4).abs() < 1e-15);
+        assert!((tle.eccentricity - 0.1859667).abs() < 1e-12);
+        assert!((tle.inclination.to_degrees() - 34.2682).abs() < 1e-12);
+        assert!((tle.mean_motion - 10.82419157).abs() < 1e-12);
+        assert_eq!(tle.revolution_number, 41366);
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let line1 = format!("{}4", &LINE1[..LINE_LENGTH - 1]);
+        assert_eq!(parse_tle(None, &line1, LINE2), Err(TleError::Checksum { line: 1, computed: 3, found: '4' }));
+    }
+
+    #[test]
+    fn reports_field_columns() {
+        let line2 = edit(LINE2, 14, "X");
+        let error = parse_tle(None, LINE1, &line2).unwrap_err();
+        assert_eq!(
+            error,
+            TleError::Field { line: 2, field: "inclination", columns: (9, 16), text: " 34.2X82".to_string() }
+        );
+        assert_eq!(error.to_string(), "line 2, columns 9-16: invalid inclination ' 34.2X82'");
+    }
+
+    #[test]
+    fn two_digit_years_roll_over_at_57() {
+        // Julian dates of January 0.0 of each year plus the day of year
+        let epoch = |year: &str| parse_tle(None, &edit(LINE1, 19, year), LINE2).unwrap().epoch;
+        assert!((epoch("00") - (2451543.5 + 179.78495062)).abs() < 1e-8);
+        assert!((epoch("56") - (2471997.5 + 179.78495062)).abs() < 1e-8);
+        assert!((epoch("57") - (2435838.5 + 179.78495062)).abs() < 1e-8);
+        assert!((epoch("99") - (2451178.5 + 179.78495062)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn epoch_day_must_fall_in_its_year() {
+        let parse = |day: &str| parse_tle(None, &edit(LINE1, 19, day), LINE2).map(|tle| tle.epoch);
+        assert!(parse("00366.50000000").is_ok());
+        assert!(matches!(parse("01366.50000000"), Err(TleError::Field { field: "epoch day", .. })));
+        assert!(matches!(parse("01000.50000000"), Err(TleError::Field { field: "epoch day", .. })));
+    }
+}
@@ -0,0 +1,428 @@
+// Dimensioned quantities
+//
+// Each physical quantity is its own type holding an SI value, built and read
+// through named units like `std::time::Duration`:
+//
+//     let radius = Length::from_kilometers(6678.0);
+//     let meters = radius.as_meters(); // 6_678_000.0
+//
+// Quantities of one kind add and subtract, scale by plain numbers, and only
+// combine with other kinds where the physics does (length / time = velocity,
+// power * time = energy, ...). Passing a mass where a length is expected, or
+// kilometres where metres were meant, no longer compiles.
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+// Arithmetic shared by every quantity: same-kind sums and differences,
+// scaling by f64 and the dimensionless ratio of two values of one kind
+macro_rules! quantity {
+    ($name:ident, $symbol:literal) => {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+        pub struct $name(f64);
+
+        impl $name {
+            pub const ZERO: $name = $name(0.0);
+
+            pub fn abs(self) -> Self {
+                $name(self.0.abs())
+            }
+
+            pub fn is_finite(self) -> bool {
+                self.0.is_finite()
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+            fn add(self, rhs: $name) -> $name {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+            fn sub(self, rhs: $name) -> $name {
+                $name(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: $name) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = $name;
+            fn mul(self, rhs: f64) -> $name {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$name> for f64 {
+            type Output = $name;
+            fn mul(self, rhs: $name) -> $name {
+                $name(self * rhs.0)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = $name;
+            fn div(self, rhs: f64) -> $name {
+                $name(self.0 / rhs)
+            }
+        }
+
+        impl Div for $name {
+            type Output = f64;
+            fn div(self, rhs: $name) -> f64 {
+                self.0 / rhs.0
+            }
+        }
+
+        impl std::iter::Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                $name(iter.map(|q| q.0).sum())
+            }
+        }
+
+        // SI value and unit, honouring the requested precision
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", $symbol)
+            }
+        }
+    };
+}
+
+// `$lhs * $rhs = $out`, in both operand orders, and the matching divisions
+macro_rules! product {
+    ($lhs:ident * $rhs:ident = $out:ident) => {
+        impl Mul<$rhs> for $lhs {
+            type Output = $out;
+            fn mul(self, rhs: $rhs) -> $out {
+                $out(self.0 * rhs.0)
+            }
+        }
+
+        impl Mul<$lhs> for $rhs {
+            type Output = $out;
+            fn mul(self, rhs: $lhs) -> $out {
+                $out(self.0 * rhs.0)
+            }
+        }
+
+        impl Div<$rhs> for $out {
+            type Output = $lhs;
+            fn div(self, rhs: $rhs) -> $lhs {
+                $lhs(self.0 / rhs.0)
+            }
+        }
+
+        impl Div<$lhs> for $out {
+            type Output = $rhs;
+            fn div(self, rhs: $lhs) -> $rhs {
+                $rhs(self.0 / rhs.0)
+            }
+        }
+    };
+}
+
+quantity!(Length, "m");
+quantity!(Velocity, "m/s");
+quantity!(Mass, "kg");
+quantity!(Time, "s");
+quantity!(Energy, "J");
+quantity!(Power, "W");
+quantity!(Frequency, "Hz");
+quantity!(Angle, "rad");
+quantity!(Force, "N");
+
+product!(Velocity * Time = Length);
+product!(Power * Time = Energy);
+product!(Force * Length = Energy);
+
+impl Length {
+    pub fn from_meters(meters: f64) -> Self {
+        Length(meters)
+    }
+
+    pub fn from_kilometers(kilometers: f64) -> Self {
+        Length(kilometers * 1000.0)
+    }
+
+    pub fn as_meters(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_kilometers(self) -> f64 {
+        self.0 / 1000.0
+    }
+}
+
+impl Velocity {
+    pub fn from_meters_per_second(speed: f64) -> Self {
+        Velocity(speed)
+    }
+
+    pub fn from_kilometers_per_second(speed: f64) -> Self {
+        Velocity(speed * 1000.0)
+    }
+
+    pub fn as_meters_per_second(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_kilometers_per_second(self) -> f64 {
+        self.0 / 1000.0
+    }
+}
+
+impl Mass {
+    pub fn from_kilograms(kilograms: f64) -> Self {
+        Mass(kilograms)
+    }
+
+    pub fn from_tonnes(tonnes: f64) -> Self {
+        Mass(tonnes * 1000.0)
+    }
+
+    pub fn as_kilograms(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_tonnes(self) -> f64 {
+        self.0 / 1000.0
+    }
+}
+
+impl Time {
+    pub fn from_seconds(seconds: f64) -> Self {
+        Time(seconds)
+    }
+
+    pub fn from_minutes(minutes: f64) -> Self {
+        Time(minutes * 60.0)
+    }
+
+    pub fn from_hours(hours: f64) -> Self {
+        Time(hours * 3600.0)
+    }
+
+    pub fn from_days(days: f64) -> Self {
+        Time(days * 86400.0)
+    }
+
+    pub fn as_seconds(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_minutes(self) -> f64 {
+        self.0 / 60.0
+    }
+
+    pub fn as_hours(self) -> f64 {
+        self.0 / 3600.0
+    }
+
+    pub fn as_days(self) -> f64 {
+        self.0 / 86400.0
+    }
+}
+
+impl Energy {
+    pub fn from_joules(joules: f64) -> Self {
+        Energy(joules)
+    }
+
+    pub fn from_watt_hours(watt_hours: f64) -> Self {
+        Energy(watt_hours * 3600.0)
+    }
+
+    pub fn as_joules(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_watt_hours(self) -> f64 {
+        self.0 / 3600.0
+    }
+}
+
+impl Power {
+    pub fn from_watts(watts: f64) -> Self {
+        Power(watts)
+    }
+
+    // Power level in decibels relative to 1 W
+    pub fn from_dbw(dbw: f64) -> Self {
+        Power(10.0_f64.powf(dbw / 10.0))
+    }
+
+    // Power level in decibels relative to 1 mW
+    pub fn from_dbm(dbm: f64) -> Self {
+        Power::from_dbw(dbm - 30.0)
+    }
+
+    pub fn as_watts(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_dbw(self) -> f64 {
+        10.0 * self.0.log10()
+    }
+
+    pub fn as_dbm(self) -> f64 {
+        self.as_dbw() + 30.0
+    }
+}
+
+impl Frequency {
+    pub fn from_hertz(hertz: f64) -> Self {
+        Frequency(hertz)
+    }
+
+    pub fn from_megahertz(megahertz: f64) -> Self {
+        Frequency(megahertz * 1e6)
+    }
+
+    pub fn from_gigahertz(gigahertz: f64) -> Self {
+        Frequency(gigahertz * 1e9)
+    }
+
+    pub fn as_hertz(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_megahertz(self) -> f64 {
+        self.0 / 1e6
+    }
+
+    pub fn as_gigahertz(self) -> f64 {
+        self.0 / 1e9
+    }
+
+    // Duration of one cycle
+    pub fn period(self) -> Time {
+        Time(1.0 / self.0)
+    }
+}
+
+impl Angle {
+    pub fn from_radians(radians: f64) -> Self {
+        Angle(radians)
+    }
+
+    pub fn from_degrees(degrees: f64) -> Self {
+        Angle(degrees.to_radians())
+    }
+
+    pub fn as_radians(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_degrees(self) -> f64 {
+        self.0.to_degrees()
+    }
+
+    pub fn sin(self) -> f64 {
+        self.0.sin()
+    }
+
+    pub fn cos(self) -> f64 {
+        self.0.cos()
+    }
+}
+
+impl Force {
+    pub fn from_newtons(newtons: f64) -> Self {
+        Force(newtons)
+    }
+
+    pub fn as_newtons(self) -> f64 {
+        self.0
+    }
+}
+
+// Dimensionless gain or loss in decibels. Adding gains multiplies the
+// underlying ratios, and a power level shifted by a gain stays a power.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Decibels(f64);
+
+impl Decibels {
+    pub const ZERO: Decibels = Decibels(0.0);
+
+    pub fn from_db(db: f64) -> Self {
+        Decibels(db)
+    }
+
+    pub fn from_ratio(ratio: f64) -> Self {
+        Decibels(10.0 * ratio.log10())
+    }
+
+    pub fn as_db(self) -> f64 {
+        self.0
+    }
+
+    pub fn as_ratio(self) -> f64 {
+        10.0_f64.powf(self.0 / 10.0)
+    }
+}
+
+impl Add for Decibels {
+    type Output = Decibels;
+    fn add(self, rhs: Decibels) -> Decibels {
+        Decibels(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Decibels {
+    type Output = Decibels;
+    fn sub(self, rhs: Decibels) -> Decibels {
+        Decibels(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Decibels {
+    type Output = Decibels;
+    fn neg(self) -> Decibels {
+        Decibels(-self.0)
+    }
+}
+
+// Adding decibels multiplies the linear power
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add<Decibels> for Power {
+    type Output = Power;
+    fn add(self, gain: Decibels) -> Power {
+        Power(self.0 * gain.as_ratio())
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub<Decibels> for Power {
+    type Output = Power;
+    fn sub(self, loss: Decibels) -> Power {
+        Power(self.0 / loss.as_ratio())
+    }
+}
+
+impl fmt::Display for Decibels {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        write!(f, " dB")
+    }
+}
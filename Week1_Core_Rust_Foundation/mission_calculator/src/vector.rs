@@ -0,0 +1,170 @@
+// Minimal 3D vector used for positions and velocities, and the 3x3 matrices
+// that rotate them between reference frames
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    pub const ZERO: Vector3 = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector3 { x, y, z }
+    }
+
+    pub fn dot(&self, other: Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Vector3) -> Vector3 {
+        Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    // Unit vector in the same direction, or None for the zero vector
+    pub fn normalized(&self) -> Option<Vector3> {
+        let n = self.norm();
+        if n == 0.0 { None } else { Some(*self / n) }
+    }
+
+    // Angle between two vectors in radians, in [0, pi]
+    pub fn angle_to(&self, other: Vector3) -> f64 {
+        self.cross(other).norm().atan2(self.dot(other))
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Vector3;
+    fn add(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Vector3 {
+    fn add_assign(&mut self, other: Vector3) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Vector3;
+    fn sub(self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl SubAssign for Vector3 {
+    fn sub_assign(&mut self, other: Vector3) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<f64> for Vector3 {
+    type Output = Vector3;
+    fn mul(self, k: f64) -> Vector3 {
+        Vector3::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl Mul<Vector3> for f64 {
+    type Output = Vector3;
+    fn mul(self, v: Vector3) -> Vector3 {
+        v * self
+    }
+}
+
+impl Div<f64> for Vector3 {
+    type Output = Vector3;
+    fn div(self, k: f64) -> Vector3 {
+        Vector3::new(self.x / k, self.y / k, self.z / k)
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Vector3;
+    fn neg(self) -> Vector3 {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl fmt::Display for Vector3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}, {}]", self.x, self.y, self.z)
+    }
+}
+
+// Row-major 3x3 matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3 {
+    pub rows: [Vector3; 3],
+}
+
+impl Matrix3 {
+    pub const IDENTITY: Matrix3 = Matrix3 {
+        rows: [
+            Vector3 { x: 1.0, y: 0.0, z: 0.0 },
+            Vector3 { x: 0.0, y: 1.0, z: 0.0 },
+            Vector3 { x: 0.0, y: 0.0, z: 1.0 },
+        ],
+    };
+
+    pub fn new(rows: [Vector3; 3]) -> Self {
+        Matrix3 { rows }
+    }
+
+    // Frame rotations by `angle` about the x, y and z axes: they give the
+    // coordinates of a fixed vector in axes turned by `angle` (Vallado's
+    // ROT1, ROT2 and ROT3)
+    pub fn rotation_x(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, c, s), Vector3::new(0.0, -s, c)])
+    }
+
+    pub fn rotation_y(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([Vector3::new(c, 0.0, -s), Vector3::new(0.0, 1.0, 0.0), Vector3::new(s, 0.0, c)])
+    }
+
+    pub fn rotation_z(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([Vector3::new(c, s, 0.0), Vector3::new(-s, c, 0.0), Vector3::new(0.0, 0.0, 1.0)])
+    }
+
+    pub fn column(&self, i: usize) -> Vector3 {
+        let pick = |v: &Vector3| [v.x, v.y, v.z][i];
+        Vector3::new(pick(&self.rows[0]), pick(&self.rows[1]), pick(&self.rows[2]))
+    }
+
+    // The inverse of a rotation
+    pub fn transpose(&self) -> Matrix3 {
+        Matrix3::new([self.column(0), self.column(1), self.column(2)])
+    }
+}
+
+impl Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+    fn mul(self, v: Vector3) -> Vector3 {
+        Vector3::new(self.rows[0].dot(v), self.rows[1].dot(v), self.rows[2].dot(v))
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Matrix3;
+    fn mul(self, other: Matrix3) -> Matrix3 {
+        let columns = [other.column(0), other.column(1), other.column(2)];
+        let row = |r: Vector3| Vector3::new(r.dot(columns[0]), r.dot(columns[1]), r.dot(columns[2]));
+        Matrix3::new([row(self.rows[0]), row(self.rows[1]), row(self.rows[2])])
+    }
+}
@@ -1,4 +1,5 @@
-use chrono::Local;
+use chrono::{Local, Utc};
+use mission_calculator::time::{Epoch, TimeScale};
 use sysinfo::System;
 use std::{thread, time::Duration};
 
@@ -64,6 +65,17 @@ fn display_current_time() {
     println!("Date: {}", Local::now().format("%Y-%m-%d"));
     println!("Time: {}", Local::now().format("%H:%M:%S"));
     println!("-------------------------");
+    
+    // Mission time in the scales used by the flight dynamics tools
+    let now = Utc::now();
+    let epoch = Epoch::from_unix(now.timestamp() as f64 + now.timestamp_subsec_micros() as f64 * 1e-6);
+    for scale in [TimeScale::Utc, TimeScale::Tai, TimeScale::Tt, TimeScale::Gps] {
+        println!("{:<4} {}", scale.to_string(), epoch.to_iso(scale, 3));
+    }
+    let (week, seconds) = epoch.gps_week();
+    println!("GPS week {}, {:.3} s", week, seconds);
+    println!("JD (UTC): {:.6}  MJD (UTC): {:.6}", epoch.julian_date(TimeScale::Utc), epoch.mjd(TimeScale::Utc));
+    println!("-------------------------");
     println!("Press Ctrl+C to exit.");
 }
 